@@ -1,6 +1,9 @@
 use crate::{
     commands::commands_fn,
-    diff::Diff,
+    diff::{
+        combined_hunks, detect_renames, matching_lines, unified_hunks, Diff, DiffAlgorithm, Hunk,
+        LineCompare, RENAME_THRESHOLD,
+    },
     file_manager::{
         self, get_commit, get_current_commit, get_current_repo, get_head, read_index,
         update_working_directory, visit_dirs, get_tags,
@@ -13,24 +16,29 @@ use crate::{
     objects::git_object::GitObject,
 };
 use crate::{
+    config::{normalize_key, Config, ConfigLevel},
     gitr_errors::GitrError,
+    i18n::tr,
     objects::{
         blob::{Blob, TreeEntry},
         commit::Commit,
         tag::Tag,
         tree::Tree,
     },
+    regex::Regex,
 };
-use flate2::write::ZlibEncoder;
+use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
 use flate2::Compression;
 
+use chrono::{Datelike, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Timelike};
 use sha1::{Digest, Sha1};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fs::{self},
-    io::{self, Read, Write},
+    io::{IsTerminal, Read, Write},
     net::TcpStream,
-    path::Path, process::{Command, Stdio},
+    path::Path,
+    process::{Command, Stdio},
 };
 
 /***************************
@@ -93,7 +101,7 @@ pub fn get_object_hash(
     write: bool,
 ) -> Result<String, GitrError> {
     *file_path = file_manager::get_current_repo(cliente.clone())?.to_string() + "/" + file_path;
-    let raw_data = file_manager::read_file(file_path.to_string())?;
+    let raw_data = read_worktree_file(file_path, cliente.clone())?;
     let blob = Blob::new(raw_data)?;
     let res: String = blob.get_hash();
     if write {
@@ -127,7 +135,7 @@ pub fn get_object_properties(
 //Output the contents or other properties such as size, type or delta information of an object
 pub fn _cat_file(flags: Vec<String>, cliente: String) -> Result<String, GitrError> {
     let (object_hash, res_output, size, object_type) =
-        get_object_properties(flags.clone(), cliente)?;
+        get_object_properties(flags.clone(), cliente.clone())?;
     let data_requested = &flags[0];
     if data_requested == "-t" {
         return Ok(object_type);
@@ -145,7 +153,7 @@ pub fn _cat_file(flags: Vec<String>, cliente: String) -> Result<String, GitrErro
         };
         match object_type.as_str() {
             "blob" => Ok(raw_data.to_string()),
-            "tree" => Ok(get_tree_data(raw_data)),
+            "tree" => get_tree_data(raw_data, cliente),
             "commit" => Ok(raw_data.to_string()),
             "tag" => Ok(raw_data.to_string()),
             _ => Err(GitrError::FileReadError(object_hash.to_string())),
@@ -165,33 +173,52 @@ pub fn print_blob_data(raw_data: &str) {
     println!("{}", raw_data);
 }
 
-// receives the raw data and returns the tree data
-pub fn get_tree_data(raw_data: &str) -> String {
-    let files = raw_data.split('\n').collect::<Vec<&str>>();
+// receives the raw data and returns the tree data. The type of each entry is read from
+// its object, since the mode alone doesn't tell a tree from an executable blob
+pub fn get_tree_data(raw_data: &str, cliente: String) -> Result<String, GitrError> {
     let mut tree_data = String::new();
-    for object in files {
-        let file_atributes = object.split(' ').collect::<Vec<&str>>();
-        let file_mode = file_atributes[0];
-        let file_path_hash = file_atributes[1];
-        let file_path = file_path_hash.split('\0').collect::<Vec<&str>>()[0];
-        let file_hash = file_path_hash.split('\0').collect::<Vec<&str>>()[1];
-        let file_type = if file_mode == "100644" {
-            "blob"
-        } else {
-            "tree"
+    for object in raw_data.split('\n').filter(|object| !object.is_empty()) {
+        let Some((file_mode, file_path_hash)) = object.split_once(' ') else {
+            continue;
         };
-
+        let Some((file_path, file_hash)) = file_path_hash.split_once('\0') else {
+            continue;
+        };
+        let file_hash = file_hash.to_string();
+        let file_type = file_manager::read_object_type(&file_hash, cliente.clone())?;
         let entry = format!("{} {} {} {}\n", file_mode, file_type, file_hash, file_path);
         tree_data.push_str(entry.as_str());
     }
-    tree_data
+    Ok(tree_data)
 }
 pub fn print_commit_data(raw_data: &str) {
     println!("{}", raw_data);
 }
 
-pub fn print_tag_data(raw_data: &str) {
-    println!("{}", raw_data);
+/// Recibe los datos de un tag anotado y devuelve su nombre, tagger, fecha y mensaje
+/// como los muestra show, junto con el hash del objeto al que apunta
+pub fn get_tag_data(raw_data: &str) -> Result<(String, String), GitrError> {
+    let (headers, message) = raw_data.split_once("\n\n").unwrap_or((raw_data, ""));
+    let campo = |nombre: &str| {
+        headers
+            .lines()
+            .find_map(|linea| linea.strip_prefix(nombre))
+            .unwrap_or_default()
+            .to_string()
+    };
+    let mut res = format!("tag {}\n", campo("tag "));
+    let tagger = campo("tagger ");
+    let mut partes = tagger.rsplitn(3, ' ');
+    let (_zona, timestamp, nombre) = (partes.next(), partes.next(), partes.next());
+    if let (Some(nombre), Some(Ok(timestamp))) = (nombre, timestamp.map(|t| t.parse::<i64>())) {
+        res.push_str(&format!("Tagger: {}\n", nombre));
+        res.push_str(&format!(
+            "Date: {}\n",
+            file_manager::format_timestamp(timestamp)?
+        ));
+    }
+    res.push_str(&format!("\n{}\n", message.trim_end()));
+    Ok((res, campo("object ")))
 }
 
 /***************************
@@ -213,7 +240,7 @@ pub fn create_trees(
                 let new_tree = create_trees(tree_map.clone(), obj.to_string(), cliente.clone())?;
                 tree_entry.push((obj.clone(), TreeEntry::Tree(new_tree)));
             } else {
-                let raw_data = file_manager::read_file(obj.clone())?;
+                let raw_data = read_worktree_file(obj, cliente.clone())?;
                 let blob = Blob::new(raw_data)?;
                 tree_entry.push((obj.clone(), TreeEntry::Blob(blob)));
             }
@@ -248,19 +275,20 @@ pub fn write_new_commit_and_branch(
     let path_complete = repo.clone() + "/gitr/" + head.as_str();
     if fs::metadata(path_complete.clone()).is_err() {
         let dir = repo + "/gitr/refs/heads/master";
-        file_manager::write_file(path_complete, final_tree.get_hash())?;
-        if !Path::new(&dir).exists() {
-            let current_commit = file_manager::get_current_commit(cliente.clone())?;
-            file_manager::write_file(dir.clone(), current_commit)?;
-        }
+        // el commit se arma antes de escribir las refs, si falla el repo queda como estaba
         let commit = Commit::new(
             final_tree.get_hash(),
             vec!["None".to_string()],
-            cliente.clone(),
-            cliente.clone(),
+            get_current_username(cliente.clone()),
+            get_current_username(cliente.clone()),
             message,
             cliente.clone(),
         )?;
+        file_manager::write_file(path_complete, final_tree.get_hash())?;
+        if !Path::new(&dir).exists() {
+            let current_commit = file_manager::get_current_commit(cliente.clone())?;
+            file_manager::write_file(dir.clone(), current_commit)?;
+        }
         commit.save(cliente.clone())?;
         file_manager::write_file(dir, commit.get_hash())?;
     } else {
@@ -273,8 +301,8 @@ pub fn write_new_commit_and_branch(
         let commit = Commit::new(
             final_tree.get_hash(),
             parents,
-            cliente.clone(),
-            cliente.clone(),
+            get_current_username(cliente.clone()),
+            get_current_username(cliente.clone()),
             message,
             cliente.clone(),
         )?;
@@ -292,7 +320,7 @@ pub fn get_hashmap_for_checkout(cliente: String) -> Result<CheckoutHashMap, Gitr
     let mut tree_order: Vec<String> = Vec::new();
     let index_files = read_index(cliente.clone())?;
     for file_info in index_files.split('\n') {
-        let file_path = file_info.split(' ').collect::<Vec<&str>>()[3];
+        let file_path = file_info.splitn(4, ' ').collect::<Vec<&str>>()[3];
         let splitted_file_path = file_path.split('/').collect::<Vec<&str>>();
         for (i, dir) in (splitted_file_path.clone()).iter().enumerate() {
             if let Some(last_element) = splitted_file_path.last() {
@@ -336,7 +364,7 @@ pub fn update_hashmap_tree_entry(
                 }
             }
             None => {
-                println!("No se encontro el folder");
+                println!("{}", tr("tree.folder_not_found", &[]));
             }
         }
     }
@@ -364,21 +392,159 @@ pub fn get_branch_to_checkout(
  **************************
  **************************/
 
-/// returns the username
+/// returns user.name from config, or the client name if it is not set
 pub fn get_current_username(cliente: String) -> String {
-    cliente
+    match Config::load(&cliente).map(|config| config.get("user.name")) {
+        Ok(Some(nombre)) => nombre,
+        _ => cliente,
+    }
 }
 
-/// returns the mail from config
+/// returns user.email from config
 pub fn get_user_mail_from_config(cliente: String) -> Result<String, GitrError> {
-    let config_data = match file_manager::read_file(cliente + "/gitrconfig") {
-        Ok(config_data) => config_data,
-        Err(e) => return Err(GitrError::FileReadError(e.to_string())),
+    Config::load(&cliente)?
+        .get("user.email")
+        .ok_or_else(|| GitrError::ConfigError(tr("config.identity_unknown", &[])))
+}
+
+/***************************
+ ***************************
+ *    CONFIG FUNCTIONS
+ **************************
+ **************************/
+
+const CONFIG_USAGE: &str =
+    "config [--system | --global | --local] [--get | --get-all | --unset | --list] [<key> [<value>]]";
+
+/// Lee o cambia la configuracion. Sin nivel se lee la union de todos y se escribe
+/// en la del repo.
+pub fn get_config(flags: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    let error = || GitrError::InvalidArgumentError(flags.join(" "), CONFIG_USAGE.to_string());
+    let (mut nivel, mut accion) = (None, None);
+    let mut args = Vec::new();
+    for flag in flags.iter() {
+        match flag.as_str() {
+            "--system" => nivel = Some(ConfigLevel::System),
+            "--global" => nivel = Some(ConfigLevel::Global),
+            "--local" => nivel = Some(ConfigLevel::Local),
+            "--get" | "--get-all" | "--unset" | "--list" | "-l" if accion.is_none() => {
+                accion = Some(flag.as_str())
+            }
+            f if f.starts_with('-') => return Err(error()),
+            _ => args.push(flag.as_str()),
+        }
+    }
+    let no_existe = |clave: &str| GitrError::ConfigError(tr("config.key_not_found", &[&clave]));
+    let config = match nivel {
+        Some(nivel) => Config::read(&nivel.path(&cliente)?)?,
+        None => Config::load(&cliente)?,
+    };
+    let path = nivel.unwrap_or(ConfigLevel::Local).path(&cliente);
+    match (accion, args.as_slice()) {
+        (Some("--list" | "-l"), []) => Ok(config
+            .entries()
+            .iter()
+            .map(|(clave, valor)| format!("{}={}\n", clave, valor))
+            .collect()),
+        (Some("--get") | None, [clave]) => {
+            normalize_key(clave)?;
+            config.get(clave).map(|v| v + "\n").ok_or(no_existe(clave))
+        }
+        (Some("--get-all"), [clave]) => {
+            normalize_key(clave)?;
+            match config.get_all(clave) {
+                valores if valores.is_empty() => Err(no_existe(clave)),
+                valores => Ok(valores.join("\n") + "\n"),
+            }
+        }
+        (Some("--unset"), [clave]) => {
+            let path = path?;
+            let mut config = Config::read(&path)?;
+            if !config.unset(clave)? {
+                return Err(no_existe(clave));
+            }
+            config.write(&path)?;
+            Ok(String::new())
+        }
+        (None, [clave, valor]) => {
+            let path = path?;
+            let mut config = Config::read(&path)?;
+            config.set(clave, valor)?;
+            config.write(&path)?;
+            Ok(String::new())
+        }
+        _ => Err(error()),
+    }
+}
+
+/***************************
+ ***************************
+ *    HOOKS FUNCTIONS
+ **************************
+ **************************/
+
+/// gitr/hooks/<nombre> si existe y es ejecutable
+fn hook_path(nombre: &str, cliente: String) -> Result<Option<String>, GitrError> {
+    use std::os::unix::fs::PermissionsExt;
+    let path = get_current_repo(cliente)? + "/gitr/hooks/" + nombre;
+    match fs::metadata(&path) {
+        Ok(meta) if meta.is_file() && meta.permissions().mode() & 0o111 != 0 => Ok(Some(path)),
+        _ => Ok(None),
+    }
+}
+
+/// Corre el hook desde la raiz del repo con los argumentos y el stdin dados.
+/// Si el hook termina con un codigo distinto de 0 se devuelve error para abortar la operacion.
+pub fn run_hook(
+    nombre: &str,
+    args: &[String],
+    stdin: &str,
+    cliente: String,
+) -> Result<(), GitrError> {
+    if cliente.contains('/') {
+        // en el server no hay hooks de cliente
+        return Ok(());
+    }
+    let Some(path) = hook_path(nombre, cliente.clone())? else {
+        return Ok(());
     };
+    let error = |motivo: String| GitrError::HookError(nombre.to_string(), motivo);
+    let programa = fs::canonicalize(&path).map_err(|e| error(e.to_string()))?;
+    let mut hijo = Command::new(programa)
+        .args(args)
+        .current_dir(get_current_repo(cliente)?)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| error(e.to_string()))?;
+    if let Some(mut entrada) = hijo.stdin.take() {
+        // si el hook no lee el stdin la escritura puede fallar, no importa
+        let _ = entrada.write_all(stdin.as_bytes());
+    }
+    let status = hijo.wait().map_err(|e| error(e.to_string()))?;
+    if !status.success() {
+        return Err(error(tr("error.exited_with", &[&status])));
+    }
+    Ok(())
+}
+
+/// los hooks post-* no pueden abortar nada, solo se avisa si fallan
+pub fn run_post_hook(nombre: &str, args: &[String], cliente: String) {
+    if let Err(e) = run_hook(nombre, args, "", cliente) {
+        println!("{}", e);
+    }
+}
 
-    let lines = config_data.split('\n').collect::<Vec<&str>>();
-    let email = lines[1].split('=').collect::<Vec<&str>>()[1].trim_start();
-    Ok(email.to_string())
+/// Corre commit-msg sobre gitr/COMMIT_EDITMSG y devuelve el mensaje, que el hook puede haber cambiado
+pub fn run_commit_msg_hook(message: String, cliente: String) -> Result<String, GitrError> {
+    let path = get_current_repo(cliente.clone())? + "/gitr/COMMIT_EDITMSG";
+    file_manager::write_file(path.clone(), message + "\n")?;
+    run_hook(
+        "commit-msg",
+        &["gitr/COMMIT_EDITMSG".to_string()],
+        "",
+        cliente,
+    )?;
+    Ok(file_manager::read_file(path)?.trim_end().to_string())
 }
 
 /***************************
@@ -387,38 +553,42 @@ pub fn get_user_mail_from_config(cliente: String) -> Result<String, GitrError> {
  **************************
  **************************/
 
-/// returns cached ls-files
-pub fn get_ls_files_cached(cliente: String) -> Result<String, GitrError> {
-    let mut string_res = String::new();
+/// returns the paths of the cached ls-files
+pub fn get_ls_files_cached(cliente: String) -> Result<Vec<String>, GitrError> {
+    let mut res = Vec::new();
     let index = match read_index(cliente.clone()) {
         Ok(index) => index,
-        Err(_) => return Ok(string_res),
+        Err(_) => return Ok(res),
     };
     for file_path in index.lines() {
         let correct_path = match file_path.split_once('/') {
             Some((_path, file)) => file,
             None => file_path,
         };
-        let line = correct_path.to_string() + "\n";
-        string_res.push_str(&line);
+        res.push(correct_path.to_string());
     }
-    Ok(string_res)
+    Ok(res)
 }
 
-/// returns deleted files or modified files depending on bool received
-pub fn get_ls_files_deleted_modified(deleted: bool, cliente: String) -> Result<String, GitrError> {
-    let mut res = String::new();
-    let (not_staged, _, _) = get_untracked_notstaged_files(cliente.clone())?;
-    let files_not_staged = get_status_files_not_staged(&not_staged, cliente.clone())?;
-    for line in files_not_staged.lines() {
-        if line.contains("deleted") && deleted {
-            let line = line.replace("deleted:   ", "");
-            res.push_str(&(line + "\n"));
-        } else if !deleted && (line.contains("modified") || line.contains("deleted")) {
-            let mut line = line.replace("modified   ", "");
-            line = line.replace("deleted:   ", "");
-            res.push_str(&(line + "\n"));
+/// returns deleted files or modified files depending on bool received, with the same
+/// paths as ls-files --cached
+pub fn get_ls_files_deleted_modified(
+    deleted: bool,
+    cliente: String,
+) -> Result<Vec<String>, GitrError> {
+    let mut res = Vec::new();
+    let (mut not_staged, _, _) = get_untracked_notstaged_files(cliente.clone())?;
+    not_staged.sort();
+    let working_dir_hashmap = get_working_dir_hashmap(cliente.clone())?;
+    for file in not_staged {
+        if deleted && working_dir_hashmap.contains_key(&file) {
+            continue;
         }
+        let file_name = match file.split_once('/') {
+            Some((_path, file)) => file.to_string(),
+            None => file,
+        };
+        res.push(file_name);
     }
     Ok(res)
 }
@@ -447,6 +617,34 @@ pub fn print_branches(cliente: String) -> Result<String, GitrError> {
     Ok(res)
 }
 
+/// branch --format: una linea por branch, ordenadas, reemplazando %(refname), %(refname:short),
+/// %(objectname), %(objectname:short), %(HEAD) y %(subject)
+pub fn format_branches(formato: &str, cliente: String) -> Result<String, GitrError> {
+    let head = file_manager::get_head(cliente.clone())?;
+    let mut branches = file_manager::get_branches(cliente.clone())?;
+    branches.sort();
+    let mut res = String::new();
+    for branch in branches {
+        let refname = format!("refs/heads/{}", branch);
+        let commit = file_manager::get_commit(branch.clone(), cliente.clone())?
+            .trim()
+            .to_string();
+        let mut linea = formato
+            .replace("%(refname:short)", &branch)
+            .replace("%(refname)", &refname)
+            .replace("%(objectname:short)", &commit[..7.min(commit.len())])
+            .replace("%(objectname)", &commit)
+            .replace("%(HEAD)", if head == refname { "*" } else { " " });
+        if linea.contains("%(subject)") {
+            let subject = get_log_subject_and_body(&commit, cliente.clone())?.0;
+            linea = linea.replace("%(subject)", &subject);
+        }
+        res.push_str(&linea);
+        res.push('\n');
+    }
+    Ok(res)
+}
+
 /// check if a branch exists
 pub fn branch_exists(branch: String, cliente: String) -> bool {
     let branches = file_manager::get_branches(cliente.clone());
@@ -487,8 +685,7 @@ pub fn branch_move_flag(
     let old_path = format!("{}/gitr/refs/heads/{}", repo.clone(), branch_origin);
     let new_path = format!("{}/gitr/refs/heads/{}", repo.clone(), branch_destination);
     file_manager::move_branch(old_path.clone(), new_path.clone())?;
-    let head = get_head(cliente.clone())?;
-    if branch_origin == head.split('/').collect::<Vec<&str>>()[2] {
+    if get_head_branch(cliente.clone())? == Some(branch_origin) {
         let ref_correct = format!("refs/heads/{}", branch_destination);
         file_manager::update_head(&ref_correct, cliente.clone())?;
     }
@@ -534,9 +731,17 @@ pub fn branch_commits_list(branch_name: String, cliente: String) -> Result<Vec<S
  **************************
  **************************/
 
+/// returns the branch HEAD points to, or None if HEAD is detached (for example during a bisect)
+pub fn get_head_branch(cliente: String) -> Result<Option<String>, GitrError> {
+    let head = get_head(cliente)?;
+    Ok(head
+        .strip_prefix("refs/heads/")
+        .map(|branch| branch.to_string()))
+}
+
 /// prints the commit confirmation after commiting
 pub fn print_commit_confirmation(message: String, cliente: String) -> Result<(), GitrError> {
-    let branch = get_head(cliente.clone())?.split('/').collect::<Vec<&str>>()[2].to_string();
+    let branch = get_head_branch(cliente.clone())?.unwrap_or(tr("commit.detached_head", &[]));
     let hash_recortado = &get_current_commit(cliente.clone())?[0..7];
     println!("[{} {}] {}", branch, hash_recortado, message);
     Ok(())
@@ -545,8 +750,8 @@ pub fn print_commit_confirmation(message: String, cliente: String) -> Result<(),
 pub fn commit_existing(cliente: String) -> Result<(), GitrError> {
     let repo = file_manager::get_current_repo(cliente.clone())?;
     let head = file_manager::get_head(cliente.clone())?;
-    let branch_name = head.split('/').collect::<Vec<&str>>()[2];
     if fs::metadata(repo.clone() + "/gitr/" + &head).is_err() {
+        let branch_name = head.strip_prefix("refs/heads/").unwrap_or(&head);
         return Err(GitrError::NoCommitExisting(branch_name.to_string()));
     }
     Ok(())
@@ -867,87 +1072,260 @@ pub fn three_way_merge(
     let mut hubo_conflict = false;
     let mut archivos_conflict = Vec::new();
 
+    if !cliente.contains('/') {
+        for (path, conflict) in merge_renamed_files(
+            &base_hashmap,
+            &origin_hashmap,
+            &branch_hashmap,
+            cliente.clone(),
+        )? {
+            hubo_conflict |= conflict;
+            if conflict {
+                if let Some(nombre_archivo) = path.rsplit('/').next() {
+                    archivos_conflict.push(nombre_archivo.to_string());
+                }
+            }
+        }
+    }
 
     for (path, origin_file_hash) in origin_hashmap.iter() {
-        let origin_file_data: String =
-            file_manager::read_file_data_from_blob_hash(origin_file_hash.clone(), cliente.clone())?;
-        if branch_hashmap.contains_key(&path.clone()) {
-            let branch_file_hash = branch_hashmap[path].clone(); //aax
-            let branch_file_data = file_manager::read_file_data_from_blob_hash(
-                branch_file_hash.clone(),
-                cliente.clone(),
-            )?;
-
-            if origin_file_hash == &branch_file_hash {
-                continue;
+        if !branch_hashmap.contains_key(&path.clone()) {
+            continue;
+        }
+        let branch_file_hash = branch_hashmap[path].clone(); //aax
+        if origin_file_hash == &branch_file_hash {
+            continue;
+        }
+        let base_file_hash = base_hashmap.get(path).cloned().unwrap_or_default();
+        if let Some(conflict) = merge_file(
+            path,
+            base_file_hash,
+            origin_file_hash.clone(),
+            branch_file_hash,
+            cliente.clone(),
+        )? {
+            hubo_conflict |= conflict;
+            if conflict {
+                if let Some(nombre_archivo) = path.rsplit('/').next() {
+                    archivos_conflict.push(nombre_archivo.to_string().clone());
+                }
             }
+        }
+    }
 
-            let mut base_file_hash = "".to_string();
-            let base_file_data;
+    Ok((hubo_conflict, archivos_conflict))
+}
 
-            if !base_hashmap.contains_key(path) {
-                base_file_data = "".to_string();
-            } else {
-                base_file_hash = base_hashmap[path].clone(); // chequear que capaz puede no exisiir en base
-                base_file_data = file_manager::read_file_data_from_blob_hash(
-                    base_file_hash.clone(),
-                    cliente.clone(),
-                )?;
-            }
+// mergea un archivo escrito en path a partir de los blobs de base, origin y branch.
+// Devuelve None si no hubo que unir diffs y si no, si la union dio conflicto
+fn merge_file(
+    path: &String,
+    base_file_hash: String,
+    origin_file_hash: String,
+    branch_file_hash: String,
+    cliente: String,
+) -> Result<Option<bool>, GitrError> {
+    let origin_file_data: String =
+        file_manager::read_file_data_from_blob_hash(origin_file_hash.clone(), cliente.clone())?;
+    let branch_file_data =
+        file_manager::read_file_data_from_blob_hash(branch_file_hash.clone(), cliente.clone())?;
+    let base_file_data = match base_file_hash.is_empty() {
+        true => "".to_string(),
+        false => {
+            file_manager::read_file_data_from_blob_hash(base_file_hash.clone(), cliente.clone())?
+        }
+    };
 
-            if &base_file_hash == origin_file_hash {
-                let diff_base_branch = Diff::new(base_file_data, branch_file_data);
-                if cliente.contains('/') {
-                    _aplicar_diffs(origin_file_data.clone(), diff_base_branch)?;
-                } else {
-                    aplicar_diffs(path.clone(), diff_base_branch)?;
-                }
-                continue;
-            }
+    if base_file_hash == origin_file_hash {
+        let diff_base_branch = Diff::new(base_file_data, branch_file_data);
+        if cliente.contains('/') {
+            _aplicar_diffs(origin_file_data.clone(), diff_base_branch)?;
+        } else {
+            aplicar_diffs(path.clone(), diff_base_branch)?;
+        }
+        return Ok(None);
+    }
 
-            if base_file_hash == branch_file_hash {
-                continue;
-            }
+    if base_file_hash == branch_file_hash {
+        return Ok(None);
+    }
 
-            let mut len_archivo = base_file_data.len();
-            if len_archivo == 0 {
-                if branch_file_data.len() > origin_file_data.len() {
-                    len_archivo = branch_file_data.len();
-                } else {
-                    len_archivo = origin_file_data.len();
-                }
-            }
+    // drivers de gitrattributes: con binary (o -merge) queda la version actual en conflicto
+    // y con ours queda la version actual sin conflicto
+    let driver = get_attributes(path, cliente.clone())?.get("merge").cloned();
+    match driver {
+        Some(AttrValue::Value(ref nombre)) if nombre == "ours" => return Ok(Some(false)),
+        Some(AttrValue::Unset) => return Ok(Some(true)),
+        Some(AttrValue::Value(ref nombre)) if nombre == "binary" => return Ok(Some(true)),
+        _ if origin_file_data.contains('\0') || branch_file_data.contains('\0') => {
+            return Ok(Some(true))
+        }
+        _ => {}
+    }
+
+    let mut len_archivo = base_file_data.len();
+    if len_archivo == 0 {
+        if branch_file_data.len() > origin_file_data.len() {
+            len_archivo = branch_file_data.len();
+        } else {
+            len_archivo = origin_file_data.len();
+        }
+    }
 
-            let diff_base_origin = Diff::new(base_file_data.clone(), origin_file_data.clone());
-            let diff_base_branch = Diff::new(base_file_data.clone(), branch_file_data.clone());
-            let union_diffs;
+    let diff_base_origin = Diff::new(base_file_data.clone(), origin_file_data.clone());
+    let diff_base_branch = Diff::new(base_file_data.clone(), branch_file_data.clone());
 
-            (union_diffs, hubo_conflict) =
-                comparar_diffs(diff_base_origin, diff_base_branch, len_archivo - 1)?; //une los diffs o da el conflict
+    let (union_diffs, hubo_conflict) =
+        comparar_diffs(diff_base_origin, diff_base_branch, len_archivo - 1)?; //une los diffs o da el conflict
 
-            if hubo_conflict {
-                if let Some(nombre_archivo) = path.split('/').last() {
-                    archivos_conflict.push(nombre_archivo.to_string().clone());
-                }
-            }
+    if base_file_data.is_empty() || base_file_data.is_empty() {
+        let archivo_reconstruido = _aplicar_diffs("".to_string(), union_diffs)?;
+        file_manager::write_file(
+            path.to_string() + "_mergeado",
+            archivo_reconstruido.concat().to_string(),
+        )?;
+    } else if cliente.contains('/') {
+        _aplicar_diffs(origin_file_data.clone(), union_diffs)?;
+    } else {
+        aplicar_diffs(path.clone(), union_diffs)?;
+        // con union los conflictos se resuelven dejando las lineas de ambos lados
+        if hubo_conflict && driver == Some(AttrValue::Value("union".to_string())) {
+            let mergeado = file_manager::read_file(path.clone())?;
+            file_manager::write_file(path.clone(), remove_conflict_markers(&mergeado))?;
+            return Ok(Some(false));
+        }
+    }
+    Ok(Some(hubo_conflict))
+}
 
-            if base_file_data.is_empty() || base_file_data.is_empty() {
-                let archivo_reconstruido = _aplicar_diffs("".to_string(), union_diffs)?;
-                file_manager::write_file(
-                    path.to_string() + "_mergeado",
-                    archivo_reconstruido.concat().to_string(),
-                )?;
-            } else if cliente.contains('/') {
-                _aplicar_diffs(origin_file_data.clone(), union_diffs)?;
-            } else {
-                aplicar_diffs(path.clone(), union_diffs)?;
+// saca las lineas de los marcadores de conflicto que arma armar_conflict2
+fn remove_conflict_markers(data: &str) -> String {
+    let mut en_conflicto = false;
+    let mut res = Vec::new();
+    for linea in data.split('\n') {
+        match linea {
+            "<<<<<<< HEAD" if !en_conflicto => en_conflicto = true,
+            "=======" if en_conflicto => {}
+            ">>>>>>> BRANCH" if en_conflicto => en_conflicto = false,
+            _ => res.push(linea),
+        }
+    }
+    res.join("\n")
+}
+
+// busca los archivos de base que del lado "nuevo" fueron renombrados a un path que el
+// lado "otro" no tiene. Devuelve (path en base, path nuevo)
+fn detect_tree_renames(
+    base_hashmap: &HashMap<String, String>,
+    nuevo_hashmap: &HashMap<String, String>,
+    otro_hashmap: &HashMap<String, String>,
+    cliente: String,
+) -> Result<Vec<(String, String)>, GitrError> {
+    let mut origenes = base_hashmap
+        .keys()
+        .filter(|path| !nuevo_hashmap.contains_key(*path) && otro_hashmap.contains_key(*path))
+        .cloned()
+        .collect::<Vec<String>>();
+    let mut destinos = nuevo_hashmap
+        .keys()
+        .filter(|path| !base_hashmap.contains_key(*path) && !otro_hashmap.contains_key(*path))
+        .cloned()
+        .collect::<Vec<String>>();
+    if origenes.is_empty() || destinos.is_empty() {
+        return Ok(vec![]);
+    }
+    origenes.sort();
+    destinos.sort();
+
+    let mut datos_origenes = Vec::new();
+    for path in origenes.iter() {
+        datos_origenes.push(file_manager::read_file_data_from_blob_hash(
+            base_hashmap[path].clone(),
+            cliente.clone(),
+        )?);
+    }
+    let mut datos_destinos = Vec::new();
+    for path in destinos.iter() {
+        datos_destinos.push(file_manager::read_file_data_from_blob_hash(
+            nuevo_hashmap[path].clone(),
+            cliente.clone(),
+        )?);
+    }
+    let pares = detect_renames(
+        &datos_origenes
+            .iter()
+            .map(|d| d.as_str())
+            .collect::<Vec<&str>>(),
+        &datos_destinos
+            .iter()
+            .map(|d| d.as_str())
+            .collect::<Vec<&str>>(),
+        get_rename_threshold(cliente.clone())?,
+        false,
+    );
+    Ok(pares
+        .into_iter()
+        .map(|(i, j, _)| (origenes[i].clone(), destinos[j].clone()))
+        .collect())
+}
+
+// lleva los cambios hechos en un lado del merge al path al que el otro lado renombro
+// el archivo. Devuelve (path, hubo_conflict) de cada archivo que se tuvo que mergear
+fn merge_renamed_files(
+    base_hashmap: &HashMap<String, String>,
+    origin_hashmap: &HashMap<String, String>,
+    branch_hashmap: &HashMap<String, String>,
+    cliente: String,
+) -> Result<Vec<(String, bool)>, GitrError> {
+    let mut mergeados = Vec::new();
+
+    // la branch renombro el archivo: el path nuevo ya se escribio con el contenido de la branch
+    for (viejo, nuevo) in detect_tree_renames(
+        base_hashmap,
+        branch_hashmap,
+        origin_hashmap,
+        cliente.clone(),
+    )? {
+        if origin_hashmap[&viejo] != base_hashmap[&viejo] {
+            let origin_data = file_manager::read_file_data_from_blob_hash(
+                origin_hashmap[&viejo].clone(),
+                cliente.clone(),
+            )?;
+            file_manager::write_file(nuevo.clone(), origin_data)?;
+            if let Some(conflict) = merge_file(
+                &nuevo,
+                base_hashmap[&viejo].clone(),
+                origin_hashmap[&viejo].clone(),
+                branch_hashmap[&nuevo].clone(),
+                cliente.clone(),
+            )? {
+                mergeados.push((nuevo.clone(), conflict));
             }
-        } else {
-            continue;
         }
+        file_manager::remove_file(viejo)?;
     }
 
-    Ok((hubo_conflict, archivos_conflict))
+    // origin renombro el archivo: el path viejo lo volvio a crear add_new_files_from_merge
+    for (viejo, nuevo) in detect_tree_renames(
+        base_hashmap,
+        origin_hashmap,
+        branch_hashmap,
+        cliente.clone(),
+    )? {
+        if branch_hashmap[&viejo] != base_hashmap[&viejo] {
+            if let Some(conflict) = merge_file(
+                &nuevo,
+                base_hashmap[&viejo].clone(),
+                origin_hashmap[&nuevo].clone(),
+                branch_hashmap[&viejo].clone(),
+                cliente.clone(),
+            )? {
+                mergeados.push((nuevo.clone(), conflict));
+            }
+        }
+        file_manager::remove_file(viejo)?;
+    }
+    Ok(mergeados)
 }
 
 pub fn create_merge_commit(
@@ -960,7 +1338,9 @@ pub fn create_merge_commit(
         return commands_fn::status(vec![], cliente.clone());
     }
 
+    run_hook("pre-merge-commit", &[], "", cliente.clone())?;
     let message = format!("Merge branch '{}'", branch_name);
+    let message = run_commit_msg_hook(message, cliente.clone())?;
     get_tree_entries(message.to_string(), branch_commit, cliente.clone())?;
     print_commit_confirmation(message, cliente.clone())?;
     Ok(())
@@ -977,21 +1357,192 @@ pub fn get_status(cliente: String) -> Result<String, GitrError> {
     res.push_str(&(status_print_current_branch(cliente.clone())? + "\n"));
     let (not_staged, untracked_files, hayindex) = get_untracked_notstaged_files(cliente.clone())?;
     let (new_files, modified_files) = get_tobe_commited_files(&not_staged, cliente.clone())?;
+    let mut new_files = new_files;
+    let renamed_files = get_status_renamed_files(&mut new_files, cliente.clone())?;
     res.push_str(&get_status_files_to_be_comited(
         &new_files,
         &modified_files,
+        &renamed_files,
     )?);
     res.push_str(&get_status_files_not_staged(&not_staged, cliente.clone())?);
     res.push_str(&get_status_files_untracked(&untracked_files, hayindex));
     if new_files.is_empty()
         && modified_files.is_empty()
+        && renamed_files.is_empty()
         && not_staged.is_empty()
         && untracked_files.is_empty()
     {
-        res.push_str("nothing to commit, working tree clean\n");
+        res.push_str(&(tr("status.clean", &[]) + "\n"));
+    }
+    Ok(res)
+}
+// un archivo de status --porcelain: estado en el index (X), en el working dir (Y) y
+// el path original si es un rename
+struct StatusEntry {
+    x: char,
+    y: char,
+    orig: Option<(String, usize)>,
+}
+
+/// Arma la salida de status --porcelain=v1|v2, con paths relativos al repo y separados por
+/// '\n' o por '\0' con -z. Con --branch empieza con la branch actual ("## <branch>" en v1
+/// y los headers "# branch.oid" y "# branch.head" en v2). El formato es estable para que
+/// lo lean scripts. Como gitr no guarda el bit de ejecucion, en v2 los modos de los
+/// archivos que existen son siempre 100644.
+pub fn get_status_porcelain(
+    version: &str,
+    z: bool,
+    branch: bool,
+    cliente: String,
+) -> Result<String, GitrError> {
+    let repo = file_manager::get_current_repo(cliente.clone())?;
+    let relativo = |path: &str| {
+        path.strip_prefix(&format!("{}/", repo))
+            .unwrap_or(path)
+            .to_string()
+    };
+    let working_dir_hashmap = get_working_dir_hashmap(cliente.clone())?;
+    let (index_hashmap, hayindex) = get_index_hashmap(cliente.clone())?;
+    let head = get_current_commit(cliente.clone()).unwrap_or_default();
+    let commit_hashmap = get_commit_hashmap(head.clone(), cliente.clone())?;
+    let (not_staged, untracked_files, _) = get_untracked_notstaged_files(cliente.clone())?;
+
+    let mut entries: BTreeMap<String, StatusEntry> = BTreeMap::new();
+    let mut new_files = Vec::new();
+    for (path, hash) in index_hashmap.iter() {
+        match commit_hashmap.get(path) {
+            None => new_files.push(path.clone()),
+            Some(commit_hash) if commit_hash != hash => {
+                entries.insert(
+                    path.clone(),
+                    StatusEntry {
+                        x: 'M',
+                        y: '.',
+                        orig: None,
+                    },
+                );
+            }
+            Some(_) => (),
+        }
+    }
+    new_files.sort();
+    let renamed_files = get_status_renamed_files(&mut new_files, cliente.clone())?;
+    for path in new_files {
+        entries.insert(
+            path,
+            StatusEntry {
+                x: 'A',
+                y: '.',
+                orig: None,
+            },
+        );
+    }
+    for (old, new, score) in renamed_files.iter() {
+        let orig = Some((old.clone(), *score));
+        entries.insert(
+            new.clone(),
+            StatusEntry {
+                x: 'R',
+                y: '.',
+                orig,
+            },
+        );
+    }
+    if hayindex {
+        for path in commit_hashmap.keys() {
+            if !index_hashmap.contains_key(path)
+                && !renamed_files.iter().any(|(old, _, _)| old == path)
+            {
+                entries.insert(
+                    path.clone(),
+                    StatusEntry {
+                        x: 'D',
+                        y: '.',
+                        orig: None,
+                    },
+                );
+            }
+        }
+    }
+    for path in not_staged {
+        let y = match working_dir_hashmap.contains_key(&path) {
+            true => 'M',
+            false => 'D',
+        };
+        entries
+            .entry(path)
+            .or_insert(StatusEntry {
+                x: '.',
+                y,
+                orig: None,
+            })
+            .y = y;
+    }
+
+    let fin = if z { '\0' } else { '\n' };
+    let mut res = String::new();
+    if branch {
+        let head_ref = file_manager::get_head(cliente.clone())?;
+        let branch = head_ref.strip_prefix("refs/heads/").unwrap_or(&head_ref);
+        match (version, head.is_empty()) {
+            ("v2", _) => {
+                let oid = if head.is_empty() { "(initial)" } else { &head };
+                res.push_str(&format!("# branch.oid {}{}", oid, fin));
+                res.push_str(&format!("# branch.head {}{}", branch, fin));
+            }
+            (_, true) => res.push_str(&format!("## No commits yet on {}{}", branch, fin)),
+            (_, false) => res.push_str(&format!("## {}{}", branch, fin)),
+        }
+    }
+    let cero = "0".repeat(40);
+    for (path, entry) in entries.iter() {
+        // v1 marca sin cambios con espacio y v2 con punto
+        let sin_cambios = |c: char| if c == '.' && version != "v2" { ' ' } else { c };
+        let (x, y) = (sin_cambios(entry.x), sin_cambios(entry.y));
+        let path_head = entry.orig.as_ref().map(|(orig, _)| orig).unwrap_or(path);
+        let linea = match (version, &entry.orig) {
+            ("v2", _) => {
+                // los modos de los trees, el index y el working dir siempre son 100644
+                let modo = |existe: bool| if existe { "100644" } else { "000000" };
+                let hash_head = commit_hashmap.get(path_head).unwrap_or(&cero);
+                let hash_index = index_hashmap.get(path).unwrap_or(&cero);
+                let campos = format!(
+                    "{}{} N... {} {} {} {} {}",
+                    x,
+                    y,
+                    modo(commit_hashmap.contains_key(path_head)),
+                    modo(index_hashmap.contains_key(path)),
+                    modo(working_dir_hashmap.contains_key(path)),
+                    hash_head,
+                    hash_index
+                );
+                match &entry.orig {
+                    Some((orig, score)) => {
+                        let sep = if z { '\0' } else { '\t' };
+                        let (path, orig) = (relativo(path), relativo(orig));
+                        format!("2 {} R{} {}{}{}", campos, score, path, sep, orig)
+                    }
+                    None => format!("1 {} {}", campos, relativo(path)),
+                }
+            }
+            (_, Some((orig, _))) if z => {
+                format!("{}{} {}\0{}", x, y, relativo(path), relativo(orig))
+            }
+            (_, Some((orig, _))) => format!("{}{} {} -> {}", x, y, relativo(orig), relativo(path)),
+            (_, None) => format!("{}{} {}", x, y, relativo(path)),
+        };
+        res.push_str(&linea);
+        res.push(fin);
+    }
+    let mut untracked_files = untracked_files;
+    untracked_files.sort();
+    for path in untracked_files {
+        let prefijo = if version == "v2" { "?" } else { "??" };
+        res.push_str(&format!("{} {}{}", prefijo, relativo(&path), fin));
     }
     Ok(res)
 }
+
 pub fn get_working_dir_hashmap(cliente: String) -> Result<HashMap<String, String>, GitrError> {
     let mut working_dir_hashmap = HashMap::new();
     let repo = file_manager::get_current_repo(cliente.clone())?;
@@ -999,7 +1550,7 @@ pub fn get_working_dir_hashmap(cliente: String) -> Result<HashMap<String, String
     let path = Path::new(&repo);
     let files = visit_dirs(path);
     for file_path in files {
-        let file_data = file_manager::read_file(file_path.clone())?;
+        let file_data = read_worktree_file(&file_path, cliente.clone())?;
 
         let blob = Blob::new(file_data.clone())?;
         let hash = blob.get_hash();
@@ -1011,11 +1562,12 @@ pub fn get_working_dir_hashmap(cliente: String) -> Result<HashMap<String, String
 pub fn get_status_files_to_be_comited(
     new_files: &Vec<String>,
     modified_files: &Vec<String>,
+    renamed_files: &[(String, String, usize)],
 ) -> Result<String, GitrError> {
     let mut res = String::new();
-    if !new_files.is_empty() || !modified_files.is_empty() {
-        let header1 = "Changes to be committed:\n".to_string();
-        let header2 = "  (use \"rm <file>...\" to unstage)\n".to_string();
+    if !new_files.is_empty() || !modified_files.is_empty() || !renamed_files.is_empty() {
+        let header1 = tr("status.to_be_committed", &[]) + "\n";
+        let header2 = tr("status.unstage_hint", &[]) + "\n";
         res.push_str(&header1);
         res.push_str(&header2);
         for file in new_files.clone() {
@@ -1023,7 +1575,10 @@ pub fn get_status_files_to_be_comited(
                 Some((_path, file)) => file.to_string(),
                 None => file.to_string(),
             };
-            let line = format!("\t\x1b[92mnew file:   {}\x1b[0m\n", file_name);
+            let line = format!(
+                "\t\x1b[92m{}\x1b[0m\n",
+                tr("status.new_file", &[&file_name])
+            );
             res.push_str(&line);
         }
         for file in modified_files.clone() {
@@ -1031,13 +1586,87 @@ pub fn get_status_files_to_be_comited(
                 Some((_path, file)) => file.to_string(),
                 None => file.to_string(),
             };
-            let line = format!("\t\x1b[92mmodified:   {}\x1b[0m\n", file_name);
+            let line = format!(
+                "\t\x1b[92m{}\x1b[0m\n",
+                tr("status.modified", &[&file_name])
+            );
+            res.push_str(&line);
+        }
+        for (old_file, new_file, _) in renamed_files {
+            let file_name = |file: &String| match file.split_once('/') {
+                Some((_path, file)) => file.to_string(),
+                None => file.to_string(),
+            };
+            let line = format!(
+                "\t\x1b[92m{}\x1b[0m\n",
+                tr(
+                    "status.renamed",
+                    &[&file_name(old_file), &file_name(new_file)]
+                )
+            );
             res.push_str(&line);
         }
     }
     Ok(res)
 }
 
+// separa de los archivos nuevos los que son renames de archivos que se sacaron del index,
+// con el porcentaje de similitud de cada uno
+pub fn get_status_renamed_files(
+    new_files: &mut Vec<String>,
+    cliente: String,
+) -> Result<Vec<(String, String, usize)>, GitrError> {
+    let (index_hashmap, hayindex) = get_index_hashmap(cliente.clone())?;
+    if !hayindex || new_files.is_empty() {
+        return Ok(vec![]);
+    }
+    let head = get_current_commit(cliente.clone()).unwrap_or_default();
+    let mut deleted_files = get_commit_hashmap(head, cliente.clone())?
+        .into_iter()
+        .filter(|(path, _)| !index_hashmap.contains_key(path))
+        .collect::<Vec<(String, String)>>();
+    deleted_files.sort();
+
+    let mut deleted_data = Vec::new();
+    for (_, hash) in deleted_files.iter() {
+        deleted_data.push(file_manager::read_file_data_from_blob_hash(
+            hash.clone(),
+            cliente.clone(),
+        )?);
+    }
+    let mut new_data = Vec::new();
+    for path in new_files.iter() {
+        let hash = index_hashmap.get(path).cloned().unwrap_or_default();
+        new_data.push(file_manager::read_file_data_from_blob_hash(
+            hash,
+            cliente.clone(),
+        )?);
+    }
+    let pares = detect_renames(
+        &deleted_data
+            .iter()
+            .map(|d| d.as_str())
+            .collect::<Vec<&str>>(),
+        &new_data.iter().map(|d| d.as_str()).collect::<Vec<&str>>(),
+        get_rename_threshold(cliente.clone())?,
+        false,
+    );
+
+    let mut renamed_files = Vec::new();
+    let mut renamed_new = HashSet::new();
+    for (i, j, score) in pares {
+        renamed_files.push((deleted_files[i].0.clone(), new_files[j].clone(), score));
+        renamed_new.insert(j);
+    }
+    *new_files = new_files
+        .drain(..)
+        .enumerate()
+        .filter(|(j, _)| !renamed_new.contains(j))
+        .map(|(_, path)| path)
+        .collect();
+    Ok(renamed_files)
+}
+
 pub fn get_status_files_not_staged(
     not_staged: &Vec<String>,
     cliente: String,
@@ -1046,10 +1675,9 @@ pub fn get_status_files_not_staged(
     let (index, hayindex) = get_index_hashmap(cliente.clone())?;
     let working_dir_hashmap = get_working_dir_hashmap(cliente.clone())?;
     if !not_staged.is_empty() {
-        let header1 = "Changes not staged for commit:\n".to_string();
-        let header2 = "  (use \"add <file>...\" to update what will be committed)\n".to_string();
-        let header3 =
-            "  (use \"rm <file>...\" to discard changes in working directory)\n".to_string();
+        let header1 = tr("status.not_staged", &[]) + "\n";
+        let header2 = tr("status.add_hint", &[]) + "\n";
+        let header3 = tr("status.discard_hint", &[]) + "\n";
         res.push_str(&header1);
         res.push_str(&header2);
         res.push_str(&header3);
@@ -1062,10 +1690,13 @@ pub fn get_status_files_not_staged(
                 && index.contains_key(&file)
                 && !working_dir_hashmap.contains_key(file.as_str())
             {
-                let line = format!("\t\x1b[31mdeleted:   {}\x1b[0m\n", file_name);
+                let line = format!("\t\x1b[31m{}\x1b[0m\n", tr("status.deleted", &[&file_name]));
                 res.push_str(&line);
             } else {
-                let line = format!("\t\x1b[92mmodified   {}\x1b[0m\n", file_name);
+                let line = format!(
+                    "\t\x1b[92m{}\x1b[0m\n",
+                    tr("status.modified", &[&file_name])
+                );
                 res.push_str(&line);
             }
         }
@@ -1076,9 +1707,8 @@ pub fn get_status_files_not_staged(
 pub fn get_status_files_untracked(untracked_files: &Vec<String>, hayindex: bool) -> String {
     let mut res = String::new();
     if !untracked_files.is_empty() {
-        let header1 = "Untracked files:\n".to_string();
-        let header2 =
-            "  (use \"add <file>...\" to include in what will be committed)\n".to_string();
+        let header1 = tr("status.untracked", &[]) + "\n";
+        let header2 = tr("status.track_hint", &[]) + "\n";
         res.push_str(&header1);
         res.push_str(&header2);
         for file in untracked_files.clone() {
@@ -1091,9 +1721,7 @@ pub fn get_status_files_untracked(untracked_files: &Vec<String>, hayindex: bool)
         }
 
         if !hayindex {
-            let nothing_output =
-                "nothing added to commit but untracked files present (use \"add\" to track)\n"
-                    .to_string();
+            let nothing_output = tr("status.nothing_added", &[]) + "\n";
             res.push_str(&nothing_output);
         }
     }
@@ -1102,11 +1730,27 @@ pub fn get_status_files_untracked(untracked_files: &Vec<String>, hayindex: bool)
 
 pub fn status_print_current_branch(cliente: String) -> Result<String, GitrError> {
     let mut res = String::new();
-    let head = file_manager::get_head(cliente.clone())?;
-    let current_branch = head.split('/').collect::<Vec<&str>>()[2];
-    res.push_str(&format!("On branch {}\n\n", current_branch));
+    match get_head_branch(cliente.clone())? {
+        Some(current_branch) => {
+            res.push_str(&(tr("status.on_branch", &[&current_branch]) + "\n"));
+        }
+        None => {
+            let commit = get_current_commit(cliente.clone())?;
+            let commit = &commit[..7.min(commit.len())];
+            res.push_str(&(tr("status.head_detached", &[&commit]) + "\n"));
+        }
+    }
+    if rebase_in_progress(cliente.clone())? {
+        let onto = read_rebase_state("onto", cliente.clone())?;
+        let onto = &onto[..7];
+        res.push_str(&(tr("status.rebase_in_progress", &[&onto]) + "\n"));
+        res.push_str(&(tr("status.rebase_continue_hint", &[]) + "\n"));
+        res.push_str(&(tr("status.rebase_skip_hint", &[]) + "\n"));
+        res.push_str(&(tr("status.rebase_abort_hint", &[]) + "\n"));
+    }
+    res.push('\n');
     if commit_existing(cliente).is_err() {
-        res.push_str("No commits yet\n");
+        res.push_str(&(tr("status.no_commits", &[]) + "\n"));
     }
     Ok(res)
 }
@@ -1123,7 +1767,7 @@ pub fn get_index_hashmap(cliente: String) -> Result<(HashMap<String, String>, bo
     };
     if hayindex {
         for index_entry in index_data.split('\n') {
-            let attributes = index_entry.split(' ').collect::<Vec<&str>>();
+            let attributes = index_entry.splitn(4, ' ').collect::<Vec<&str>>();
             let path = attributes[3].to_string();
             let hash = attributes[1].to_string();
             index_hashmap.insert(path, hash);
@@ -1150,7 +1794,7 @@ pub fn get_subtrees_data(
     };
     for entry in tree_entries.split('\n') {
         if entry.split(' ').collect::<Vec<&str>>()[0] == "40000" {
-            let attributes = entry.split(' ').collect::<Vec<&str>>()[1];
+            let attributes = entry.splitn(2, ' ').collect::<Vec<&str>>()[1];
             let relative_file_path = attributes.split('\0').collect::<Vec<&str>>()[0].to_string();
             let file_path = format!("{}/{}", file_path, relative_file_path);
             let file_hash = attributes.split('\0').collect::<Vec<&str>>()[1].to_string();
@@ -1160,7 +1804,7 @@ pub fn get_subtrees_data(
             continue;
         }
 
-        let attributes = entry.split(' ').collect::<Vec<&str>>()[1];
+        let attributes = entry.splitn(2, ' ').collect::<Vec<&str>>()[1];
         let relative_file_path = attributes.split('\0').collect::<Vec<&str>>()[0].to_string();
         let file_path = format!("{}/{}", file_path, relative_file_path);
         let file_hash = attributes.split('\0').collect::<Vec<&str>>()[1].to_string();
@@ -1185,7 +1829,7 @@ pub fn get_commit_hashmap(
         };
         for entry in tree_entries.split('\n') {
             if entry.split(' ').collect::<Vec<&str>>()[0] == "40000" {
-                let attributes = entry.split(' ').collect::<Vec<&str>>()[1];
+                let attributes = entry.splitn(2, ' ').collect::<Vec<&str>>()[1];
                 let _file_path = attributes.split('\0').collect::<Vec<&str>>()[0].to_string();
                 let file_path = format!("{}/{}", repo, _file_path);
                 let file_hash = attributes.split('\0').collect::<Vec<&str>>()[1].to_string();
@@ -1196,7 +1840,7 @@ pub fn get_commit_hashmap(
                 continue;
             }
 
-            let attributes = entry.split(' ').collect::<Vec<&str>>()[1];
+            let attributes = entry.splitn(2, ' ').collect::<Vec<&str>>()[1];
             let _file_path = attributes.split('\0').collect::<Vec<&str>>()[0].to_string();
             let file_path = format!("{}/{}", repo, _file_path);
             let file_hash = attributes.split('\0').collect::<Vec<&str>>()[1].to_string();
@@ -1242,6 +1886,9 @@ pub fn get_untracked_notstaged_files(
             };
         }
     }
+    // los archivos que matchean el gitrignore no se muestran como sin seguimiento
+    let ignorados = check_ignore_(untracked_files.clone(), cliente)?;
+    untracked_files.retain(|path| !ignorados.contains(path));
     Ok((not_staged, untracked_files, hayindex))
 }
 
@@ -1265,7 +1912,7 @@ pub fn get_current_commit_hashmap(cliente: String) -> Result<HashMap<String, Str
             None => "",
         };
         for entry in tree_entries.split('\n') {
-            let attributes = entry.split(' ').collect::<Vec<&str>>()[1];
+            let attributes = entry.splitn(2, ' ').collect::<Vec<&str>>()[1];
             let _file_path = attributes.split('\0').collect::<Vec<&str>>()[0].to_string();
             let file_path = format!("{}/{}", repo, _file_path);
             let file_hash = attributes.split('\0').collect::<Vec<&str>>()[1].to_string();
@@ -1296,236 +1943,3504 @@ pub fn get_tobe_commited_files(
 
 /***************************
  ***************************
- *    ADD FUNCTIONS
+ *    REV-PARSE FUNCTIONS
  **************************
  **************************/
 
-pub fn save_and_add_blob_to_index(file_path: String, cliente: String) -> Result<(), GitrError> {
-    match path_is_ignored(vec![file_path.clone()], cliente.clone()) {
-        Ok(r) => {
-            if r {
-                return Ok(());
+// si el objeto es un tag anotado devuelve el commit al que apunta
+fn peel_to_commit(hash: String, reference: String, cliente: String) -> Result<String, GitrError> {
+    let data = file_manager::read_object(&hash, get_current_repo(cliente.clone())?, true)
+        .map_err(|_| GitrError::NoCommitExisting(reference.clone()))?;
+    if data.starts_with("commit") {
+        return Ok(hash);
+    }
+    if data.starts_with("tag") {
+        if let Some(linea) = data.split('\0').nth(1).and_then(|body| body.lines().next()) {
+            if let Some(object) = linea.strip_prefix("object ") {
+                return peel_to_commit(object.to_string(), reference, cliente);
             }
         }
-        Err(e) => return Err(e),
     }
-    let raw_data = file_manager::read_file(file_path.clone())?;
-    let blob = Blob::new(raw_data)?;
-    blob.save(cliente.clone())?;
-    let hash = blob.get_hash();
-    file_manager::add_to_index(&file_path, &hash, cliente.clone())?;
-    Ok(())
+    Err(GitrError::NoCommitExisting(reference))
 }
 
-pub fn update_index_before_add(cliente: String) -> Result<(), GitrError> {
-    let repo = file_manager::get_current_repo(cliente.clone())?;
-    let index_path = &(repo.clone() + "/gitr/index");
-    if Path::new(index_path).is_file() {
-        let index_data = file_manager::read_index(cliente.clone())?;
-        let mut index_vector: Vec<&str> = Vec::new();
-        if !index_data.is_empty() {
-            index_vector = index_data.split('\n').collect::<Vec<&str>>();
-        }
-        let mut i: i32 = 0;
-        while i != index_vector.len() as i32 {
-            let entry = index_vector[i as usize];
-            let path_to_check = entry.split(' ').collect::<Vec<&str>>()[3];
-            if !Path::new(path_to_check).exists() {
-                index_vector.remove(i as usize);
-                i -= 1;
-            }
-            i += 1;
-        }
-        file_manager::remove_file(index_path.to_string())?;
-        for entry in index_vector {
-            let path = entry.split(' ').collect::<Vec<&str>>()[3];
-            save_and_add_blob_to_index(path.to_string(), cliente.clone())?;
+fn first_parent(commit: String, reference: String, cliente: String) -> Result<String, GitrError> {
+    match file_manager::get_parent_commit(commit, cliente)?.first() {
+        Some(parent) if parent != "None" => Ok(parent.clone()),
+        _ => Err(GitrError::NoCommitExisting(reference)),
+    }
+}
+
+// busca los objetos cuyo hash empieza con el prefijo recibido
+fn objects_with_prefix(prefix: &str, cliente: String) -> Result<Vec<String>, GitrError> {
+    let dir = format!(
+        "{}/gitr/objects/{}",
+        get_current_repo(cliente)?,
+        &prefix[..2]
+    );
+    let mut hashes = Vec::new();
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let hash = format!("{}{}", &prefix[..2], entry.file_name().to_string_lossy());
+            if hash.starts_with(prefix) {
+                hashes.push(hash);
+            }
         }
     }
-    Ok(())
+    Ok(hashes)
 }
 
-pub fn add_files_command(file_path: String, cliente: String) -> Result<(), GitrError> {
+/// Recibe HEAD, una branch, un tag o un hash (o su prefijo), con ~n o ^ opcionales,
+/// y devuelve el hash del commit al que se refiere.
+pub fn resolve_commit(reference: String, cliente: String) -> Result<String, GitrError> {
+    if let Some(base) = reference.strip_suffix('^') {
+        let commit = resolve_commit(base.to_string(), cliente.clone())?;
+        return first_parent(commit, reference, cliente);
+    }
+    if let Some((base, n)) = reference.rsplit_once('~') {
+        let n = match n {
+            "" => 1,
+            n => n
+                .parse::<usize>()
+                .map_err(|_| GitrError::NoCommitExisting(reference.clone()))?,
+        };
+        let mut commit = resolve_commit(base.to_string(), cliente.clone())?;
+        for _ in 0..n {
+            commit = first_parent(commit, reference.clone(), cliente.clone())?;
+        }
+        return Ok(commit);
+    }
+    if reference == "HEAD" || reference == "@" {
+        return get_current_commit(cliente).map_err(|_| GitrError::NoCommitExisting(reference));
+    }
     let repo = get_current_repo(cliente.clone())?;
-    if file_path == "." {
-        let files = visit_dirs(std::path::Path::new(&repo));
-        for file in files {
-            if file.contains("gitr") || file.contains("gitrignore") {
-                continue;
-            }
-            save_and_add_blob_to_index(file.clone(), cliente.clone())?;
-
+    let mut ref_paths = vec![
+        format!("{}/gitr/refs/heads/{}", repo, reference),
+        format!("{}/gitr/refs/tags/{}", repo, reference),
+        format!("{}/gitr/refs/remotes/{}", repo, reference),
+    ];
+    if reference.ends_with("HEAD") {
+        ref_paths.push(format!("{}/gitr/{}", repo, reference));
+    }
+    for path in ref_paths {
+        if Path::new(&path).is_file() {
+            let hash = file_manager::read_file(path)?.trim().to_string();
+            return peel_to_commit(hash, reference, cliente);
         }
-    } else {
-        let full_file_path = repo + "/" + &file_path;
-        save_and_add_blob_to_index(full_file_path, cliente)?;
     }
-    Ok(())
+    if reference.len() >= 4 && reference.chars().all(|c| c.is_ascii_hexdigit()) {
+        let hashes = objects_with_prefix(&reference.to_lowercase(), cliente.clone())?;
+        if hashes.len() == 1 {
+            return peel_to_commit(hashes[0].clone(), reference, cliente);
+        }
+    }
+    Err(GitrError::NoCommitExisting(reference))
 }
 
 /***************************
  ***************************
- *   RM FUNCTIONS
+ *    DIFF FUNCTIONS
  **************************
  **************************/
 
-pub fn rm_from_index(file_to_delete: &str, cliente: String) -> Result<bool, GitrError> {
-    let mut removed: bool = false;
-    let mut index = file_manager::read_index(cliente.clone())?;
-    index += "\n";
-    let current_repo = file_manager::get_current_repo(cliente)?;
-    let file_to_rm_path = format!("{}/{}", current_repo, file_to_delete);
-    for line in index.lines() {
-        let attributes = line.split(' ').collect::<Vec<&str>>();
-        if attributes[3] == file_to_rm_path {
-            let complete_line = format!("{}\n", line);
-            index = index.replace(&complete_line, "");
-            let res = index.trim_end().to_string();
-            removed = true;
-            let compressed_index = flate2compress(res)?;
-            let _ = file_manager::write_compressed_data(
-                &(current_repo + "/gitr/index"),
-                compressed_index.as_slice(),
-            );
-            break;
+const DIFF_USAGE: &str = "diff [--cached] [-U<n>] [--color|--no-color] [--stat|--numstat|--shortstat|--name-only|--name-status] [-w|-b] [--ignore-blank-lines] [-M[<n>]|-C[<n>]|--no-renames] [<commit> [<commit>]] [-- <path>...]";
+
+// ancho maximo de la barra de +/- en --stat
+const DIFF_STAT_WIDTH: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiffFormat {
+    Patch,
+    Stat,
+    Numstat,
+    Shortstat,
+    NameOnly,
+    NameStatus,
+}
+
+pub struct DiffOptions {
+    pub cached: bool,
+    pub contexto: usize,
+    pub color: bool,
+    pub formato: DiffFormat,
+    pub comparacion: LineCompare,
+    // umbral de similitud para detectar renames, None si no se detectan
+    pub renames: Option<usize>,
+    pub copies: bool,
+    pub commits: Vec<String>,
+    pub paths: Vec<String>,
+}
+
+// un archivo que cambio entre los dos lados del diff, con (hash, contenido) de cada lado
+pub struct DiffFile {
+    pub path: String,
+    // distinto de path si el archivo fue renombrado o copiado
+    pub old_path: String,
+    pub old: Option<(String, String)>,
+    pub new: Option<(String, String)>,
+    // modo de cada lado, como "100644" o "100755"; vacio si el lado no existe
+    pub old_mode: String,
+    pub new_mode: String,
+    pub similarity: Option<usize>,
+    pub copy: bool,
+    // marcado como binary o -diff en gitrattributes
+    pub binary: bool,
+}
+
+impl DiffFile {
+    pub fn old_data(&self) -> &str {
+        self.old.as_ref().map_or("", |(_, data)| data)
+    }
+
+    pub fn new_data(&self) -> &str {
+        self.new.as_ref().map_or("", |(_, data)| data)
+    }
+
+    pub fn is_binary(&self) -> bool {
+        self.binary || self.old_data().contains('\0') || self.new_data().contains('\0')
+    }
+
+    // A, D, M, R o C como en --name-status
+    pub fn status(&self) -> char {
+        match (&self.old, &self.new, self.similarity) {
+            (_, _, Some(_)) if self.copy => 'C',
+            (_, _, Some(_)) => 'R',
+            (None, _, _) => 'A',
+            (_, None, _) => 'D',
+            _ => 'M',
         }
     }
-    Ok(removed)
+
+    // "old => new" para renames y copias
+    pub fn display_path(&self) -> String {
+        match self.similarity {
+            Some(_) => format!("{} => {}", self.old_path, self.path),
+            None => self.path.clone(),
+        }
+    }
+
+    pub fn hunks(&self, opciones: &DiffOptions) -> Vec<Hunk> {
+        unified_hunks(
+            self.old_data(),
+            self.new_data(),
+            opciones.contexto,
+            opciones.comparacion,
+        )
+    }
+
+    // (lineas agregadas, lineas eliminadas); los binarios no tienen lineas y cuentan 0
+    pub fn numstat(&self, opciones: &DiffOptions) -> (usize, usize) {
+        let (mut agregadas, mut eliminadas) = (0, 0);
+        if self.is_binary() {
+            return (agregadas, eliminadas);
+        }
+        let comparacion = opciones.comparacion;
+        for hunk in unified_hunks(self.old_data(), self.new_data(), 0, comparacion) {
+            for (op, _) in hunk.lineas {
+                match op {
+                    '+' => agregadas += 1,
+                    '-' => eliminadas += 1,
+                    _ => {}
+                }
+            }
+        }
+        (agregadas, eliminadas)
+    }
 }
 
-/***************************
- ***************************
- *    TAG FUNCTIONS
- **************************
- **************************/
+// de donde sale cada lado del diff
+enum DiffSide {
+    Commit(String),
+    Index,
+    WorkingDir,
+}
 
-pub fn create_lightweight_tag(tag_name: String, cliente: String) -> Result<(), GitrError> {
-    let current_commit = match get_current_commit(cliente.clone()) {
-        Ok(commit) => commit,
-        Err(_) => {
-            println!("fatal: Failed to resolve 'HEAD' as a valid ref.");
-            return Ok(());
+pub fn parse_diff_flags(flags: Vec<String>, cliente: String) -> Result<DiffOptions, GitrError> {
+    let invalid = || GitrError::InvalidArgumentError(flags.join(" "), DIFF_USAGE.to_string());
+    let mut opciones = DiffOptions {
+        cached: false,
+        contexto: 3,
+        // con color solo si la salida es una terminal, no al redirigir a un archivo
+        color: std::io::stdout().is_terminal(),
+        formato: DiffFormat::Patch,
+        comparacion: LineCompare::default(),
+        renames: Some(get_rename_threshold(cliente.clone())?),
+        copies: false,
+        commits: vec![],
+        paths: vec![],
+    };
+    let mut solo_paths = false;
+    for flag in flags.iter() {
+        if solo_paths {
+            opciones.paths.push(flag.clone());
+            continue;
+        }
+        match flag.as_str() {
+            "--" => solo_paths = true,
+            "--cached" | "--staged" => opciones.cached = true,
+            "--color" => opciones.color = true,
+            "--no-color" => opciones.color = false,
+            "--stat" => opciones.formato = DiffFormat::Stat,
+            "--numstat" => opciones.formato = DiffFormat::Numstat,
+            "--shortstat" => opciones.formato = DiffFormat::Shortstat,
+            "--name-only" => opciones.formato = DiffFormat::NameOnly,
+            "--name-status" => opciones.formato = DiffFormat::NameStatus,
+            "-w" | "--ignore-all-space" => opciones.comparacion.ignore_all_space = true,
+            "-b" | "--ignore-space-change" => opciones.comparacion.ignore_space_change = true,
+            "--ignore-blank-lines" => opciones.comparacion.ignore_blank_lines = true,
+            "--no-renames" => opciones.renames = None,
+            _ if flag.starts_with("-M") || flag.starts_with("--find-renames") => {
+                let umbral = flag
+                    .trim_start_matches("-M")
+                    .trim_start_matches("--find-renames");
+                opciones.renames =
+                    Some(parse_rename_threshold(umbral, cliente.clone()).ok_or_else(invalid)?);
+            }
+            _ if flag.starts_with("-C") || flag.starts_with("--find-copies") => {
+                let umbral = flag
+                    .trim_start_matches("-C")
+                    .trim_start_matches("--find-copies");
+                opciones.renames =
+                    Some(parse_rename_threshold(umbral, cliente.clone()).ok_or_else(invalid)?);
+                opciones.copies = true;
+            }
+            _ if flag.starts_with("-U") || flag.starts_with("--unified=") => {
+                let n = flag
+                    .trim_start_matches("-U")
+                    .trim_start_matches("--unified=");
+                opciones.contexto = n.parse::<usize>().map_err(|_| invalid())?;
+            }
+            _ if flag.starts_with("--diff-algorithm=") => {
+                let nombre = flag.trim_start_matches("--diff-algorithm=");
+                opciones.comparacion.algoritmo =
+                    DiffAlgorithm::from_name(nombre).ok_or_else(invalid)?;
+            }
+            _ if flag.starts_with('-') => return Err(invalid()),
+            _ => {
+                // <commit>..<commit> solo si los dos lados son commits, como en ../dir
+                let rango = flag.split_once("..").map(|(a, b)| {
+                    [a, b].map(|lado| if lado.is_empty() { "HEAD" } else { lado }.to_string())
+                });
+                match rango.filter(|lados| {
+                    lados
+                        .iter()
+                        .all(|lado| resolve_commit(lado.clone(), cliente.clone()).is_ok())
+                }) {
+                    Some(lados) => opciones.commits.extend(lados),
+                    None => {
+                        let es_archivo =
+                            Path::new(&armar_path(flag.clone(), cliente.clone())?).exists();
+                        if es_archivo && resolve_commit(flag.clone(), cliente.clone()).is_err() {
+                            opciones.paths.push(flag.clone());
+                        } else {
+                            opciones.commits.push(flag.clone());
+                        }
+                    }
+                }
+            }
         }
+    }
+    if opciones.commits.len() > 2 || (opciones.cached && opciones.commits.len() > 1) {
+        return Err(invalid());
+    }
+    Ok(opciones)
+}
+
+// "", "60", "60%" o "=60"
+fn parse_rename_threshold(umbral: &str, cliente: String) -> Option<usize> {
+    let umbral = umbral.trim_start_matches('=').trim_end_matches('%');
+    match umbral {
+        "" => get_rename_threshold(cliente).ok(),
+        umbral => umbral.parse::<usize>().ok().filter(|n| *n <= 100),
+    }
+}
+
+/// Similitud minima para detectar renames en diff, status, log y merge: diff.renameThreshold
+/// de la config (p.ej. "60" o "60%") o RENAME_THRESHOLD si no esta.
+pub fn get_rename_threshold(cliente: String) -> Result<usize, GitrError> {
+    let Some(valor) = Config::load(&cliente)?.get("diff.renameThreshold") else {
+        return Ok(RENAME_THRESHOLD);
     };
-    let tag_path = get_current_repo(cliente.clone())? + "/gitr/refs/tags/" + &tag_name;
-    if Path::new(&tag_path).exists() {
-        return Err(GitrError::TagAlreadyExistsError(tag_name.clone()));
+    match valor.trim().trim_end_matches('%').parse::<usize>() {
+        Ok(umbral) if umbral <= 100 => Ok(umbral),
+        _ => Err(GitrError::ConfigError(tr(
+            "diff.rename_threshold",
+            &[&valor],
+        ))),
     }
-    file_manager::write_file(tag_path, current_commit)?;
-    Ok(())
 }
 
-pub fn create_annotated_tag(
-    tag_name: String,
-    tag_message: String,
-    cliente: String,
-) -> Result<(), GitrError> {
-    let current_commit = match get_current_commit(cliente.clone()) {
-        Ok(commit) => commit,
-        Err(_) => {
-            println!("fatal: Failed to resolve 'HEAD' as a valid ref.");
-            return Ok(());
-        }
+/// Reemplaza los pares de archivo eliminado y agregado parecidos por un rename,
+/// y con copies los agregados parecidos a un archivo modificado o eliminado por una copia.
+pub fn detect_file_renames(files: Vec<DiffFile>, umbral: usize, copies: bool) -> Vec<DiffFile> {
+    let indices = |status: &[char]| {
+        files
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| !f.is_binary() && status.contains(&f.status()))
+            .map(|(i, _)| i)
+            .collect::<Vec<usize>>()
     };
-    let tag_path = get_current_repo(cliente.clone())? + "/gitr/refs/tags/" + &tag_name;
-    if Path::new(&tag_path).exists() {
-        return Err(GitrError::TagAlreadyExistsError(tag_name.clone()));
+    let (eliminados, agregados) = (indices(&['D']), indices(&['A']));
+    let datos_old = |i: &Vec<usize>| {
+        i.iter()
+            .map(|i| files[*i].old_data())
+            .collect::<Vec<&str>>()
+    };
+    let datos_new = |i: &Vec<usize>| {
+        i.iter()
+            .map(|i| files[*i].new_data())
+            .collect::<Vec<&str>>()
+    };
+
+    let mut pares = Vec::new();
+    for (i, j, score) in detect_renames(
+        &datos_old(&eliminados),
+        &datos_new(&agregados),
+        umbral,
+        false,
+    ) {
+        pares.push((eliminados[i], agregados[j], score, false));
     }
-    let tag = Tag::new(tag_name, tag_message, current_commit, cliente.clone())?;
-    tag.save(cliente.clone())?;
-    file_manager::write_file(tag_path, tag.get_hash())?;
-    Ok(())
+    if copies {
+        let usados = pares.iter().map(|p| p.1).collect::<HashSet<usize>>();
+        let agregados = agregados
+            .into_iter()
+            .filter(|j| !usados.contains(j))
+            .collect::<Vec<usize>>();
+        let origenes = indices(&['M', 'D']);
+        for (i, j, score) in
+            detect_renames(&datos_old(&origenes), &datos_new(&agregados), umbral, true)
+        {
+            pares.push((origenes[i], agregados[j], score, true));
+        }
+    }
+
+    let mut consumidos = HashSet::new();
+    let mut nuevos = Vec::new();
+    for (origen, destino, score, copy) in pares {
+        if !copy {
+            consumidos.insert(origen);
+        }
+        consumidos.insert(destino);
+        nuevos.push(DiffFile {
+            path: files[destino].path.clone(),
+            old_path: files[origen].path.clone(),
+            old: files[origen].old.clone(),
+            new: files[destino].new.clone(),
+            old_mode: files[origen].old_mode.clone(),
+            new_mode: files[destino].new_mode.clone(),
+            similarity: Some(score),
+            copy,
+            binary: files[origen].binary || files[destino].binary,
+        });
+    }
+    let mut res = files
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !consumidos.contains(i))
+        .map(|(_, f)| f)
+        .collect::<Vec<DiffFile>>();
+    res.append(&mut nuevos);
+    res.sort_by(|a, b| a.path.cmp(&b.path));
+    res
 }
 
-pub fn get_tags_str(cliente: String) -> Result<String, GitrError> {
-    let tags = file_manager::get_tags(cliente)?;
-    let mut tag_str = String::new();
-    for t in tags {
-        tag_str.push_str(&(t + "\n"))
+fn get_diff_side_hashmap(
+    side: &DiffSide,
+    cliente: String,
+) -> Result<HashMap<String, String>, GitrError> {
+    match side {
+        DiffSide::Commit(commit) => get_commit_hashmap(commit.clone(), cliente),
+        DiffSide::Index => Ok(get_index_hashmap(cliente)?.0),
+        DiffSide::WorkingDir => get_working_dir_hashmap(cliente),
     }
-    Ok(tag_str.strip_suffix('\n').unwrap_or("").to_string())
 }
-/***************************
- ***************************
- *    CLONE FUNCTIONS
- **************************
- **************************/
 
-pub fn write_reference_from_cloning(
-    references: Vec<(String, String)>,
-    _ref_disc: String,
+// los modos de los archivos de un tree, con los mismos paths que get_commit_hashmap
+fn get_tree_modes(
+    tree: &str,
+    directorio: &str,
     cliente: String,
+    modos: &mut HashMap<String, String>,
 ) -> Result<(), GitrError> {
-    let repo = file_manager::get_current_repo(cliente.clone())?;
-    for reference in &references[1..] {
-        let path_str = repo.clone() + "/gitr/" + &reference.1.clone();
-        if references[0].0 == reference.0 {
-            file_manager::update_head(&reference.1.clone(), cliente.clone())?;
+    let (_, body) = read_object_body(tree, cliente.clone())?;
+    for (modo, tipo, hash, nombre) in get_tree_entries_data(&body, cliente.clone())? {
+        let path = format!("{}/{}", directorio, nombre);
+        match tipo.as_str() {
+            "tree" => get_tree_modes(&hash, &path, cliente.clone(), modos)?,
+            _ => {
+                modos.insert(path, modo);
+            }
         }
-        let into_hash = reference.0.clone();
-        file_manager::write_file(path_str, into_hash)?;
     }
     Ok(())
 }
 
-pub fn clone_connect_to_server(address: String) -> Result<TcpStream, GitrError> {
-    let socket = match TcpStream::connect(address) {
-        Ok(socket) => socket,
-        Err(_) => {
+fn get_diff_side_modes(
+    side: &DiffSide,
+    cliente: String,
+) -> Result<HashMap<String, String>, GitrError> {
+    let mut modos = HashMap::new();
+    match side {
+        DiffSide::Commit(commit) if !commit.is_empty() => {
+            let tree = file_manager::get_main_tree(commit.clone(), cliente.clone())?;
+            let repo = get_current_repo(cliente.clone())?;
+            get_tree_modes(&tree, &repo, cliente, &mut modos)?;
+        }
+        DiffSide::Commit(_) => {}
+        DiffSide::Index => {
+            for linea in file_manager::read_index(cliente)
+                .unwrap_or_default()
+                .lines()
+            {
+                let campos = linea.splitn(4, ' ').collect::<Vec<&str>>();
+                if campos.len() == 4 {
+                    modos.insert(campos[3].to_string(), campos[0].to_string());
+                }
+            }
+        }
+        // gitr no guarda el bit de ejecucion en el index ni en los trees, asi que los
+        // archivos del working tree tienen el modo del index
+        DiffSide::WorkingDir => return get_diff_side_modes(&DiffSide::Index, cliente),
+    }
+    Ok(modos)
+}
+
+fn get_diff_side_data(
+    side: &DiffSide,
+    path: &str,
+    hash: Option<&String>,
+    cliente: String,
+) -> Result<Option<(String, String)>, GitrError> {
+    let data = match (side, hash) {
+        (_, None) => return Ok(None),
+        (DiffSide::WorkingDir, Some(_)) => read_worktree_file(path, cliente)?,
+        (_, Some(hash)) => file_manager::read_blob_text(hash.clone(), cliente)?,
+    };
+    Ok(hash.map(|hash| (hash.clone(), data)))
+}
+
+// un path relativo matchea si es alguno de los paths pedidos o esta adentro de alguno
+pub fn diff_path_matches(relative_path: &str, paths: &[String]) -> bool {
+    paths.is_empty()
+        || paths.iter().any(|path| {
+            let path = path.trim_end_matches('/');
+            path == "." || relative_path == path || relative_path.starts_with(&format!("{}/", path))
+        })
+}
+
+/// Devuelve los archivos que cambiaron entre los lados pedidos en las opciones,
+/// ordenados por path.
+pub fn get_diff_files(opciones: &DiffOptions, cliente: String) -> Result<Vec<DiffFile>, GitrError> {
+    let mut commits = Vec::new();
+    for commit in opciones.commits.iter() {
+        commits.push(resolve_commit(commit.clone(), cliente.clone())?);
+    }
+    let head = get_current_commit(cliente.clone()).unwrap_or_default();
+    let (old_side, new_side) = match (commits.as_slice(), opciones.cached) {
+        ([], true) => (DiffSide::Commit(head), DiffSide::Index),
+        ([], false) => (DiffSide::Index, DiffSide::WorkingDir),
+        ([commit], true) => (DiffSide::Commit(commit.clone()), DiffSide::Index),
+        ([commit], false) => (DiffSide::Commit(commit.clone()), DiffSide::WorkingDir),
+        ([a, b], _) => (DiffSide::Commit(a.clone()), DiffSide::Commit(b.clone())),
+        _ => {
             return Err(GitrError::InvalidArgumentError(
-                "address".to_string(),
-                "localhost:9418".to_string(),
+                opciones.commits.join(" "),
+                DIFF_USAGE.to_string(),
             ))
         }
     };
-    Ok(socket)
+    get_diff_files_between(old_side, new_side, opciones, cliente)
 }
 
-pub fn clone_send_git_upload_pack(socket: &mut TcpStream) -> Result<usize, GitrError> {
-    match socket.write("0031git-upload-pack /mi-repo\0host=localhost:9418\0".as_bytes()) {
-        //51 to hexa =
-        Ok(bytes) => Ok(bytes),
-        Err(e) => Err(GitrError::SocketError(
-            "clone_send_git_upload_pack()".to_string(),
-            e.to_string(),
-        )),
+/// Igual que get_diff_files pero entre dos commits ya resueltos. Un commit vacio
+/// se toma como un arbol vacio, para el primer commit del repo.
+pub fn get_commit_diff_files(
+    old_commit: String,
+    new_commit: String,
+    opciones: &DiffOptions,
+    cliente: String,
+) -> Result<Vec<DiffFile>, GitrError> {
+    get_diff_files_between(
+        DiffSide::Commit(old_commit),
+        DiffSide::Commit(new_commit),
+        opciones,
+        cliente,
+    )
+}
+
+fn get_diff_files_between(
+    old_side: DiffSide,
+    new_side: DiffSide,
+    opciones: &DiffOptions,
+    cliente: String,
+) -> Result<Vec<DiffFile>, GitrError> {
+    let old_hashmap = get_diff_side_hashmap(&old_side, cliente.clone())?;
+    let mut new_hashmap = get_diff_side_hashmap(&new_side, cliente.clone())?;
+    if let DiffSide::WorkingDir = new_side {
+        // los archivos untracked no aparecen en el diff
+        let (index_hashmap, _) = get_index_hashmap(cliente.clone())?;
+        new_hashmap
+            .retain(|path, _| index_hashmap.contains_key(path) || old_hashmap.contains_key(path));
+    }
+
+    let (old_modes, new_modes) = (
+        get_diff_side_modes(&old_side, cliente.clone())?,
+        get_diff_side_modes(&new_side, cliente.clone())?,
+    );
+    let modo = |modos: &HashMap<String, String>, path: &String, hash: Option<&String>| match hash {
+        Some(_) => modos.get(path).cloned().unwrap_or("100644".to_string()),
+        None => String::new(),
+    };
+
+    let repo = get_current_repo(cliente.clone())? + "/";
+    let mut paths = old_hashmap
+        .keys()
+        .chain(new_hashmap.keys())
+        .cloned()
+        .collect::<Vec<String>>();
+    paths.sort();
+    paths.dedup();
+
+    let mut files = Vec::new();
+    for path in paths {
+        let relative_path = path.strip_prefix(&repo).unwrap_or(&path);
+        let (old_hash, new_hash) = (old_hashmap.get(&path), new_hashmap.get(&path));
+        let (old_mode, new_mode) = (
+            modo(&old_modes, &path, old_hash),
+            modo(&new_modes, &path, new_hash),
+        );
+        if (old_hash == new_hash && old_mode == new_mode)
+            || !diff_path_matches(relative_path, &opciones.paths)
+        {
+            continue;
+        }
+        files.push(DiffFile {
+            path: relative_path.to_string(),
+            old_path: relative_path.to_string(),
+            old: get_diff_side_data(&old_side, &path, old_hash, cliente.clone())?,
+            new: get_diff_side_data(&new_side, &path, new_hash, cliente.clone())?,
+            old_mode,
+            new_mode,
+            similarity: None,
+            copy: false,
+            binary: get_attributes(&path, cliente.clone())?.get("diff") == Some(&AttrValue::Unset),
+        });
     }
+    Ok(match opciones.renames {
+        Some(umbral) => detect_file_renames(files, umbral, opciones.copies),
+        None => files,
+    })
 }
 
-pub fn clone_read_reference_discovery(socket: &mut TcpStream) -> Result<String, GitrError> {
-    let mut buffer = [0; 1024];
-    let mut response = String::new();
-    loop {
-        let bytes_read = match socket.read(&mut buffer) {
-            Ok(bytes) => bytes,
-            Err(e) => {
-                return Err(GitrError::SocketError(
-                    "clone_read_reference_discovery()".to_string(),
-                    e.to_string(),
-                ))
+/// Devuelve el diff unificado de un archivo, con el header "diff --git" incluido.
+pub fn format_file_diff(file: &DiffFile, opciones: &DiffOptions) -> String {
+    let color = |codigo: &'static str| if opciones.color { codigo } else { "" };
+    let (bold, cyan, red, green, reset) = (
+        color("\x1b[1m"),
+        color("\x1b[36m"),
+        color("\x1b[31m"),
+        color("\x1b[92m"),
+        color("\x1b[0m"),
+    );
+    let mut res = format!("{}diff --git a/{} b/{}\n", bold, file.old_path, file.path);
+    match file.status() {
+        'A' => res.push_str(&format!("new file mode {}\n", file.new_mode)),
+        'D' => res.push_str(&format!("deleted file mode {}\n", file.old_mode)),
+        'R' | 'C' => {
+            let tipo = if file.copy { "copy" } else { "rename" };
+            res.push_str(&format!(
+                "similarity index {}%\n{} from {}\n{} to {}\n",
+                file.similarity.unwrap_or(0),
+                tipo,
+                file.old_path,
+                tipo,
+                file.path
+            ));
+            if file.similarity == Some(100) {
+                return res + reset;
             }
-        };
-        let received_message = String::from_utf8_lossy(&buffer[..bytes_read]).to_string();
-        if bytes_read == 0 || received_message == "0000" {
-            break;
         }
-        response.push_str(&received_message);
+        _ => {}
     }
-    Ok(response)
+    let short_hash = |lado: &Option<(String, String)>| match lado {
+        Some((hash, _)) => hash[..7].to_string(),
+        None => "0000000".to_string(),
+    };
+    let cambio_de_modo = !matches!(file.status(), 'A' | 'D') && file.old_mode != file.new_mode;
+    if cambio_de_modo {
+        res.push_str(&format!(
+            "old mode {}\nnew mode {}\n",
+            file.old_mode, file.new_mode
+        ));
+        if file.old_data() == file.new_data() {
+            return res + reset;
+        }
+    }
+    let modo = match file.status() {
+        'A' | 'D' => String::new(),
+        _ if cambio_de_modo => String::new(),
+        _ => format!(" {}", file.new_mode),
+    };
+    res.push_str(&format!(
+        "index {}..{}{}\n",
+        short_hash(&file.old),
+        short_hash(&file.new),
+        modo
+    ));
+
+    let old_path = file
+        .old
+        .as_ref()
+        .map_or("/dev/null".to_string(), |_| format!("a/{}", file.old_path));
+    let new_path = file
+        .new
+        .as_ref()
+        .map_or("/dev/null".to_string(), |_| format!("b/{}", file.path));
+    if file.is_binary() {
+        res.push_str(&format!(
+            "Binary files {} and {} differ{}\n",
+            old_path, new_path, reset
+        ));
+        return res;
+    }
+    res.push_str(&format!("--- {}\n+++ {}{}\n", old_path, new_path, reset));
+    for hunk in file.hunks(opciones) {
+        res.push_str(&format!("{}{}{}\n", cyan, hunk.header(), reset));
+        for (op, linea) in hunk.lineas {
+            match op {
+                '-' => res.push_str(&format!("{}-{}{}\n", red, linea, reset)),
+                '+' => res.push_str(&format!("{}+{}{}\n", green, linea, reset)),
+                _ => res.push_str(&format!("{}{}\n", op, linea)),
+            }
+        }
+    }
+    res
 }
 
-pub fn write_socket(socket: &mut TcpStream, message: &[u8]) -> Result<(), GitrError> {
-    match socket.write(message) {
-        Ok(_) => Ok(()),
-        Err(e) => Err(GitrError::SocketError(
-            "write_socket()".to_string(),
-            e.to_string(),
-        )),
+// " 2 files changed, 3 insertions(+), 1 deletion(-)"
+fn format_shortstat(archivos: usize, agregadas: usize, eliminadas: usize) -> String {
+    let mut res = match archivos {
+        1 => format!(" {}", tr("diff.file_changed", &[&archivos])),
+        _ => format!(" {}", tr("diff.files_changed", &[&archivos])),
+    };
+    if agregadas > 0 || eliminadas == 0 {
+        let insertions = match agregadas {
+            1 => tr("diff.insertion", &[&agregadas]),
+            _ => tr("diff.insertions", &[&agregadas]),
+        };
+        res.push_str(&format!(", {}", insertions));
     }
+    if eliminadas > 0 || agregadas == 0 {
+        let deletions = match eliminadas {
+            1 => tr("diff.deletion", &[&eliminadas]),
+            _ => tr("diff.deletions", &[&eliminadas]),
+        };
+        res.push_str(&format!(", {}", deletions));
+    }
+    res + "\n"
 }
 
-pub fn read_socket(socket: &mut TcpStream, buffer: &mut [u8]) -> Result<(), GitrError> {
-    let bytes_read = match socket.read(buffer) {
+fn format_stat(files: &[DiffFile], opciones: &DiffOptions) -> String {
+    let stats = files
+        .iter()
+        .map(|file| file.numstat(opciones))
+        .collect::<Vec<(usize, usize)>>();
+    let ancho_path = files
+        .iter()
+        .map(|f| f.display_path().len())
+        .max()
+        .unwrap_or(0);
+    let max_cambios = stats.iter().map(|(a, e)| a + e).max().unwrap_or(0);
+    let ancho_numero = max_cambios.to_string().len().max(3);
+    let (green, red, reset) = match opciones.color {
+        true => ("\x1b[92m", "\x1b[31m", "\x1b[0m"),
+        false => ("", "", ""),
+    };
+
+    let mut res = String::new();
+    for (file, (agregadas, eliminadas)) in files.iter().zip(stats.iter()) {
+        if file.is_binary() {
+            res.push_str(&format!(
+                " {:<ancho_path$} | {:>ancho_numero$} {} -> {} bytes\n",
+                file.display_path(),
+                "Bin",
+                file.old_data().len(),
+                file.new_data().len(),
+            ));
+            continue;
+        }
+        let escalar = |n: usize| match max_cambios > DIFF_STAT_WIDTH {
+            true if n > 0 => (n * DIFF_STAT_WIDTH).div_ceil(max_cambios),
+            true => 0,
+            false => n,
+        };
+        res.push_str(&format!(
+            " {:<ancho_path$} | {:>ancho_numero$} {}{}{}{}{}\n",
+            file.display_path(),
+            agregadas + eliminadas,
+            green,
+            "+".repeat(escalar(*agregadas)),
+            red,
+            "-".repeat(escalar(*eliminadas)),
+            reset,
+        ));
+    }
+    let agregadas = stats.iter().map(|s| s.0).sum();
+    let eliminadas = stats.iter().map(|s| s.1).sum();
+    res + &format_shortstat(files.len(), agregadas, eliminadas)
+}
+
+fn format_name_status(files: &[DiffFile]) -> String {
+    let mut res = String::new();
+    for file in files.iter() {
+        match file.similarity {
+            Some(score) => res.push_str(&format!(
+                "{}{:03}\t{}\t{}\n",
+                file.status(),
+                score,
+                file.old_path,
+                file.path
+            )),
+            None => res.push_str(&format!("{}\t{}\n", file.status(), file.path)),
+        }
+    }
+    res
+}
+
+pub fn get_diff(flags: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    let opciones = parse_diff_flags(flags, cliente.clone())?;
+    let mut files = get_diff_files(&opciones, cliente)?;
+    let comparacion = opciones.comparacion;
+    if comparacion.ignore_all_space
+        || comparacion.ignore_space_change
+        || comparacion.ignore_blank_lines
+    {
+        files.retain(|file| file.is_binary() || !file.hunks(&opciones).is_empty());
+    }
+    Ok(format_diff_files(&files, &opciones))
+}
+
+// arma la salida del diff en el formato pedido en las opciones
+pub fn format_diff_files(files: &[DiffFile], opciones: &DiffOptions) -> String {
+    let mut res = String::new();
+    match opciones.formato {
+        DiffFormat::Patch => {
+            for file in files.iter() {
+                res.push_str(&format_file_diff(file, opciones));
+            }
+        }
+        DiffFormat::Stat if !files.is_empty() => res = format_stat(files, opciones),
+        DiffFormat::Shortstat if !files.is_empty() => {
+            let (agregadas, eliminadas) = files
+                .iter()
+                .map(|file| file.numstat(opciones))
+                .fold((0, 0), |total, (a, e)| (total.0 + a, total.1 + e));
+            res = format_shortstat(files.len(), agregadas, eliminadas);
+        }
+        DiffFormat::Stat | DiffFormat::Shortstat => {}
+        DiffFormat::Numstat => {
+            for file in files.iter() {
+                match file.is_binary() {
+                    true => res.push_str(&format!("-\t-\t{}\n", file.display_path())),
+                    false => {
+                        let (agregadas, eliminadas) = file.numstat(opciones);
+                        res.push_str(&format!(
+                            "{}\t{}\t{}\n",
+                            agregadas,
+                            eliminadas,
+                            file.display_path()
+                        ));
+                    }
+                }
+            }
+        }
+        DiffFormat::NameOnly => {
+            for file in files.iter() {
+                res.push_str(&format!("{}\n", file.path));
+            }
+        }
+        DiffFormat::NameStatus => res = format_name_status(files),
+    }
+    res
+}
+
+/***************************
+ ***************************
+ *    LOG FUNCTIONS
+ **************************
+ **************************/
+
+const LOG_USAGE: &str = "log [-n <count>] [--oneline] [--graph] [--pretty=format:<format>] [--author=<pattern>] [--since=<date>] [--until=<date>] [--grep=<pattern>] [--date-order | --topo-order] [--name-status] [--json] [<commit>...] [-- <path>...]";
+
+#[derive(PartialEq)]
+pub enum LogOrder {
+    // del mas nuevo al mas viejo
+    Date,
+    // por fecha, pero sin mostrar un padre antes que todos sus hijos
+    DateTopo,
+    // sin mezclar las lineas de historia de distintas ramas
+    Topo,
+}
+
+pub enum LogFormat {
+    Medium,
+    Oneline,
+    Format(String),
+    // un array JSON con un objeto por commit, para scripts
+    Json,
+}
+
+pub struct LogOptions {
+    pub limit: Option<usize>,
+    pub formato: LogFormat,
+    pub graph: bool,
+    pub orden: LogOrder,
+    pub author: Option<Regex>,
+    pub grep: Option<Regex>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub name_status: bool,
+    pub commits: Vec<String>,
+    pub paths: Vec<String>,
+}
+
+fn log_argument_error(flag: &str) -> GitrError {
+    GitrError::InvalidArgumentError(flag.to_string(), LOG_USAGE.to_string())
+}
+
+// acepta un timestamp, YYYY-MM-DD o YYYY-MM-DDTHH:MM:SS, en la zona horaria de los commits
+fn parse_log_date(fecha: &str, fin_del_dia: bool) -> Result<i64, GitrError> {
+    if let Ok(timestamp) = fecha.parse::<i64>() {
+        return Ok(timestamp);
+    }
+    let offset = FixedOffset::west_opt(3 * 3600).ok_or(GitrError::TimeError)?;
+    let fecha_hora = match NaiveDate::parse_from_str(fecha, "%Y-%m-%d") {
+        Ok(dia) if fin_del_dia => dia.and_hms_opt(23, 59, 59),
+        Ok(dia) => dia.and_hms_opt(0, 0, 0),
+        Err(_) => NaiveDateTime::parse_from_str(fecha, "%Y-%m-%dT%H:%M:%S").ok(),
+    };
+    match fecha_hora.and_then(|fecha_hora| offset.from_local_datetime(&fecha_hora).single()) {
+        Some(fecha_hora) => Ok(fecha_hora.timestamp()),
+        None => Err(log_argument_error(fecha)),
+    }
+}
+
+pub fn parse_log_flags(flags: Vec<String>) -> Result<LogOptions, GitrError> {
+    let mut opciones = LogOptions {
+        limit: None,
+        formato: LogFormat::Medium,
+        graph: false,
+        orden: LogOrder::Date,
+        author: None,
+        grep: None,
+        since: None,
+        until: None,
+        name_status: false,
+        commits: vec![],
+        paths: vec![],
+    };
+    let con_valor = [
+        "--author",
+        "--grep",
+        "--since",
+        "--after",
+        "--until",
+        "--before",
+        "--max-count",
+        "--pretty",
+        "--format",
+    ];
+    let mut solo_paths = false;
+    let mut i = 0;
+    while i < flags.len() {
+        let mut flag = flags[i].clone();
+        i += 1;
+        if solo_paths {
+            opciones.paths.push(flag);
+            continue;
+        }
+        if (con_valor.contains(&flag.as_str()) || flag == "-n") && i < flags.len() {
+            flag = format!("{}={}", flag, flags[i]);
+            i += 1;
+        }
+        let (clave, valor) = flag.split_once('=').unwrap_or((flag.as_str(), ""));
+        match clave {
+            "--" => solo_paths = true,
+            "--oneline" => opciones.formato = LogFormat::Oneline,
+            "--json" => opciones.formato = LogFormat::Json,
+            "--graph" => opciones.graph = true,
+            "--date-order" => opciones.orden = LogOrder::DateTopo,
+            "--topo-order" => opciones.orden = LogOrder::Topo,
+            "--name-status" => opciones.name_status = true,
+            "-n" | "--max-count" => match valor.parse::<usize>() {
+                Ok(limit) => opciones.limit = Some(limit),
+                Err(_) => return Err(log_argument_error(&flag)),
+            },
+            "--author" if !valor.is_empty() => opciones.author = Some(Regex::new(valor, false)?),
+            "--grep" if !valor.is_empty() => opciones.grep = Some(Regex::new(valor, false)?),
+            "--since" | "--after" => opciones.since = Some(parse_log_date(valor, false)?),
+            "--until" | "--before" => opciones.until = Some(parse_log_date(valor, true)?),
+            "--pretty" => {
+                opciones.formato = match valor {
+                    "oneline" => LogFormat::Oneline,
+                    "medium" | "" => LogFormat::Medium,
+                    _ => match valor
+                        .strip_prefix("format:")
+                        .or(valor.strip_prefix("tformat:"))
+                    {
+                        Some(formato) => LogFormat::Format(formato.to_string()),
+                        None => return Err(log_argument_error(&flag)),
+                    },
+                }
+            }
+            "--format" => opciones.formato = LogFormat::Format(valor.to_string()),
+            _ if clave.starts_with('-') => return Err(log_argument_error(&flag)),
+            _ => opciones.commits.push(flag),
+        }
+    }
+    if opciones.graph && matches!(opciones.formato, LogFormat::Json) {
+        return Err(log_argument_error("--graph --json"));
+    }
+    if opciones.graph && opciones.orden == LogOrder::Date {
+        opciones.orden = LogOrder::Topo;
+    }
+    Ok(opciones)
+}
+
+fn get_log_parents(commit: &str, cliente: String) -> Result<Vec<String>, GitrError> {
+    Ok(
+        file_manager::get_parent_commit(commit.to_string(), cliente)?
+            .into_iter()
+            .filter(|parent| parent != "None")
+            .collect(),
+    )
+}
+
+// reordena los commits (que vienen ordenados por fecha) para que ningun padre quede antes
+// que sus hijos. Con por_fecha elige siempre el mas nuevo de los que ya se pueden mostrar,
+// si no sigue la rama del ultimo commit mostrado
+fn topo_order(
+    commits: Vec<String>,
+    por_fecha: bool,
+    cliente: String,
+) -> Result<Vec<String>, GitrError> {
+    let posiciones: HashMap<String, usize> = commits
+        .iter()
+        .enumerate()
+        .map(|(i, commit)| (commit.clone(), i))
+        .collect();
+    let mut padres = Vec::new();
+    let mut hijos_pendientes = vec![0; commits.len()];
+    for commit in commits.iter() {
+        let parents = get_log_parents(commit, cliente.clone())?
+            .iter()
+            .filter_map(|parent| posiciones.get(parent).copied())
+            .collect::<Vec<usize>>();
+        for parent in parents.iter() {
+            hijos_pendientes[*parent] += 1;
+        }
+        padres.push(parents);
+    }
+
+    let mut listos = (0..commits.len())
+        .rev()
+        .filter(|i| hijos_pendientes[*i] == 0)
+        .collect::<Vec<usize>>();
+    let mut res = Vec::new();
+    loop {
+        let siguiente = match por_fecha {
+            true => {
+                let mas_nuevo = listos
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, posicion)| **posicion)
+                    .map(|(i, _)| i);
+                mas_nuevo.map(|i| listos.remove(i))
+            }
+            false => listos.pop(),
+        };
+        let Some(siguiente) = siguiente else {
+            break;
+        };
+        res.push(commits[siguiente].clone());
+        for parent in padres[siguiente].iter().rev() {
+            hijos_pendientes[*parent] -= 1;
+            if hijos_pendientes[*parent] == 0 {
+                listos.push(*parent);
+            }
+        }
+    }
+    Ok(res)
+}
+
+// si el commit cambio alguno de los paths respecto de todos sus padres
+fn commit_touches_paths(
+    commit: &str,
+    paths: &[String],
+    cliente: String,
+) -> Result<bool, GitrError> {
+    let repo = get_current_repo(cliente.clone())? + "/";
+    let matchea =
+        |path: &String| diff_path_matches(path.strip_prefix(&repo).unwrap_or(path), paths);
+    let actual = get_commit_hashmap(commit.to_string(), cliente.clone())?;
+    let mut padres = Vec::new();
+    for parent in get_log_parents(commit, cliente.clone())? {
+        padres.push(get_commit_hashmap(parent, cliente.clone())?);
+    }
+    if padres.is_empty() {
+        padres.push(HashMap::new());
+    }
+    Ok(padres.iter().all(|padre| {
+        actual
+            .iter()
+            .any(|(path, hash)| padre.get(path) != Some(hash) && matchea(path))
+            || padre
+                .keys()
+                .any(|path| !actual.contains_key(path) && matchea(path))
+    }))
+}
+
+fn log_commit_matches(
+    commit: &str,
+    opciones: &LogOptions,
+    cliente: String,
+) -> Result<bool, GitrError> {
+    let timestamp = file_manager::get_commit_timestamp(commit.to_string(), cliente.clone())?;
+    if opciones.since.is_some_and(|since| timestamp < since)
+        || opciones.until.is_some_and(|until| timestamp > until)
+    {
+        return Ok(false);
+    }
+    if let Some(author) = &opciones.author {
+        let nombre = file_manager::get_commit_author(commit.to_string(), cliente.clone())?;
+        let mail = file_manager::get_commit_author_mail(commit.to_string(), cliente.clone())?;
+        if !author.is_match(&nombre) && !author.is_match(&mail) {
+            return Ok(false);
+        }
+    }
+    if let Some(grep) = &opciones.grep {
+        let message = file_manager::get_commit_message(commit.to_string(), cliente.clone())?;
+        if !grep.is_match(&message) {
+            return Ok(false);
+        }
+    }
+    if !opciones.paths.is_empty() && !commit_touches_paths(commit, &opciones.paths, cliente)? {
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+// (titulo, cuerpo) del mensaje del commit
+fn get_log_subject_and_body(commit: &str, cliente: String) -> Result<(String, String), GitrError> {
+    let message = file_manager::get_commit_message(commit.to_string(), cliente)?;
+    let message = message.trim();
+    let (subject, body) = message.split_once('\n').unwrap_or((message, ""));
+    Ok((subject.trim().to_string(), body.trim().to_string()))
+}
+
+// reemplaza los placeholders de --pretty=format: (%H %h %P %p %an %ae %ad %at %s %b %n %%)
+fn format_log_placeholders(
+    formato: &str,
+    commit: &str,
+    cliente: String,
+) -> Result<String, GitrError> {
+    let mut res = String::new();
+    let mut chars = formato.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            res.push(c);
+            continue;
+        }
+        let placeholder = match chars.next() {
+            Some('a') => match chars.peek() {
+                Some('n' | 'e' | 'd' | 't') => format!("a{}", chars.next().unwrap_or_default()),
+                _ => "a".to_string(),
+            },
+            Some(c) => c.to_string(),
+            None => String::new(),
+        };
+        let commit = commit.to_string();
+        match placeholder.as_str() {
+            "H" => res.push_str(&commit),
+            "h" => res.push_str(&commit[..7.min(commit.len())]),
+            "P" | "p" => {
+                let largo = if placeholder == "P" { 40 } else { 7 };
+                let parents = get_log_parents(&commit, cliente.clone())?
+                    .iter()
+                    .map(|parent| parent[..largo.min(parent.len())].to_string())
+                    .collect::<Vec<String>>();
+                res.push_str(&parents.join(" "));
+            }
+            "an" => res.push_str(&file_manager::get_commit_author(commit, cliente.clone())?),
+            "ae" => res.push_str(&file_manager::get_commit_author_mail(
+                commit,
+                cliente.clone(),
+            )?),
+            "ad" => res.push_str(&file_manager::get_commit_date(commit, cliente.clone())?),
+            "at" => res.push_str(
+                &file_manager::get_commit_timestamp(commit, cliente.clone())?.to_string(),
+            ),
+            "s" => res.push_str(&get_log_subject_and_body(&commit, cliente.clone())?.0),
+            "b" => res.push_str(&get_log_subject_and_body(&commit, cliente.clone())?.1),
+            "n" => res.push('\n'),
+            "%" => res.push('%'),
+            _ => res.push_str(&format!("%{}", placeholder)),
+        }
+    }
+    Ok(res)
+}
+
+// archivos cambiados por el commit respecto de su primer padre, para --name-status
+fn get_log_name_status_files(
+    commit: &str,
+    opciones: &LogOptions,
+    cliente: String,
+) -> Result<Vec<DiffFile>, GitrError> {
+    let diff_opciones = DiffOptions {
+        cached: false,
+        contexto: 3,
+        color: false,
+        formato: DiffFormat::NameStatus,
+        comparacion: LineCompare::default(),
+        renames: Some(get_rename_threshold(cliente.clone())?),
+        copies: false,
+        commits: vec![],
+        paths: opciones.paths.clone(),
+    };
+    let parent = get_log_parents(commit, cliente.clone())?
+        .first()
+        .cloned()
+        .unwrap_or_default();
+    get_commit_diff_files(parent, commit.to_string(), &diff_opciones, cliente)
+}
+
+// la entrada de log --json de un commit
+fn format_log_json(
+    commit: &str,
+    opciones: &LogOptions,
+    cliente: String,
+) -> Result<serde_json::Value, GitrError> {
+    let (subject, body) = get_log_subject_and_body(commit, cliente.clone())?;
+    let mut entrada = serde_json::json!({
+        "hash": commit,
+        "parents": get_log_parents(commit, cliente.clone())?,
+        "author": file_manager::get_commit_author(commit.to_string(), cliente.clone())?,
+        "email": file_manager::get_commit_author_mail(commit.to_string(), cliente.clone())?,
+        "timestamp": file_manager::get_commit_timestamp(commit.to_string(), cliente.clone())?,
+        "subject": subject,
+        "body": body,
+    });
+    if opciones.name_status {
+        let files = get_log_name_status_files(commit, opciones, cliente)?
+            .iter()
+            .map(|file| {
+                let mut archivo = serde_json::json!({
+                    "status": file.status().to_string(),
+                    "path": file.path,
+                });
+                if file.similarity.is_some() {
+                    archivo["old_path"] = serde_json::json!(file.old_path);
+                    archivo["similarity"] = serde_json::json!(file.similarity);
+                }
+                archivo
+            })
+            .collect::<Vec<serde_json::Value>>();
+        entrada["files"] = serde_json::json!(files);
+    }
+    Ok(entrada)
+}
+
+fn format_log_entry(
+    commit: &str,
+    opciones: &LogOptions,
+    cliente: String,
+) -> Result<String, GitrError> {
+    let mut res = match &opciones.formato {
+        LogFormat::Json => return Ok(format_log_json(commit, opciones, cliente)?.to_string()),
+        LogFormat::Medium => {
+            file_manager::format_commit_log_entry(commit.to_string(), cliente.clone())?
+        }
+        LogFormat::Oneline => format!(
+            "{} {}\n",
+            &commit[..7.min(commit.len())],
+            get_log_subject_and_body(commit, cliente.clone())?.0
+        ),
+        LogFormat::Format(formato) => {
+            format_log_placeholders(formato, commit, cliente.clone())? + "\n"
+        }
+    };
+    if opciones.name_status {
+        let files = get_log_name_status_files(commit, opciones, cliente)?;
+        res.push_str(&format_name_status(&files));
+        res.push('\n');
+    }
+    Ok(res)
+}
+
+// columnas del grafo de log --graph, con el commit que se espera en cada una
+#[derive(Default)]
+struct LogGraph {
+    columnas: Vec<String>,
+}
+
+impl LogGraph {
+    // dibuja una linea de conexion: '|' en las columnas anteriores a desde y el caracter
+    // recibido, corrido media columna, en las que se desplazan. La columna omitida no se dibuja
+    fn conexion(
+        columnas: usize,
+        desde: usize,
+        omitida: Option<usize>,
+        c: char,
+        corrimiento: isize,
+    ) -> String {
+        let mut linea = vec![' '; 2 * columnas + 1];
+        for k in (0..columnas).filter(|k| Some(*k) != omitida) {
+            match k < desde {
+                true => linea[2 * k] = '|',
+                false => linea[(2 * k as isize + corrimiento) as usize] = c,
+            }
+        }
+        linea.into_iter().collect()
+    }
+
+    // devuelve las lineas de texto de la entrada con el grafo adelante
+    fn dibujar(&mut self, commit: &str, parents: Vec<String>, texto: &str) -> String {
+        let idx = match self.columnas.iter().position(|c| c == commit) {
+            Some(idx) => idx,
+            None => {
+                self.columnas.push(commit.to_string());
+                self.columnas.len() - 1
+            }
+        };
+        let mut prefijos = vec![(0..self.columnas.len())
+            .map(|k| if k == idx { "*" } else { "|" })
+            .collect::<Vec<&str>>()
+            .join(" ")];
+
+        let nuevos = parents
+            .iter()
+            .skip(1)
+            .filter(|parent| !self.columnas.contains(parent) && Some(*parent) != parents.first())
+            .cloned()
+            .collect::<Vec<String>>();
+        match parents.first() {
+            Some(parent) => self.columnas[idx] = parent.clone(),
+            None => {
+                self.columnas.remove(idx);
+                if idx < self.columnas.len() {
+                    prefijos.push(Self::conexion(
+                        self.columnas.len() + 1,
+                        idx,
+                        Some(idx),
+                        '/',
+                        -1,
+                    ));
+                }
+            }
+        }
+        if !nuevos.is_empty() {
+            let mut linea = Self::conexion(self.columnas.len(), idx + 1, None, '\\', 1);
+            linea.replace_range(2 * idx + 1..2 * idx + 2, "\\");
+            prefijos.push(linea);
+            for (k, parent) in nuevos.into_iter().enumerate() {
+                self.columnas.insert(idx + 1 + k, parent);
+            }
+        }
+        // dos columnas esperando el mismo commit se juntan
+        while let Some(j) =
+            (1..self.columnas.len()).find(|j| self.columnas[..*j].contains(&self.columnas[*j]))
+        {
+            prefijos.push(Self::conexion(self.columnas.len(), j, None, '/', -1));
+            self.columnas.remove(j);
+        }
+
+        let rieles = vec!["|"; self.columnas.len()].join(" ");
+        let ancho = prefijos
+            .iter()
+            .map(|p| p.trim_end().len())
+            .chain([rieles.len()])
+            .max()
+            .unwrap_or(0);
+        let lineas = texto.lines().collect::<Vec<&str>>();
+        let mut res = String::new();
+        for i in 0..lineas.len().max(prefijos.len()) {
+            let prefijo = prefijos.get(i).unwrap_or(&rieles);
+            let linea = format!(
+                "{:<ancho$} {}",
+                prefijo.trim_end(),
+                lineas.get(i).unwrap_or(&"")
+            );
+            res.push_str(linea.trim_end());
+            res.push('\n');
+        }
+        res
+    }
+}
+
+/// Arma la salida de log recorriendo todo el grafo de commits desde HEAD o los commits pedidos.
+pub fn get_log(flags: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    let opciones = parse_log_flags(flags)?;
+    let mut inicios = Vec::new();
+    for commit in opciones.commits.iter() {
+        inicios.push(resolve_commit(commit.clone(), cliente.clone())?);
+    }
+    if inicios.is_empty() {
+        inicios.push(get_current_commit(cliente.clone())?);
+    }
+    let mut commits = file_manager::rev_list(inicios, cliente.clone())?;
+    if opciones.orden != LogOrder::Date {
+        commits = topo_order(
+            commits,
+            opciones.orden == LogOrder::DateTopo,
+            cliente.clone(),
+        )?;
+    }
+
+    let mut seleccionados = Vec::new();
+    for commit in commits {
+        if opciones
+            .limit
+            .is_some_and(|limit| seleccionados.len() >= limit)
+        {
+            break;
+        }
+        if log_commit_matches(&commit, &opciones, cliente.clone())? {
+            seleccionados.push(commit);
+        }
+    }
+
+    if let LogFormat::Json = opciones.formato {
+        let mut entradas = Vec::new();
+        for commit in seleccionados.iter() {
+            entradas.push(format_log_entry(commit, &opciones, cliente.clone())?);
+        }
+        return Ok(format!("[{}]\n", entradas.join(",")));
+    }
+
+    let mostrados = seleccionados.iter().collect::<HashSet<&String>>();
+    let mut grafo = LogGraph::default();
+    let mut res = String::new();
+    for commit in seleccionados.iter() {
+        let texto = format_log_entry(commit, &opciones, cliente.clone())?;
+        match opciones.graph {
+            true => {
+                let parents = get_log_parents(commit, cliente.clone())?
+                    .into_iter()
+                    .filter(|parent| mostrados.contains(parent))
+                    .collect();
+                res.push_str(&grafo.dibujar(commit, parents, &texto));
+            }
+            false => res.push_str(&texto),
+        }
+    }
+    Ok(res)
+}
+
+/***************************
+ ***************************
+ *    SHOW FUNCTIONS
+ **************************
+ **************************/
+
+const SHOW_USAGE: &str = "show [--no-patch] [<diff options>] [<object>...] [<commit>:<path>]";
+
+// (tipo, contenido sin header) de un objeto
+fn read_object_body(hash: &str, cliente: String) -> Result<(String, String), GitrError> {
+    let data = file_manager::read_object(&hash.to_string(), get_current_repo(cliente)?, true)
+        .map_err(|_| GitrError::ObjectNotFound(hash.to_string()))?;
+    match data.split_once('\0') {
+        Some((header, body)) => Ok((
+            header.split(' ').next().unwrap_or_default().to_string(),
+            body.to_string(),
+        )),
+        None => Err(GitrError::ObjectNotFound(hash.to_string())),
+    }
+}
+
+// (modo, tipo, hash, nombre) de cada entrada de un tree
+fn get_tree_entries_data(
+    body: &str,
+    cliente: String,
+) -> Result<Vec<(String, String, String, String)>, GitrError> {
+    Ok(get_tree_data(body, cliente)?
+        .lines()
+        .filter_map(|entry| {
+            let mut campos = entry.splitn(4, ' ').map(|campo| campo.to_string());
+            Some((
+                campos.next()?,
+                campos.next()?,
+                campos.next()?,
+                campos.next()?,
+            ))
+        })
+        .collect())
+}
+
+/// Resuelve un objeto de cualquier tipo: <commit>:<path>, un tag (sin pelar), un hash
+/// o prefijo de hash, o cualquier referencia que acepte resolve_commit.
+pub fn resolve_object(reference: String, cliente: String) -> Result<String, GitrError> {
+    if let Some((rev, path)) = reference.split_once(':') {
+        let commit = resolve_commit(rev.to_string(), cliente.clone())?;
+        let mut hash = file_manager::get_main_tree(commit, cliente.clone())?;
+        for nombre in path.split('/').filter(|nombre| !nombre.is_empty()) {
+            let (_, body) = read_object_body(&hash, cliente.clone())?;
+            hash = match get_tree_entries_data(&body, cliente.clone())?
+                .into_iter()
+                .find(|(_, _, _, entry)| entry == nombre)
+            {
+                Some((_, _, entry_hash, _)) => entry_hash,
+                None => return Err(GitrError::ObjectNotFound(reference)),
+            };
+        }
+        return Ok(hash);
+    }
+    let tag_path = format!(
+        "{}/gitr/refs/tags/{}",
+        get_current_repo(cliente.clone())?,
+        reference
+    );
+    if Path::new(&tag_path).is_file() {
+        return Ok(file_manager::read_file(tag_path)?.trim().to_string());
+    }
+    if reference.len() >= 4 && reference.chars().all(|c| c.is_ascii_hexdigit()) {
+        let hashes = objects_with_prefix(&reference.to_lowercase(), cliente.clone())?;
+        if hashes.len() == 1 {
+            return Ok(hashes[0].clone());
+        }
+    }
+    resolve_commit(reference, cliente)
+}
+
+// diff combinado de un merge: solo los archivos que difieren de todos los padres
+fn format_combined_diff(
+    commit: &str,
+    parents: &[String],
+    opciones: &DiffOptions,
+    cliente: String,
+) -> Result<String, GitrError> {
+    let color = |codigo: &'static str| if opciones.color { codigo } else { "" };
+    let (bold, cyan, red, green, reset) = (
+        color("\x1b[1m"),
+        color("\x1b[36m"),
+        color("\x1b[31m"),
+        color("\x1b[92m"),
+        color("\x1b[0m"),
+    );
+    let repo = get_current_repo(cliente.clone())? + "/";
+    let resultado = get_commit_hashmap(commit.to_string(), cliente.clone())?;
+    let mut padres = Vec::new();
+    for parent in parents {
+        padres.push(get_commit_hashmap(parent.clone(), cliente.clone())?);
+    }
+    let mut paths = resultado
+        .keys()
+        .chain(padres.iter().flat_map(|padre| padre.keys()))
+        .filter(|path| {
+            padres
+                .iter()
+                .all(|padre| padre.get(*path) != resultado.get(*path))
+        })
+        .cloned()
+        .collect::<Vec<String>>();
+    paths.sort();
+    paths.dedup();
+
+    let mut res = String::new();
+    for path in paths {
+        let relative_path = path.strip_prefix(&repo).unwrap_or(&path);
+        let leer = |hash: Option<&String>| match hash {
+            Some(hash) => file_manager::read_blob_text(hash.clone(), cliente.clone()),
+            None => Ok(String::new()),
+        };
+        let mut datos_padres = Vec::new();
+        for padre in padres.iter() {
+            datos_padres.push(leer(padre.get(&path))?);
+        }
+        let datos_resultado = leer(resultado.get(&path))?;
+        let short_hash =
+            |hash: Option<&String>| hash.map_or("0000000".to_string(), |h| h[..7].to_string());
+        res.push_str(&format!("{}diff --combined {}\n", bold, relative_path));
+        res.push_str(&format!(
+            "index {}..{}\n",
+            padres
+                .iter()
+                .map(|padre| short_hash(padre.get(&path)))
+                .collect::<Vec<String>>()
+                .join(","),
+            short_hash(resultado.get(&path))
+        ));
+        res.push_str(&format!(
+            "--- a/{}\n+++ b/{}{}\n",
+            relative_path, relative_path, reset
+        ));
+        let datos_padres = datos_padres
+            .iter()
+            .map(|d| d.as_str())
+            .collect::<Vec<&str>>();
+        for hunk in combined_hunks(&datos_padres, &datos_resultado, opciones.contexto) {
+            res.push_str(&format!("{}{}{}\n", cyan, hunk.header(), reset));
+            for (columnas, linea) in hunk.lineas {
+                let color = match (columnas.contains('-'), columnas.contains('+')) {
+                    (true, _) => red,
+                    (_, true) => green,
+                    _ => "",
+                };
+                let reset = if color.is_empty() { "" } else { reset };
+                res.push_str(&format!("{}{}{}{}\n", color, columnas, linea, reset));
+            }
+        }
+    }
+    Ok(res)
+}
+
+// muestra un commit con su diff, un tag seguido del objeto al que apunta, un tree o un blob
+fn show_object(
+    hash: &str,
+    opciones: &DiffOptions,
+    con_diff: bool,
+    cliente: String,
+) -> Result<String, GitrError> {
+    let (tipo, body) = read_object_body(hash, cliente.clone())?;
+    match tipo.as_str() {
+        "commit" => {
+            let mut res = file_manager::format_commit_log_entry(hash.to_string(), cliente.clone())?;
+            if !con_diff {
+                return Ok(res);
+            }
+            let parents = get_log_parents(hash, cliente.clone())?;
+            if parents.len() > 1 && opciones.formato == DiffFormat::Patch {
+                res.push_str(&format_combined_diff(hash, &parents, opciones, cliente)?);
+            } else {
+                let parent = parents.first().cloned().unwrap_or_default();
+                let files = get_commit_diff_files(parent, hash.to_string(), opciones, cliente)?;
+                res.push_str(&format_diff_files(&files, opciones));
+            }
+            Ok(res)
+        }
+        "tag" => {
+            let (mut res, objeto) = get_tag_data(&body)?;
+            res.push('\n');
+            res.push_str(&show_object(&objeto, opciones, con_diff, cliente)?);
+            Ok(res)
+        }
+        "tree" => get_tree_data(&body, cliente),
+        "blob" => Ok(file_manager::blob_data_to_utf8(&body)),
+        _ => Ok(body),
+    }
+}
+
+/// Arma la salida de show para cada objeto pedido (HEAD si no se pide ninguno).
+pub fn get_show(flags: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    let mut diff_flags = Vec::new();
+    let mut objetos = Vec::new();
+    let mut con_diff = true;
+    for flag in flags {
+        match flag.as_str() {
+            "-s" | "--no-patch" => con_diff = false,
+            _ if flag.starts_with('-') => diff_flags.push(flag),
+            _ => objetos.push(flag),
+        }
+    }
+    let opciones = parse_diff_flags(diff_flags.clone(), cliente.clone()).map_err(|_| {
+        GitrError::InvalidArgumentError(diff_flags.join(" "), SHOW_USAGE.to_string())
+    })?;
+    if objetos.is_empty() {
+        objetos.push("HEAD".to_string());
+    }
+    let mut res = String::new();
+    for objeto in objetos {
+        let hash = resolve_object(objeto, cliente.clone())?;
+        res.push_str(&show_object(&hash, &opciones, con_diff, cliente.clone())?);
+    }
+    Ok(res)
+}
+
+/***************************
+ ***************************
+ *    BLAME FUNCTIONS
+ **************************
+ **************************/
+
+const BLAME_USAGE: &str = "blame <file> [<commit>]";
+
+// contenido del archivo en el commit, None si el commit no lo tiene
+fn get_file_at_commit(
+    commit: &str,
+    full_path: &str,
+    cliente: String,
+) -> Result<Option<String>, GitrError> {
+    match get_commit_hashmap(commit.to_string(), cliente.clone())?.get(full_path) {
+        Some(hash) => Ok(Some(file_manager::read_blob_text(hash.clone(), cliente)?)),
+        None => Ok(None),
+    }
+}
+
+/// Devuelve, para cada linea del archivo en el commit, el commit que la cambio por ultima vez.
+pub fn blame_lines(
+    file: &str,
+    commit: String,
+    cliente: String,
+) -> Result<Vec<(String, String)>, GitrError> {
+    let full_path = format!("{}/{}", get_current_repo(cliente.clone())?, file);
+    let contenido = match get_file_at_commit(&commit, &full_path, cliente.clone())? {
+        Some(contenido) => contenido,
+        None => return Err(GitrError::ObjectNotFound(format!("{}:{}", commit, file))),
+    };
+    let lineas = contenido
+        .lines()
+        .map(|l| l.to_string())
+        .collect::<Vec<String>>();
+    let mut autores = vec![String::new(); lineas.len()];
+
+    // (commit, contenido en ese commit, (linea en ese commit, linea final) sin asignar)
+    let mut pendientes = vec![(
+        commit,
+        contenido,
+        (0..lineas.len())
+            .map(|i| (i, i))
+            .collect::<Vec<(usize, usize)>>(),
+    )];
+    while let Some((commit, contenido, mut lineas_pendientes)) = pendientes.pop() {
+        for parent in get_log_parents(&commit, cliente.clone())? {
+            if lineas_pendientes.is_empty() {
+                break;
+            }
+            let Some(contenido_padre) = get_file_at_commit(&parent, &full_path, cliente.clone())?
+            else {
+                continue;
+            };
+            let coincidencias = matching_lines(&contenido_padre, &contenido);
+            let (mut heredadas, mut propias) = (Vec::new(), Vec::new());
+            for (linea, final_) in lineas_pendientes {
+                match coincidencias.get(linea).copied().flatten() {
+                    Some(linea_padre) => heredadas.push((linea_padre, final_)),
+                    None => propias.push((linea, final_)),
+                }
+            }
+            if !heredadas.is_empty() {
+                pendientes.push((parent, contenido_padre, heredadas));
+            }
+            lineas_pendientes = propias;
+        }
+        for (_, final_) in lineas_pendientes {
+            autores[final_] = commit.clone();
+        }
+    }
+    Ok(autores.into_iter().zip(lineas).collect())
+}
+
+/// Arma la salida de blame: hash corto, autor, fecha, numero de linea y la linea.
+pub fn get_blame(flags: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    if flags.is_empty() || flags.len() > 2 {
+        return Err(GitrError::InvalidArgumentError(
+            flags.join(" "),
+            BLAME_USAGE.to_string(),
+        ));
+    }
+    let commit = match flags.get(1) {
+        Some(rev) => resolve_commit(rev.clone(), cliente.clone())?,
+        None => get_current_commit(cliente.clone())?,
+    };
+    let lineas = blame_lines(&flags[0], commit, cliente.clone())?;
+
+    let mut datos: HashMap<String, (String, String)> = HashMap::new();
+    for (commit, _) in lineas.iter() {
+        if !datos.contains_key(commit) {
+            let author = file_manager::get_commit_author(commit.clone(), cliente.clone())?;
+            let date = file_manager::get_commit_date(commit.clone(), cliente.clone())?;
+            datos.insert(commit.clone(), (author, date));
+        }
+    }
+    let ancho_autor = datos
+        .values()
+        .map(|(author, _)| author.len())
+        .max()
+        .unwrap_or(0);
+    let ancho_numero = lineas.len().to_string().len();
+    let mut res = String::new();
+    for (i, (commit, linea)) in lineas.iter().enumerate() {
+        let (author, date) = &datos[commit];
+        res.push_str(&format!(
+            "{} ({:<ancho_autor$} {} {:>ancho_numero$}) {}\n",
+            &commit[..7],
+            author,
+            date,
+            i + 1,
+            linea
+        ));
+    }
+    Ok(res)
+}
+
+/***************************
+ ***************************
+ *    GREP FUNCTIONS
+ **************************
+ **************************/
+
+const GREP_USAGE: &str =
+    "grep [-n] [-i] [-l] [-c] [-E | -F] [--cached] [-e] <pattern> [<rev>] [-- <paths>]";
+
+struct GrepOptions {
+    patron: Option<String>,
+    line_number: bool,
+    ignore_case: bool,
+    files_with_matches: bool,
+    count: bool,
+    fixed_strings: bool,
+    cached: bool,
+    revs: Vec<String>,
+    paths: Vec<String>,
+}
+
+// el primer argumento que no es flag es el patron, los siguientes son revisiones
+// hasta el "--" (o paths, si no se pueden resolver como commit)
+fn parse_grep_flags(flags: &[String], cliente: String) -> Result<GrepOptions, GitrError> {
+    let error = || GitrError::InvalidArgumentError(flags.join(" "), GREP_USAGE.to_string());
+    let mut opciones = GrepOptions {
+        patron: None,
+        line_number: false,
+        ignore_case: false,
+        files_with_matches: false,
+        count: false,
+        fixed_strings: false,
+        cached: false,
+        revs: Vec::new(),
+        paths: Vec::new(),
+    };
+    let mut args = flags.iter();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "-n" | "--line-number" => opciones.line_number = true,
+            "-i" | "--ignore-case" => opciones.ignore_case = true,
+            "-l" | "--files-with-matches" => opciones.files_with_matches = true,
+            "-c" | "--count" => opciones.count = true,
+            "-E" | "--extended-regexp" => opciones.fixed_strings = false,
+            "-F" | "--fixed-strings" => opciones.fixed_strings = true,
+            "--cached" => opciones.cached = true,
+            "-e" => match (args.next(), &opciones.patron) {
+                (Some(patron), None) => opciones.patron = Some(patron.clone()),
+                _ => return Err(error()),
+            },
+            "--" => {
+                opciones.paths.extend(args.by_ref().cloned());
+            }
+            flag if flag.starts_with('-') && flag.len() > 1 => return Err(error()),
+            arg => {
+                if opciones.patron.is_none() {
+                    opciones.patron = Some(arg.to_string());
+                } else if opciones.paths.is_empty()
+                    && resolve_commit(arg.to_string(), cliente.clone()).is_ok()
+                {
+                    opciones.revs.push(arg.to_string());
+                } else {
+                    opciones.paths.push(arg.to_string());
+                }
+            }
+        }
+    }
+    if opciones.patron.is_none() || (opciones.cached && !opciones.revs.is_empty()) {
+        return Err(error());
+    }
+    Ok(opciones)
+}
+
+// archivos a buscar como (nombre a mostrar, contenido), ordenados por path
+fn get_grep_sources(
+    opciones: &GrepOptions,
+    cliente: String,
+) -> Result<Vec<(String, String)>, GitrError> {
+    let repo = file_manager::get_current_repo(cliente.clone())?;
+    let relativo = |path: &String| path[repo.len() + 1..].to_string();
+    let mut fuentes = Vec::new();
+    if opciones.revs.is_empty() {
+        let (index, _) = get_index_hashmap(cliente.clone())?;
+        let mut paths = index.keys().collect::<Vec<&String>>();
+        paths.sort();
+        for path in paths {
+            if !diff_path_matches(&relativo(path), &opciones.paths) {
+                continue;
+            }
+            let contenido = match opciones.cached {
+                true => file_manager::read_blob_text(index[path].clone(), cliente.clone())?,
+                false => match fs::read(path) {
+                    Ok(bytes) => String::from_utf8_lossy(&bytes).to_string(),
+                    Err(_) => continue,
+                },
+            };
+            fuentes.push((relativo(path), contenido));
+        }
+    }
+    for rev in opciones.revs.iter() {
+        let commit = resolve_commit(rev.clone(), cliente.clone())?;
+        let archivos = get_commit_hashmap(commit, cliente.clone())?;
+        let mut paths = archivos.keys().collect::<Vec<&String>>();
+        paths.sort();
+        for path in paths {
+            if diff_path_matches(&relativo(path), &opciones.paths) {
+                let contenido =
+                    file_manager::read_blob_text(archivos[path].clone(), cliente.clone())?;
+                fuentes.push((format!("{}:{}", rev, relativo(path)), contenido));
+            }
+        }
+    }
+    Ok(fuentes)
+}
+
+/// Busca el patron en los archivos trackeados del working tree, del index
+/// (--cached) o de las revisiones pedidas.
+pub fn get_grep(flags: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    let opciones = parse_grep_flags(&flags, cliente.clone())?;
+    let patron = opciones.patron.clone().unwrap_or_default();
+    let regex = match opciones.fixed_strings {
+        true => Regex::literal(&patron, opciones.ignore_case),
+        false => Regex::new(&patron, opciones.ignore_case)?,
+    };
+    let mut res = String::new();
+    for (nombre, contenido) in get_grep_sources(&opciones, cliente)? {
+        let coincidencias = contenido
+            .lines()
+            .enumerate()
+            .filter(|(_, linea)| regex.is_match(linea))
+            .collect::<Vec<(usize, &str)>>();
+        if coincidencias.is_empty() {
+            continue;
+        }
+        if opciones.files_with_matches {
+            res.push_str(&format!("{}\n", nombre));
+        } else if opciones.count {
+            res.push_str(&format!("{}:{}\n", nombre, coincidencias.len()));
+        } else if contenido.contains('\0') {
+            res.push_str(&format!("Binary file {} matches\n", nombre));
+        } else {
+            for (i, linea) in coincidencias {
+                match opciones.line_number {
+                    true => res.push_str(&format!("{}:{}:{}\n", nombre, i + 1, linea)),
+                    false => res.push_str(&format!("{}:{}\n", nombre, linea)),
+                }
+            }
+        }
+    }
+    Ok(res)
+}
+
+/***************************
+ ***************************
+ *    DESCRIBE FUNCTIONS
+ **************************
+ **************************/
+
+const DESCRIBE_USAGE: &str = "describe [--tags] [--dirty] [--long] [--always] [<rev>]";
+const NAME_REV_USAGE: &str = "name-rev [--tags] [--name-only] <commit>...";
+
+// peso de pasar al segundo padre de un merge, para preferir los nombres por primer padre
+const MERGE_TRAVERSAL_WEIGHT: usize = 65535;
+
+// (nombre, commit, anotado) de cada tag, con los tags anotados ya pelados hasta el commit
+fn get_tag_commits(cliente: String) -> Result<Vec<(String, String, bool)>, GitrError> {
+    let repo = get_current_repo(cliente.clone())?;
+    let mut tags = Vec::new();
+    for tag in file_manager::get_tags(cliente.clone()).unwrap_or_default() {
+        let hash = file_manager::read_file(format!("{}/gitr/refs/tags/{}", repo, tag))?;
+        let hash = hash.trim().to_string();
+        let (tipo, _) = read_object_body(&hash, cliente.clone())?;
+        if let Ok(commit) = peel_to_commit(hash, tag.clone(), cliente.clone()) {
+            tags.push((tag, commit, tipo == "tag"));
+        }
+    }
+    tags.sort();
+    Ok(tags)
+}
+
+// ancestros del commit incluyendolo
+fn get_ancestors(commit: &str, cliente: String) -> Result<HashSet<String>, GitrError> {
+    let mut visitados = HashSet::new();
+    let mut pendientes = vec![commit.to_string()];
+    while let Some(commit) = pendientes.pop() {
+        if visitados.insert(commit.clone()) {
+            pendientes.extend(get_log_parents(&commit, cliente.clone())?);
+        }
+    }
+    Ok(visitados)
+}
+
+// true si hay cambios en el index o en el working tree respecto de HEAD
+fn working_tree_is_dirty(cliente: String) -> Result<bool, GitrError> {
+    let (not_staged, _, _) = get_untracked_notstaged_files(cliente.clone())?;
+    let (index, _) = get_index_hashmap(cliente.clone())?;
+    let head = get_commit_hashmap(get_current_commit(cliente.clone())?, cliente)?;
+    Ok(!not_staged.is_empty() || index != head)
+}
+
+/// Describe el commit con el tag alcanzable mas cercano: `<tag>-<n>-g<hash>`, donde n
+/// es la cantidad de commits alcanzables desde el commit que no lo son desde el tag.
+pub fn get_describe(flags: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    let error = || GitrError::InvalidArgumentError(flags.join(" "), DESCRIBE_USAGE.to_string());
+    let (mut tags, mut dirty, mut long, mut always) = (false, false, false, false);
+    let mut revs = Vec::new();
+    for flag in flags.iter() {
+        match flag.as_str() {
+            "--tags" => tags = true,
+            "--dirty" => dirty = true,
+            "--long" => long = true,
+            "--always" => always = true,
+            flag if flag.starts_with('-') => return Err(error()),
+            rev => revs.push(rev.to_string()),
+        }
+    }
+    if revs.len() > 1 || (dirty && !revs.is_empty()) {
+        return Err(error());
+    }
+    let commit = resolve_commit(
+        revs.pop().unwrap_or_else(|| "HEAD".to_string()),
+        cliente.clone(),
+    )?;
+    let ancestros = get_ancestors(&commit, cliente.clone())?;
+
+    // de los tags alcanzables se queda con el de menos commits de distancia, y entre
+    // esos con el mas reciente
+    let mut mejor: Option<(usize, i64, String)> = None;
+    for (tag, tag_commit, anotado) in get_tag_commits(cliente.clone())? {
+        if (!anotado && !tags) || !ancestros.contains(&tag_commit) {
+            continue;
+        }
+        let distancia = ancestros.len() - get_ancestors(&tag_commit, cliente.clone())?.len();
+        let fecha = file_manager::get_commit_timestamp(tag_commit, cliente.clone())?;
+        if mejor
+            .as_ref()
+            .is_none_or(|(d, f, _)| distancia < *d || (distancia == *d && fecha > *f))
+        {
+            mejor = Some((distancia, fecha, tag));
+        }
+    }
+    let mut res = match mejor {
+        Some((0, _, tag)) if !long => tag,
+        Some((distancia, _, tag)) => format!("{}-{}-g{}", tag, distancia, &commit[..7]),
+        None if always => commit[..7].to_string(),
+        None => return Err(GitrError::NoTagToDescribe(commit)),
+    };
+    if dirty && working_tree_is_dirty(cliente)? {
+        res.push_str("-dirty");
+    }
+    Ok(res + "\n")
+}
+
+// nombre de cada commit alcanzable desde las refs, como "<ref>~n^p...". Se queda con
+// el nombre de menor peso, donde ir por un segundo padre pesa mucho mas que el primero
+fn get_rev_names(tags_only: bool, cliente: String) -> Result<HashMap<String, String>, GitrError> {
+    let mut puntas = Vec::new();
+    for (tag, commit, _) in get_tag_commits(cliente.clone())? {
+        puntas.push((format!("tags/{}", tag), commit));
+    }
+    if !tags_only {
+        let mut branches = file_manager::get_branches(cliente.clone())?;
+        branches.sort();
+        for branch in branches {
+            puntas.push((
+                branch.clone(),
+                file_manager::get_commit(branch, cliente.clone())?,
+            ));
+        }
+    }
+
+    let mut nombres: HashMap<String, (usize, String)> = HashMap::new();
+    for (nombre, commit) in puntas {
+        // (commit, nombre base, pasos por primer padre desde la base, peso)
+        let mut pendientes = vec![(commit, nombre, 0, 0)];
+        while let Some((commit, base, generacion, peso)) = pendientes.pop() {
+            if nombres.get(&commit).is_some_and(|(p, _)| *p <= peso) {
+                continue;
+            }
+            let nombre = match generacion {
+                0 => base.clone(),
+                n => format!("{}~{}", base, n),
+            };
+            for (i, parent) in get_log_parents(&commit, cliente.clone())?
+                .into_iter()
+                .enumerate()
+            {
+                match i {
+                    0 => pendientes.push((parent, base.clone(), generacion + 1, peso + 1)),
+                    i => pendientes.push((
+                        parent,
+                        format!("{}^{}", nombre, i + 1),
+                        0,
+                        peso + MERGE_TRAVERSAL_WEIGHT,
+                    )),
+                }
+            }
+            nombres.insert(commit, (peso, nombre));
+        }
+    }
+    Ok(nombres
+        .into_iter()
+        .map(|(commit, (_, nombre))| (commit, nombre))
+        .collect())
+}
+
+/// Nombra cada commit pedido relativo a las branches y tags, o "undefined" si
+/// ninguna ref lo alcanza.
+pub fn get_name_rev(flags: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    let (mut tags_only, mut name_only) = (false, false);
+    let mut revs = Vec::new();
+    for flag in flags.iter() {
+        match flag.as_str() {
+            "--tags" => tags_only = true,
+            "--name-only" => name_only = true,
+            flag if flag.starts_with('-') => {
+                return Err(GitrError::InvalidArgumentError(
+                    flags.join(" "),
+                    NAME_REV_USAGE.to_string(),
+                ))
+            }
+            rev => revs.push(rev.to_string()),
+        }
+    }
+    if revs.is_empty() {
+        return Err(GitrError::InvalidArgumentError(
+            flags.join(" "),
+            NAME_REV_USAGE.to_string(),
+        ));
+    }
+    let nombres = get_rev_names(tags_only, cliente.clone())?;
+    let mut res = String::new();
+    for rev in revs {
+        let commit = resolve_commit(rev.clone(), cliente.clone())?;
+        let nombre = nombres
+            .get(&commit)
+            .map(|nombre| nombre.as_str())
+            .unwrap_or("undefined");
+        match name_only {
+            true => res.push_str(&format!("{}\n", nombre)),
+            false => res.push_str(&format!("{} {}\n", rev, nombre)),
+        }
+    }
+    Ok(res)
+}
+
+/***************************
+ ***************************
+ *    ARCHIVE FUNCTIONS
+ **************************
+ **************************/
+
+const ARCHIVE_USAGE: &str =
+    "archive [--format=tar|tar.gz|tgz|zip] [--prefix=<dir>/] [-o <file>] <tree-ish> [<paths>]";
+
+// (path dentro del archivo, modo, contenido) de cada entrada, None para directorios
+type ArchiveEntry = (String, u32, Option<Vec<u8>>);
+
+// recorre el tree agregando los archivos que matchean los paths y los directorios
+// que terminan conteniendo alguno, siempre antes que sus archivos
+// los archivos y directorios con export-ignore en gitrattributes no van al archivo
+fn get_archive_entries(
+    tree: &str,
+    directorio: &str,
+    (paths, reglas): (&[String], &[AttrRule]),
+    cliente: String,
+    entradas: &mut Vec<ArchiveEntry>,
+) -> Result<(), GitrError> {
+    let (_, body) = read_object_body(tree, cliente.clone())?;
+    for (modo, tipo, hash, nombre) in get_tree_entries_data(&body, cliente.clone())? {
+        let path = format!("{}{}", directorio, nombre);
+        let modo = u32::from_str_radix(&modo, 8).unwrap_or(0o100644);
+        let mut atributos = HashMap::new();
+        apply_attr_rules(reglas, &path, &mut atributos);
+        if atributos.get("export-ignore") == Some(&AttrValue::Set) {
+            continue;
+        }
+        if tipo == "tree" {
+            let posicion = entradas.len();
+            get_archive_entries(
+                &hash,
+                &format!("{}/", path),
+                (paths, reglas),
+                cliente.clone(),
+                entradas,
+            )?;
+            if entradas.len() > posicion {
+                entradas.insert(posicion, (format!("{}/", path), 0o40755, None));
+            }
+        } else if diff_path_matches(&path, paths) {
+            // read_object guarda cada byte del blob como un char
+            let contenido = file_manager::read_file_data_from_blob_hash(hash, cliente.clone())?
+                .chars()
+                .map(|c| c as u8)
+                .collect();
+            entradas.push((path, modo, Some(contenido)));
+        }
+    }
+    Ok(())
+}
+
+// escribe el numero en octal ocupando el campo completo, terminado en \0
+fn tar_octal(campo: &mut [u8], valor: u64) {
+    let octal = format!("{:0ancho$o}\0", valor, ancho = campo.len() - 1);
+    campo.copy_from_slice(octal.as_bytes());
+}
+
+fn tar_header(path: &str, modo: u32, tamanio: usize, mtime: i64) -> Result<Vec<u8>, GitrError> {
+    let mut header = vec![0u8; 512];
+    // los nombres de mas de 100 bytes se parten en prefix (155) y name (100) por una '/'
+    let (prefix, nombre) = match path.len() {
+        0..=100 => ("", path),
+        _ => match path[..path.len() - 1]
+            .char_indices()
+            .rev()
+            .find(|(i, c)| *c == '/' && *i <= 155 && path.len() - i - 1 <= 100)
+        {
+            Some((i, _)) => (&path[..i], &path[i + 1..]),
+            None => return Err(GitrError::FileWriteError(path.to_string())),
+        },
+    };
+    header[..nombre.len()].copy_from_slice(nombre.as_bytes());
+    tar_octal(&mut header[100..108], (modo & 0o7777) as u64);
+    tar_octal(&mut header[108..116], 0);
+    tar_octal(&mut header[116..124], 0);
+    tar_octal(&mut header[124..136], tamanio as u64);
+    tar_octal(&mut header[136..148], mtime.max(0) as u64);
+    header[156] = if path.ends_with('/') { b'5' } else { b'0' };
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+    // el checksum se calcula con su propio campo lleno de espacios
+    header[148..156].copy_from_slice(b"        ");
+    let checksum: u64 = header.iter().map(|b| *b as u64).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+    Ok(header)
+}
+
+fn write_tar(entradas: &[ArchiveEntry], mtime: i64) -> Result<Vec<u8>, GitrError> {
+    let mut tar = Vec::new();
+    for (path, modo, contenido) in entradas {
+        let contenido = contenido.as_deref().unwrap_or_default();
+        tar.extend(tar_header(path, *modo, contenido.len(), mtime)?);
+        tar.extend(contenido);
+        tar.resize(tar.len().div_ceil(512) * 512, 0);
+    }
+    tar.resize(tar.len() + 1024, 0);
+    Ok(tar)
+}
+
+// fecha y hora en el formato de MS-DOS que usa zip, (hora, fecha)
+fn zip_dos_datetime(mtime: i64) -> (u16, u16) {
+    let fecha = match NaiveDateTime::from_timestamp_opt(mtime.max(315532800), 0) {
+        Some(fecha) => fecha,
+        None => return (0, 0x21),
+    };
+    let hora = ((fecha.hour() << 11) | (fecha.minute() << 5) | (fecha.second() / 2)) as u16;
+    let dia = (((fecha.year() as u32 - 1980) << 9) | (fecha.month() << 5) | fecha.day()) as u16;
+    (hora, dia)
+}
+
+fn write_zip(entradas: &[ArchiveEntry], mtime: i64) -> Result<Vec<u8>, GitrError> {
+    let (hora, dia) = zip_dos_datetime(mtime);
+    let mut zip = Vec::new();
+    let mut central = Vec::new();
+    for (path, modo, contenido) in entradas {
+        let datos = contenido.as_deref().unwrap_or_default();
+        let mut crc = flate2::Crc::new();
+        crc.update(datos);
+        let (metodo, comprimido): (u16, Vec<u8>) = match contenido {
+            Some(_) => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder
+                    .write_all(datos)
+                    .map_err(|_| GitrError::CompressionError)?;
+                (
+                    8,
+                    encoder.finish().map_err(|_| GitrError::CompressionError)?,
+                )
+            }
+            None => (0, Vec::new()),
+        };
+        // los atributos externos llevan el modo unix en los 16 bits altos
+        let atributos = match contenido {
+            Some(_) => (0o100000 | (modo & 0o7777)) << 16,
+            None => (0o40755 << 16) | 0x10,
+        };
+        let campos_comunes = [
+            &20u16.to_le_bytes()[..],
+            &0u16.to_le_bytes(),
+            &metodo.to_le_bytes(),
+            &hora.to_le_bytes(),
+            &dia.to_le_bytes(),
+            &crc.sum().to_le_bytes(),
+            &(comprimido.len() as u32).to_le_bytes(),
+            &(datos.len() as u32).to_le_bytes(),
+            &(path.len() as u16).to_le_bytes(),
+            &0u16.to_le_bytes(),
+        ]
+        .concat();
+
+        central.extend(0x02014b50u32.to_le_bytes());
+        central.extend((3u16 << 8 | 20).to_le_bytes());
+        central.extend(&campos_comunes);
+        central.extend([0u8; 6]);
+        central.extend(atributos.to_le_bytes());
+        central.extend((zip.len() as u32).to_le_bytes());
+        central.extend(path.as_bytes());
+
+        zip.extend(0x04034b50u32.to_le_bytes());
+        zip.extend(&campos_comunes);
+        zip.extend(path.as_bytes());
+        zip.extend(comprimido);
+    }
+    let inicio_central = zip.len() as u32;
+    zip.extend(&central);
+    zip.extend(0x06054b50u32.to_le_bytes());
+    zip.extend([0u8; 4]);
+    zip.extend((entradas.len() as u16).to_le_bytes());
+    zip.extend((entradas.len() as u16).to_le_bytes());
+    zip.extend((central.len() as u32).to_le_bytes());
+    zip.extend(inicio_central.to_le_bytes());
+    zip.extend([0u8; 2]);
+    Ok(zip)
+}
+
+/// Arma un archivo tar, tar.gz o zip con el tree de una revision, sin tocar el working
+/// directory. Devuelve los bytes y el archivo de salida pedido con -o, si hay.
+pub fn get_archive(
+    flags: Vec<String>,
+    cliente: String,
+) -> Result<(Vec<u8>, Option<String>), GitrError> {
+    let error = || GitrError::InvalidArgumentError(flags.join(" "), ARCHIVE_USAGE.to_string());
+    let (mut formato, mut prefix, mut output) = (None, String::new(), None);
+    let mut args = Vec::new();
+    let mut iter = flags.iter();
+    while let Some(flag) = iter.next() {
+        if let Some(valor) = flag.strip_prefix("--format=") {
+            formato = Some(valor.to_string());
+        } else if let Some(valor) = flag.strip_prefix("--prefix=") {
+            prefix = valor.to_string();
+        } else if let Some(valor) = flag.strip_prefix("--output=") {
+            output = Some(valor.to_string());
+        } else if flag == "-o" {
+            output = Some(iter.next().ok_or_else(error)?.clone());
+        } else if flag.starts_with('-') && flag != "--" {
+            return Err(error());
+        } else if flag != "--" {
+            args.push(flag.clone());
+        }
+    }
+    // sin --format se deduce de la extension de la salida
+    let formato = formato.unwrap_or_else(|| {
+        match output.as_deref() {
+            Some(o) if o.ends_with(".zip") => "zip",
+            Some(o) if o.ends_with(".tar.gz") || o.ends_with(".tgz") => "tar.gz",
+            _ => "tar",
+        }
+        .to_string()
+    });
+    let (rev, paths) = args.split_first().ok_or_else(error)?;
+
+    let (tree, mtime) = match resolve_commit(rev.clone(), cliente.clone()) {
+        Ok(commit) => (
+            file_manager::get_main_tree(commit.clone(), cliente.clone())?,
+            file_manager::get_commit_timestamp(commit, cliente.clone())?,
+        ),
+        Err(_) => {
+            let hash = resolve_object(rev.clone(), cliente.clone())?;
+            if read_object_body(&hash, cliente.clone())?.0 != "tree" {
+                return Err(GitrError::InvalidTreeError);
+            }
+            (hash, chrono::Utc::now().timestamp())
+        }
+    };
+    let mut reglas = Vec::new();
+    get_tree_attr_rules(&tree, "", cliente.clone(), &mut reglas)?;
+    let mut entradas = Vec::new();
+    get_archive_entries(&tree, "", (paths, &reglas), cliente, &mut entradas)?;
+    for (path, _, _) in entradas.iter_mut() {
+        *path = format!("{}{}", prefix, path);
+    }
+    if prefix.ends_with('/') {
+        entradas.insert(0, (prefix.clone(), 0o40755, None));
+    }
+
+    let bytes = match formato.as_str() {
+        "tar" => write_tar(&entradas, mtime)?,
+        "tar.gz" | "tgz" => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder
+                .write_all(&write_tar(&entradas, mtime)?)
+                .map_err(|_| GitrError::CompressionError)?;
+            encoder.finish().map_err(|_| GitrError::CompressionError)?
+        }
+        "zip" => write_zip(&entradas, mtime)?,
+        _ => return Err(error()),
+    };
+    Ok((bytes, output))
+}
+
+/***************************
+ ***************************
+ *    APPLY FUNCTIONS
+ **************************
+ **************************/
+
+// un hunk de un patch, con los rangos de su header y sus lineas (' ', '-' o '+', linea).
+// Los flags marcan si la ultima linea de cada lado no termina en salto de linea
+// ("\ No newline at end of file")
+pub struct PatchHunk {
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    pub lineas: Vec<(char, String)>,
+    pub sin_newline_viejo: bool,
+    pub sin_newline_nuevo: bool,
+}
+
+impl PatchHunk {
+    pub fn header(&self) -> String {
+        format!(
+            "@@ -{},{} +{},{} @@",
+            self.old_start, self.old_len, self.new_start, self.new_len
+        )
+    }
+}
+
+// los cambios de un archivo dentro de un patch. El path viejo es None para archivos
+// nuevos y el nuevo es None para archivos borrados
+#[derive(Default)]
+pub struct FilePatch {
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub old_mode: Option<String>,
+    pub new_mode: Option<String>,
+    // hashes (abreviados) de la linea "index"
+    pub old_hash: Option<String>,
+    pub new_hash: Option<String>,
+    pub hunks: Vec<PatchHunk>,
+    pub binary: bool,
+}
+
+impl FilePatch {
+    // path con el que se muestra el archivo en los mensajes
+    pub fn path(&self) -> String {
+        self.new_path
+            .clone()
+            .or(self.old_path.clone())
+            .unwrap_or_default()
+    }
+
+    // el patch que deshace este: intercambia los dos lados de cada hunk y del header
+    fn invertir(&mut self) {
+        std::mem::swap(&mut self.old_path, &mut self.new_path);
+        std::mem::swap(&mut self.old_mode, &mut self.new_mode);
+        std::mem::swap(&mut self.old_hash, &mut self.new_hash);
+        for hunk in self.hunks.iter_mut() {
+            std::mem::swap(&mut hunk.old_start, &mut hunk.new_start);
+            std::mem::swap(&mut hunk.old_len, &mut hunk.new_len);
+            std::mem::swap(&mut hunk.sin_newline_viejo, &mut hunk.sin_newline_nuevo);
+            for (op, _) in hunk.lineas.iter_mut() {
+                *op = match op {
+                    '+' => '-',
+                    '-' => '+',
+                    _ => *op,
+                };
+            }
+        }
+    }
+}
+
+// "a/dir/file" -> Some("dir/file"), "/dev/null" -> None
+fn patch_path(path: &str) -> Option<String> {
+    let path = path.split('\t').next().unwrap_or_default().trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    match path.split_once('/') {
+        Some((prefijo, resto)) if prefijo == "a" || prefijo == "b" => Some(resto.to_string()),
+        _ => Some(path.to_string()),
+    }
+}
+
+// el path tiene que ser relativo al repo, sin ".." ni caracteres de control
+fn check_patch_path(path: Option<String>, linea: &str) -> Result<Option<String>, GitrError> {
+    if let Some(p) = path.as_ref() {
+        if p.starts_with('/')
+            || p.chars().any(|c| c.is_control())
+            || p.split('/').any(|parte| parte.is_empty() || parte == "..")
+        {
+            return Err(GitrError::PatchError(tr("patch.invalid_path", &[&linea])));
+        }
+    }
+    Ok(path)
+}
+
+// un modo de archivo son seis digitos octales, como 100644
+fn check_patch_mode(modo: &str, linea: &str) -> Result<String, GitrError> {
+    match modo.len() == 6 && modo.chars().all(|c| ('0'..='7').contains(&c)) {
+        true => Ok(modo.to_string()),
+        false => Err(GitrError::PatchError(tr("patch.invalid_mode", &[&linea]))),
+    }
+}
+
+// "-3,2" -> (3, 2), "-3" -> (3, 1)
+fn parse_hunk_range(rango: &str) -> Option<(usize, usize)> {
+    let rango = rango.get(1..)?;
+    match rango.split_once(',') {
+        Some((inicio, largo)) => Some((inicio.parse().ok()?, largo.parse().ok()?)),
+        None => Some((rango.parse().ok()?, 1)),
+    }
+}
+
+/// Parsea un diff unificado (con o sin headers de git) en los cambios de cada archivo.
+/// Ignora el texto que no forma parte del diff, como los headers de un mail, pero falla
+/// si un header o un hunk no se puede parsear o si el hunk no tiene las lineas que dice.
+pub fn parse_patch(texto: &str) -> Result<Vec<FilePatch>, GitrError> {
+    let mut patches: Vec<FilePatch> = Vec::new();
+    let mut lineas = texto.lines().peekable();
+    // en un diff de git los headers "---"/"+++" pertenecen al ultimo "diff --git"
+    let mut en_headers_git = false;
+    while let Some(linea) = lineas.next() {
+        // por ejemplo la salida de diff --color
+        if linea.contains('\x1b') && (!patches.is_empty() || linea.contains("diff --git ")) {
+            return Err(GitrError::PatchError(tr(
+                "patch.color_codes",
+                &[&linea.escape_debug()],
+            )));
+        }
+        if let Some(paths) = linea.strip_prefix("diff --git ") {
+            let (viejo, nuevo) = match paths.split_once(" b/") {
+                Some((viejo, nuevo)) if viejo.starts_with("a/") => (viejo, nuevo),
+                _ => return Err(GitrError::PatchError(tr("patch.invalid_header", &[&linea]))),
+            };
+            patches.push(FilePatch {
+                old_path: check_patch_path(patch_path(viejo), linea)?,
+                new_path: check_patch_path(patch_path(&format!("b/{}", nuevo)), linea)?,
+                ..Default::default()
+            });
+            en_headers_git = true;
+            continue;
+        }
+        if linea.starts_with("--- ") && lineas.peek().is_some_and(|l| l.starts_with("+++ ")) {
+            let nuevo = lineas.next().unwrap_or_default();
+            if !en_headers_git || patches.is_empty() {
+                patches.push(FilePatch::default());
+            }
+            if let Some(patch) = patches.last_mut() {
+                patch.old_path = check_patch_path(patch_path(&linea[4..]), linea)?;
+                patch.new_path = check_patch_path(patch_path(&nuevo[4..]), nuevo)?;
+            }
+            en_headers_git = false;
+            continue;
+        }
+        let Some(patch) = patches.last_mut() else {
+            continue;
+        };
+        if let Some(modo) = linea.strip_prefix("new file mode ") {
+            patch.old_path = None;
+            patch.new_mode = Some(check_patch_mode(modo, linea)?);
+        } else if let Some(modo) = linea.strip_prefix("deleted file mode ") {
+            patch.new_path = None;
+            patch.old_mode = Some(check_patch_mode(modo, linea)?);
+        } else if let Some(modo) = linea.strip_prefix("old mode ") {
+            patch.old_mode = Some(check_patch_mode(modo, linea)?);
+        } else if let Some(modo) = linea.strip_prefix("new mode ") {
+            patch.new_mode = Some(check_patch_mode(modo, linea)?);
+        } else if let Some(path) = linea
+            .strip_prefix("rename from ")
+            .or(linea.strip_prefix("copy from "))
+        {
+            patch.old_path = check_patch_path(Some(path.to_string()), linea)?;
+        } else if let Some(path) = linea
+            .strip_prefix("rename to ")
+            .or(linea.strip_prefix("copy to "))
+        {
+            patch.new_path = check_patch_path(Some(path.to_string()), linea)?;
+        } else if let Some(index) = linea.strip_prefix("index ") {
+            let mut campos = index.split(' ');
+            let es_hash = |h: &str| !h.is_empty() && h.chars().all(|c| c.is_ascii_hexdigit());
+            let (viejo, nuevo) = match campos.next().and_then(|hashes| hashes.split_once("..")) {
+                Some((viejo, nuevo)) if es_hash(viejo) && es_hash(nuevo) => (viejo, nuevo),
+                _ => return Err(GitrError::PatchError(tr("patch.invalid_header", &[&linea]))),
+            };
+            let hash = |h: &str| match h.chars().all(|c| c == '0') {
+                true => None,
+                false => Some(h.to_string()),
+            };
+            (patch.old_hash, patch.new_hash) = (hash(viejo), hash(nuevo));
+            if let Some(modo) = campos.next() {
+                let modo = check_patch_mode(modo, linea)?;
+                patch.old_mode.get_or_insert(modo.clone());
+                patch.new_mode.get_or_insert(modo);
+            }
+        } else if linea.starts_with("Binary files ") || linea == "GIT binary patch" {
+            patch.binary = true;
+        } else if let Some(rangos) = linea.strip_prefix("@@ ") {
+            let mut campos = rangos.split(' ');
+            let invalido = || GitrError::PatchError(tr("patch.invalid_hunk", &[&linea]));
+            let (old_start, old_len) = campos
+                .next()
+                .filter(|rango| rango.starts_with('-'))
+                .and_then(parse_hunk_range)
+                .ok_or_else(invalido)?;
+            let (new_start, new_len) = campos
+                .next()
+                .filter(|rango| rango.starts_with('+'))
+                .and_then(parse_hunk_range)
+                .ok_or_else(invalido)?;
+            if campos.next() != Some("@@") {
+                return Err(invalido());
+            }
+            let mut hunk = PatchHunk {
+                old_start,
+                old_len,
+                new_start,
+                new_len,
+                lineas: Vec::new(),
+                sin_newline_viejo: false,
+                sin_newline_nuevo: false,
+            };
+            let (mut viejas, mut nuevas) = (0, 0);
+            while viejas < old_len || nuevas < new_len {
+                let linea = lineas.next().ok_or_else(invalido)?;
+                let (op, contenido) = match linea.chars().next() {
+                    Some(op @ (' ' | '-' | '+')) => (op, linea[1..].to_string()),
+                    Some('\\') => {
+                        marcar_sin_newline(&mut hunk);
+                        continue;
+                    }
+                    // algunos editores borran el espacio de las lineas de contexto vacias
+                    None => (' ', String::new()),
+                    Some(_) => return Err(invalido()),
+                };
+                if op != '+' {
+                    viejas += 1;
+                }
+                if op != '-' {
+                    nuevas += 1;
+                }
+                hunk.lineas.push((op, contenido));
+            }
+            if viejas != old_len || nuevas != new_len {
+                return Err(invalido());
+            }
+            if lineas
+                .next_if(|siguiente| siguiente.starts_with('\\'))
+                .is_some()
+            {
+                marcar_sin_newline(&mut hunk);
+            }
+            // una linea de cambio justo despues significa que el header cuenta de menos;
+            // "--- " empieza el proximo archivo y "-- " es la firma de format-patch
+            let sobra = lineas.peek().is_some_and(|siguiente| {
+                siguiente.starts_with(['+', ' '])
+                    || (siguiente.starts_with('-')
+                        && !siguiente.starts_with("--- ")
+                        && *siguiente != "-- ")
+            });
+            if sobra {
+                return Err(GitrError::PatchError(tr("patch.hunk_too_long", &[&linea])));
+            }
+            patch.hunks.push(hunk);
+        }
+        en_headers_git = en_headers_git && !linea.starts_with("@@ ");
+    }
+    Ok(patches)
+}
+
+// el "\ No newline at end of file" se refiere a la linea anterior del hunk
+fn marcar_sin_newline(hunk: &mut PatchHunk) {
+    match hunk.lineas.last().map(|(op, _)| *op) {
+        Some('-') => hunk.sin_newline_viejo = true,
+        Some('+') => hunk.sin_newline_nuevo = true,
+        Some(_) => {
+            hunk.sin_newline_viejo = true;
+            hunk.sin_newline_nuevo = true;
+        }
+        None => {}
+    }
+}
+
+/// Aplica los hunks al contenido. Cada hunk se busca primero donde dice su header y
+/// si no esta ahi en la posicion mas cercana, por si el archivo se corrio.
+pub fn apply_hunks(contenido: &str, hunks: &[PatchHunk]) -> Result<String, GitrError> {
+    let lineas = contenido.lines().collect::<Vec<&str>>();
+    let mut res: Vec<&str> = Vec::new();
+    let mut pos = 0;
+    let mut corrimiento: isize = 0;
+    for hunk in hunks {
+        let viejas = hunk
+            .lineas
+            .iter()
+            .filter(|(op, _)| *op != '+')
+            .map(|(_, linea)| linea.as_str())
+            .collect::<Vec<&str>>();
+        let inicio = match hunk.old_len {
+            0 => hunk.old_start,
+            _ => hunk.old_start.saturating_sub(1),
+        };
+        let esperado = (inicio as isize + corrimiento).max(pos as isize) as usize;
+        // si el lado viejo no termina en salto de linea, el hunk tiene que ir al final
+        let coincide = |p: usize| {
+            p + viejas.len() <= lineas.len()
+                && lineas[p..p + viejas.len()] == viejas[..]
+                && (!hunk.sin_newline_viejo || p + viejas.len() == lineas.len())
+        };
+        let encontrado = (0..=lineas.len()).find_map(|distancia| {
+            [Some(esperado + distancia), esperado.checked_sub(distancia)]
+                .into_iter()
+                .flatten()
+                .find(|p| *p >= pos && coincide(*p))
+        });
+        let Some(p) = encontrado else {
+            return Err(GitrError::PatchError(tr(
+                "patch.hunk_does_not_apply",
+                &[&hunk.header()],
+            )));
+        };
+        res.extend(&lineas[pos..p]);
+        res.extend(
+            hunk.lineas
+                .iter()
+                .filter(|(op, _)| *op != '-')
+                .map(|(_, linea)| linea.as_str()),
+        );
+        pos = p + viejas.len();
+        corrimiento = p as isize - inicio as isize;
+    }
+    // si el ultimo hunk llega al final del archivo, su lado nuevo dice si termina en
+    // salto de linea; si no, queda como estaba
+    let newline_final = match hunks.last() {
+        Some(hunk) if pos == lineas.len() => !hunk.sin_newline_nuevo,
+        _ => contenido.is_empty() || contenido.ends_with('\n'),
+    };
+    res.extend(&lineas[pos..]);
+    let mut resultado = res.join("\n");
+    if !res.is_empty() && newline_final {
+        resultado.push('\n');
+    }
+    Ok(resultado)
+}
+
+/// Aplica el patch de un archivo a su contenido actual (None si no existe). Devuelve el
+/// contenido nuevo, o None si el patch borra el archivo.
+pub fn apply_file_patch(
+    patch: &FilePatch,
+    contenido: Option<&str>,
+) -> Result<Option<String>, GitrError> {
+    if patch.binary {
+        return Err(GitrError::PatchError(tr("patch.binary", &[&patch.path()])));
+    }
+    match (&patch.old_path, &patch.new_path, contenido) {
+        (None, _, Some(_)) => Err(GitrError::PatchError(tr(
+            "patch.already_exists",
+            &[&patch.path()],
+        ))),
+        (Some(_), _, None) => Err(GitrError::PatchError(tr(
+            "patch.does_not_exist",
+            &[&patch.path()],
+        ))),
+        (_, None, Some(contenido)) => match apply_hunks(contenido, &patch.hunks)?.is_empty() {
+            true => Ok(None),
+            false => Err(GitrError::PatchError(tr(
+                "patch.not_empty_after_delete",
+                &[&patch.path()],
+            ))),
+        },
+        (_, _, contenido) => Ok(Some(apply_hunks(
+            contenido.unwrap_or_default(),
+            &patch.hunks,
+        )?)),
+    }
+}
+
+const APPLY_USAGE: &str = "apply [--check] [-R] [--cached | --index] [--3way] <patch>";
+
+// donde se aplican los patches y si se puede recurrir a un merge de 3 vias
+struct ApplyTarget {
+    worktree: bool,
+    index: bool,
+    three_way: bool,
+}
+
+// lo que hay que escribir para un archivo del patch
+enum ApplyResult {
+    // contenido nuevo, o None si el archivo se borra
+    Aplicado(Option<String>),
+    // contenido actual, hash del blob base y contenido del patch aplicado sobre la base
+    Merge(String, String, String),
+}
+
+// busca el blob al que apunta un hash abreviado de la linea "index" de un patch
+fn resolve_patch_blob(hash: &Option<String>, cliente: String) -> Option<String> {
+    let hash = hash.as_ref().filter(|h| h.len() >= 4)?;
+    match objects_with_prefix(&hash.to_lowercase(), cliente)
+        .ok()?
+        .as_slice()
+    {
+        [hash] => Some(hash.clone()),
+        _ => None,
+    }
+}
+
+// cambia el modo con el que figura un archivo en el index
+fn set_index_mode(full_path: &str, modo: &str, cliente: String) -> Result<(), GitrError> {
+    let index = file_manager::read_index(cliente.clone())?
+        .lines()
+        .map(|linea| match linea.split_once(' ') {
+            Some((_, resto)) if resto.ends_with(&format!(" {}", full_path)) => {
+                format!("{} {}", modo, resto)
+            }
+            _ => linea.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    let dir = get_current_repo(cliente)? + "/gitr/index";
+    file_manager::write_compressed_data(&dir, flate2compress(index)?.as_slice())
+}
+
+// calcula el resultado de aplicar un patch, recurriendo al merge de 3 vias con el blob
+// base del patch si los hunks no aplican
+fn apply_patch_result(
+    patch: &FilePatch,
+    contenido: Option<String>,
+    destino: &ApplyTarget,
+    cliente: String,
+) -> Result<ApplyResult, GitrError> {
+    let error = match apply_file_patch(patch, contenido.as_deref()) {
+        Ok(resultado) => return Ok(ApplyResult::Aplicado(resultado)),
+        Err(error) => error,
+    };
+    if !destino.three_way {
+        return Err(error);
+    }
+    let (Some(base), Some(actual)) = (
+        resolve_patch_blob(&patch.old_hash, cliente.clone()),
+        contenido,
+    ) else {
+        return Err(error);
+    };
+    let data_base = file_manager::read_blob_text(base.clone(), cliente)?;
+    match apply_file_patch(patch, Some(&data_base)) {
+        Ok(Some(nuevo)) => Ok(ApplyResult::Merge(actual, base, nuevo)),
+        _ => Err(error),
+    }
+}
+
+// aplica los patches al working tree y/o al index, todos o ninguno. Devuelve los paths
+// que quedaron con conflictos despues de un merge de 3 vias
+fn apply_patches(
+    patches: &[FilePatch],
+    destino: &ApplyTarget,
+    check: bool,
+    cliente: String,
+) -> Result<Vec<String>, GitrError> {
+    let repo = get_current_repo(cliente.clone())?;
+    let (index_hashmap, _) = get_index_hashmap(cliente.clone())?;
+    let mut resultados = Vec::new();
+    for patch in patches {
+        let path = format!(
+            "{}/{}",
+            repo,
+            patch.old_path.clone().unwrap_or(patch.path())
+        );
+        let contenido = match destino.worktree {
+            true => fs::read(&path)
+                .ok()
+                .map(|bytes| String::from_utf8_lossy(&bytes).to_string()),
+            false => match index_hashmap.get(&path) {
+                Some(hash) => Some(file_manager::read_blob_text(hash.clone(), cliente.clone())?),
+                None => None,
+            },
+        };
+        resultados.push(apply_patch_result(
+            patch,
+            contenido,
+            destino,
+            cliente.clone(),
+        )?);
+    }
+    if check {
+        return Ok(vec![]);
+    }
+    let mut conflictos = Vec::new();
+    for (patch, resultado) in patches.iter().zip(resultados) {
+        if let Some(viejo) = &patch.old_path {
+            if patch.new_path.as_ref() != Some(viejo) {
+                if destino.worktree {
+                    let _ = fs::remove_file(format!("{}/{}", repo, viejo));
+                }
+                if destino.index {
+                    rm_from_index(viejo, cliente.clone())?;
+                }
+            }
+        }
+        let Some(nuevo) = &patch.new_path else {
+            continue;
+        };
+        let full_path = format!("{}/{}", repo, nuevo);
+        let contenido = match resultado {
+            ApplyResult::Aplicado(Some(contenido)) => contenido,
+            ApplyResult::Aplicado(None) => continue,
+            ApplyResult::Merge(actual, base, nuevo_base) => {
+                let (actual, nuevo_base) = (Blob::new(actual)?, Blob::new(nuevo_base)?);
+                actual.save(cliente.clone())?;
+                nuevo_base.save(cliente.clone())?;
+                let conflicto = merge_file(
+                    &full_path,
+                    base,
+                    actual.get_hash(),
+                    nuevo_base.get_hash(),
+                    cliente.clone(),
+                )?;
+                if conflicto == Some(true) {
+                    conflictos.push(nuevo.clone());
+                    continue;
+                }
+                file_manager::read_file(full_path.clone())?
+            }
+        };
+        if destino.worktree {
+            if let Some(dir) = Path::new(&full_path).parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+            file_manager::write_file(full_path.clone(), contenido.clone())?;
+            if let Some(modo) = &patch.new_mode {
+                use std::os::unix::fs::PermissionsExt;
+                let permisos = match modo.as_str() {
+                    "100755" => 0o755,
+                    _ => 0o644,
+                };
+                let _ = fs::set_permissions(&full_path, fs::Permissions::from_mode(permisos));
+            }
+        }
+        if destino.index {
+            match destino.worktree {
+                true => save_and_add_blob_to_index(full_path.clone(), cliente.clone())?,
+                false => {
+                    let blob = Blob::new(contenido)?;
+                    blob.save(cliente.clone())?;
+                    file_manager::add_to_index(&full_path, &blob.get_hash(), cliente.clone())?;
+                }
+            }
+            if let Some(modo) = patch.new_mode.as_ref().filter(|m| *m != "100644") {
+                set_index_mode(&full_path, modo, cliente.clone())?;
+            }
+        }
+    }
+    Ok(conflictos)
+}
+
+/// Aplica un patch al working tree (por defecto), al index (--cached) o a ambos
+/// (--index). Con --check solo verifica que aplique y con --3way, si algun hunk no
+/// aplica, mergea usando los blobs de las lineas "index" del patch. Con -R deshace el
+/// patch.
+pub fn get_apply(flags: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    let error = || GitrError::InvalidArgumentError(flags.join(" "), APPLY_USAGE.to_string());
+    let (mut check, mut cached, mut index, mut three_way) = (false, false, false, false);
+    let mut reverse = false;
+    let mut archivos = Vec::new();
+    for flag in flags.iter() {
+        match flag.as_str() {
+            "--check" => check = true,
+            "--cached" => cached = true,
+            "--index" => index = true,
+            "--3way" | "-3" => three_way = true,
+            "-R" | "--reverse" => reverse = true,
+            f if f.starts_with('-') => return Err(error()),
+            f => archivos.push(f.to_string()),
+        }
+    }
+    // el merge de 3 vias deja los conflictos en el working tree
+    if archivos.len() != 1 || (cached && (index || three_way)) {
+        return Err(error());
+    }
+    let destino = ApplyTarget {
+        worktree: !cached,
+        index: cached || index || three_way,
+        three_way,
+    };
+    let texto = file_manager::read_file(archivos[0].clone())?;
+    let mut patches = parse_patch(&texto)?;
+    if reverse {
+        patches.iter_mut().for_each(FilePatch::invertir);
+    }
+    if patches.is_empty() {
+        return Err(GitrError::PatchError(tr(
+            "patch.no_patches",
+            &[&archivos[0]],
+        )));
+    }
+    let conflictos = apply_patches(&patches, &destino, check, cliente)?;
+    let mut res = String::new();
+    for patch in patches.iter() {
+        let path = patch.path();
+        match conflictos.contains(&path) {
+            true => res += &(tr("apply.conflicts", &[&path, &path]) + "\n"),
+            false if check => res += &(tr("apply.checked", &[&path]) + "\n"),
+            false => res += &(tr("apply.clean", &[&path]) + "\n"),
+        }
+    }
+    Ok(res)
+}
+
+/***************************
+ ***************************
+ *    FORMAT-PATCH FUNCTIONS
+ **************************
+ **************************/
+
+const FORMAT_PATCH_USAGE: &str =
+    "format-patch [-o <dir>] [--stdout] (-<n> | <since> | <rev-range>)";
+
+// (nombre, mail, timestamp, zona) del autor del commit
+fn get_commit_author_ident(
+    commit: &str,
+    cliente: String,
+) -> Result<(String, String, i64, String), GitrError> {
+    let (_, body) = read_object_body(commit, cliente)?;
+    let invalido = || GitrError::InvalidCommitError;
+    let autor = body
+        .lines()
+        .find_map(|linea| linea.strip_prefix("author "))
+        .ok_or_else(invalido)?;
+    let (nombre, resto) = autor.split_once(" <").ok_or_else(invalido)?;
+    let (mail, resto) = resto.split_once("> ").ok_or_else(invalido)?;
+    let (timestamp, zona) = resto.split_once(' ').unwrap_or((resto, "+0000"));
+    Ok((
+        nombre.to_string(),
+        mail.to_string(),
+        timestamp.parse().map_err(|_| invalido())?,
+        zona.to_string(),
+    ))
+}
+
+// "Tue, 3 Oct 2023 12:00:00 -0300"
+fn format_rfc2822_date(timestamp: i64, zona: &str) -> Result<String, GitrError> {
+    let segundos = zona
+        .get(1..)
+        .and_then(|hhmm| hhmm.parse::<i32>().ok())
+        .map(|hhmm| {
+            (hhmm / 100 * 3600 + hhmm % 100 * 60) * if zona.starts_with('-') { -1 } else { 1 }
+        })
+        .unwrap_or(0);
+    let offset = FixedOffset::east_opt(segundos).ok_or(GitrError::TimeError)?;
+    match offset.timestamp_opt(timestamp, 0).single() {
+        Some(fecha) => Ok(fecha.format("%a, %-d %b %Y %H:%M:%S %z").to_string()),
+        None => Err(GitrError::TimeError),
+    }
+}
+
+// "Agrega el parser!" -> "Agrega-el-parser"
+fn patch_file_slug(subject: &str) -> String {
+    let mut slug = String::new();
+    for c in subject.chars() {
+        match c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+            true => slug.push(c),
+            false if !slug.ends_with('-') => slug.push('-'),
+            false => {}
+        }
+    }
+    let slug = slug.trim_matches(|c| c == '-' || c == '.');
+    slug.chars()
+        .take(52)
+        .collect::<String>()
+        .trim_end_matches('-')
+        .to_string()
+}
+
+// commits del rango sin los merges, del mas viejo al mas nuevo
+fn get_format_patch_commits(rango: &str, cliente: String) -> Result<Vec<String>, GitrError> {
+    let (desde, hasta) = match rango.split_once("..") {
+        Some((desde, hasta)) => (
+            Some(desde.to_string()),
+            if hasta.is_empty() { "HEAD" } else { hasta }.to_string(),
+        ),
+        None => match rango.strip_prefix('-').map(|n| n.parse::<usize>()) {
+            Some(Ok(n)) => {
+                let hasta = resolve_commit("HEAD".to_string(), cliente.clone())?;
+                let commits = file_manager::rev_list(vec![hasta], cliente.clone())?;
+                let mut commits = commits
+                    .into_iter()
+                    .filter(|commit| {
+                        get_log_parents(commit, cliente.clone()).is_ok_and(|p| p.len() <= 1)
+                    })
+                    .take(n)
+                    .collect::<Vec<String>>();
+                commits.reverse();
+                return Ok(commits);
+            }
+            _ => (Some(rango.to_string()), "HEAD".to_string()),
+        },
+    };
+    let hasta = resolve_commit(hasta, cliente.clone())?;
+    let excluidos = match desde {
+        Some(desde) => get_ancestors(&resolve_commit(desde, cliente.clone())?, cliente.clone())?,
+        None => HashSet::new(),
+    };
+    let mut commits = Vec::new();
+    for commit in file_manager::rev_list(vec![hasta], cliente.clone())? {
+        if !excluidos.contains(&commit) && get_log_parents(&commit, cliente.clone())?.len() <= 1 {
+            commits.push(commit);
+        }
+    }
+    commits.reverse();
+    Ok(commits)
+}
+
+// el mail de un commit: headers, mensaje, stat y diff contra su padre
+fn format_patch_mail(
+    commit: &str,
+    numero: usize,
+    total: usize,
+    cliente: String,
+) -> Result<String, GitrError> {
+    let (nombre, mail, timestamp, zona) = get_commit_author_ident(commit, cliente.clone())?;
+    let (subject, body) = get_log_subject_and_body(commit, cliente.clone())?;
+    let prefijo = match total {
+        1 => "[PATCH]".to_string(),
+        _ => format!("[PATCH {}/{}]", numero, total),
+    };
+    let mut res = format!(
+        "From {} Mon Sep 17 00:00:00 2001\nFrom: {} <{}>\nDate: {}\nSubject: {} {}\n\n",
+        commit,
+        nombre,
+        mail,
+        format_rfc2822_date(timestamp, &zona)?,
+        prefijo,
+        subject
+    );
+    if !body.is_empty() {
+        res.push_str(&format!("{}\n", body));
+    }
+
+    let mut opciones = parse_diff_flags(vec!["--no-color".to_string()], cliente.clone())?;
+    opciones.renames = None;
+    let parent = get_log_parents(commit, cliente.clone())?
+        .first()
+        .cloned()
+        .unwrap_or_default();
+    let files = get_commit_diff_files(parent, commit.to_string(), &opciones, cliente)?;
+    res.push_str("---\n");
+    res.push_str(&format_stat(&files, &opciones));
+    res.push('\n');
+    res.push_str(&format_diff_files(&files, &opciones));
+    res.push_str("-- \ngitr\n\n");
+    Ok(res)
+}
+
+/// Escribe un patch con formato de mail por cada commit del rango y devuelve los
+/// archivos escritos, o con --stdout devuelve los mails en lugar de escribirlos.
+pub fn get_format_patch(flags: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    let error = || GitrError::InvalidArgumentError(flags.join(" "), FORMAT_PATCH_USAGE.to_string());
+    let mut directorio = get_current_repo(cliente.clone())?;
+    let mut stdout = false;
+    let mut rango = None;
+    let mut args = flags.iter();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "-o" | "--output-directory" => directorio = args.next().ok_or_else(error)?.clone(),
+            "--stdout" => stdout = true,
+            _ if rango.is_none() => rango = Some(flag.clone()),
+            _ => return Err(error()),
+        }
+    }
+    let commits = get_format_patch_commits(&rango.ok_or_else(error)?, cliente.clone())?;
+
+    let mut res = String::new();
+    for (i, commit) in commits.iter().enumerate() {
+        let mail = format_patch_mail(commit, i + 1, commits.len(), cliente.clone())?;
+        if stdout {
+            res.push_str(&mail);
+            continue;
+        }
+        let (subject, _) = get_log_subject_and_body(commit, cliente.clone())?;
+        let archivo = format!(
+            "{}/{:04}-{}.patch",
+            directorio,
+            i + 1,
+            patch_file_slug(&subject)
+        );
+        let _ = fs::create_dir_all(&directorio);
+        file_manager::write_file(archivo.clone(), mail)?;
+        res.push_str(&format!("{}\n", archivo));
+    }
+    Ok(res)
+}
+
+/***************************
+ ***************************
+ *    AM FUNCTIONS
+ **************************
+ **************************/
+
+// un patch recibido por mail: identidad del autor, mensaje del commit y el diff
+struct MailPatch {
+    autor: String,
+    mensaje: String,
+    diff: String,
+}
+
+// separa un mbox en sus mails, cada uno empieza con "From <hash> "
+fn split_mbox(texto: &str) -> Vec<String> {
+    let mut mails: Vec<String> = Vec::new();
+    for linea in texto.split_inclusive('\n') {
+        let separador = linea
+            .strip_prefix("From ")
+            .and_then(|resto| resto.split(' ').next())
+            .is_some_and(|hash| hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit()));
+        match mails.last_mut() {
+            Some(mail) if !separador => mail.push_str(linea),
+            _ => mails.push(linea.to_string()),
+        }
+    }
+    mails
+}
+
+fn parse_mail_patch(mail: &str) -> Result<MailPatch, GitrError> {
+    let invalido = |motivo: &str| GitrError::PatchError(tr("patch.invalid_mail", &[&motivo]));
+    let (headers, cuerpo) = mail
+        .split_once("\n\n")
+        .ok_or_else(|| invalido(&tr("patch.mail_without_headers", &[])))?;
+    // los headers largos pueden seguir en las lineas siguientes, empezando con espacio
+    let mut campos: Vec<(String, String)> = Vec::new();
+    for linea in headers.lines() {
+        match (linea.starts_with([' ', '\t']), campos.last_mut()) {
+            (true, Some((_, valor))) => valor.push_str(linea),
+            _ => {
+                if let Some((nombre, valor)) = linea.split_once(": ") {
+                    campos.push((nombre.to_string(), valor.to_string()));
+                }
+            }
+        }
+    }
+    let campo = |nombre: &str| {
+        campos
+            .iter()
+            .find(|(n, _)| n == nombre)
+            .map(|(_, valor)| valor.trim().to_string())
+            .ok_or_else(|| invalido(&tr("patch.mail_missing_header", &[&nombre])))
+    };
+    let from = campo("From")?;
+    let fecha = chrono::DateTime::parse_from_rfc2822(&campo("Date")?)
+        .map_err(|_| invalido(&tr("patch.mail_invalid_date", &[])))?;
+    let mut subject = campo("Subject")?;
+    if subject.starts_with("[PATCH") {
+        if let Some((_, resto)) = subject.split_once("] ") {
+            subject = resto.to_string();
+        }
+    }
+
+    let inicio_diff = cuerpo.find("diff --git ").unwrap_or(cuerpo.len());
+    let (mensaje, diff) = cuerpo.split_at(inicio_diff);
+    let body = match mensaje
+        .rfind("\n---\n")
+        .or(mensaje.starts_with("---\n").then_some(0))
+    {
+        Some(fin) => &mensaje[..fin],
+        None => mensaje,
+    };
+    let mensaje = match body.trim() {
+        "" => subject,
+        body => format!("{}\n\n{}", subject, body),
+    };
+    let autor = match from.rsplit_once(" <") {
+        Some((nombre, mail)) => format!("{} <{}", nombre.trim_matches('"'), mail),
+        None => format!("{} <{}>", from, from),
+    };
+    Ok(MailPatch {
+        autor: format!("{} {} {}", autor, fecha.timestamp(), fecha.format("%z")),
+        mensaje,
+        diff: diff.to_string(),
+    })
+}
+
+// aplica el patch del mail y crea un commit con el autor y el mensaje originales
+fn apply_mail_patch(mail: &MailPatch, cliente: String) -> Result<String, GitrError> {
+    let patches = parse_patch(&mail.diff)?;
+    if patches.is_empty() {
+        return Err(GitrError::PatchError(tr("patch.mail_without_patches", &[])));
+    }
+    let destino = ApplyTarget {
+        worktree: true,
+        index: true,
+        three_way: false,
+    };
+    apply_patches(&patches, &destino, false, cliente.clone())?;
+    let (tree_map, tree_order) = get_hashmap_for_checkout(cliente.clone())?;
+    let final_tree = create_trees(tree_map, tree_order[0].clone(), cliente.clone())?;
+    let commit = Commit::new_with_author(
+        final_tree.get_hash(),
+        vec![get_current_commit(cliente.clone())?],
+        mail.autor.clone(),
+        get_current_username(cliente.clone()),
+        mail.mensaje.clone(),
+        cliente.clone(),
+    )?;
+    commit.save(cliente.clone())?;
+    let dir = get_current_repo(cliente.clone())? + "/gitr/" + &get_head(cliente)?;
+    file_manager::write_file(dir, commit.get_hash())?;
+    Ok(commit.get_hash())
+}
+
+/// Aplica los patches de los mails de cada archivo (mbox) creando un commit por cada uno.
+/// Si un patch no aplica se detiene sin tocar nada de ese patch.
+pub fn get_am(files: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    if files.is_empty() {
+        return Err(GitrError::InvalidArgumentError(
+            String::new(),
+            "am <mbox>...".to_string(),
+        ));
+    }
+    let mut res = String::new();
+    for file in files {
+        for mail in split_mbox(&file_manager::read_file(file)?) {
+            let mail = parse_mail_patch(&mail)?;
+            res.push_str(&format!(
+                "{}\n",
+                tr(
+                    "am.applying",
+                    &[&mail.mensaje.lines().next().unwrap_or_default()]
+                )
+            ));
+            apply_mail_patch(&mail, cliente.clone())?;
+        }
+    }
+    Ok(res)
+}
+
+/***************************
+ ***************************
+ *    ADD FUNCTIONS
+ **************************
+ **************************/
+
+pub fn save_and_add_blob_to_index(file_path: String, cliente: String) -> Result<(), GitrError> {
+    match path_is_ignored(vec![file_path.clone()], cliente.clone()) {
+        Ok(r) => {
+            if r {
+                return Ok(());
+            }
+        }
+        Err(e) => return Err(e),
+    }
+    let raw_data = read_worktree_file(&file_path, cliente.clone())?;
+    let blob = Blob::new(raw_data)?;
+    blob.save(cliente.clone())?;
+    let hash = blob.get_hash();
+    file_manager::add_to_index(&file_path, &hash, cliente.clone())?;
+    Ok(())
+}
+
+pub fn update_index_before_add(cliente: String) -> Result<(), GitrError> {
+    let repo = file_manager::get_current_repo(cliente.clone())?;
+    let index_path = &(repo.clone() + "/gitr/index");
+    if Path::new(index_path).is_file() {
+        let index_data = file_manager::read_index(cliente.clone())?;
+        let mut index_vector: Vec<&str> = Vec::new();
+        if !index_data.is_empty() {
+            index_vector = index_data.split('\n').collect::<Vec<&str>>();
+        }
+        let mut i: i32 = 0;
+        while i != index_vector.len() as i32 {
+            let entry = index_vector[i as usize];
+            let path_to_check = entry.splitn(4, ' ').collect::<Vec<&str>>()[3];
+            if !Path::new(path_to_check).exists() {
+                index_vector.remove(i as usize);
+                i -= 1;
+            }
+            i += 1;
+        }
+        file_manager::remove_file(index_path.to_string())?;
+        for entry in index_vector {
+            let path = entry.splitn(4, ' ').collect::<Vec<&str>>()[3];
+            save_and_add_blob_to_index(path.to_string(), cliente.clone())?;
+        }
+    }
+    Ok(())
+}
+
+pub fn add_files_command(file_path: String, cliente: String) -> Result<(), GitrError> {
+    let repo = get_current_repo(cliente.clone())?;
+    if file_path == "." {
+        let files = visit_dirs(std::path::Path::new(&repo));
+        for file in files {
+            // el directorio gitr y el gitrignore de la raiz no se agregan, pero si el
+            // gitrattributes
+            let relativo = file.strip_prefix(&(repo.clone() + "/")).unwrap_or(&file);
+            if relativo.starts_with("gitr/") || relativo == "gitrignore" {
+                continue;
+            }
+            save_and_add_blob_to_index(file.clone(), cliente.clone())?;
+
+        }
+    } else {
+        let full_file_path = repo + "/" + &file_path;
+        save_and_add_blob_to_index(full_file_path, cliente)?;
+    }
+    Ok(())
+}
+
+/***************************
+ ***************************
+ *   RM FUNCTIONS
+ **************************
+ **************************/
+
+pub fn rm_from_index(file_to_delete: &str, cliente: String) -> Result<bool, GitrError> {
+    let mut removed: bool = false;
+    let mut index = file_manager::read_index(cliente.clone())?;
+    index += "\n";
+    let current_repo = file_manager::get_current_repo(cliente)?;
+    let file_to_rm_path = format!("{}/{}", current_repo, file_to_delete);
+    for line in index.lines() {
+        let attributes = line.splitn(4, ' ').collect::<Vec<&str>>();
+        if attributes[3] == file_to_rm_path {
+            let complete_line = format!("{}\n", line);
+            index = index.replace(&complete_line, "");
+            let res = index.trim_end().to_string();
+            removed = true;
+            let compressed_index = flate2compress(res)?;
+            let _ = file_manager::write_compressed_data(
+                &(current_repo + "/gitr/index"),
+                compressed_index.as_slice(),
+            );
+            break;
+        }
+    }
+    Ok(removed)
+}
+
+/***************************
+ ***************************
+ *    TAG FUNCTIONS
+ **************************
+ **************************/
+
+pub fn create_lightweight_tag(tag_name: String, cliente: String) -> Result<(), GitrError> {
+    let current_commit = match get_current_commit(cliente.clone()) {
+        Ok(commit) => commit,
+        Err(_) => {
+            println!("fatal: Failed to resolve 'HEAD' as a valid ref.");
+            return Ok(());
+        }
+    };
+    let tag_path = get_current_repo(cliente.clone())? + "/gitr/refs/tags/" + &tag_name;
+    if Path::new(&tag_path).exists() {
+        return Err(GitrError::TagAlreadyExistsError(tag_name.clone()));
+    }
+    file_manager::write_file(tag_path, current_commit)?;
+    Ok(())
+}
+
+pub fn create_annotated_tag(
+    tag_name: String,
+    tag_message: String,
+    cliente: String,
+) -> Result<(), GitrError> {
+    let current_commit = match get_current_commit(cliente.clone()) {
+        Ok(commit) => commit,
+        Err(_) => {
+            println!("fatal: Failed to resolve 'HEAD' as a valid ref.");
+            return Ok(());
+        }
+    };
+    let tag_path = get_current_repo(cliente.clone())? + "/gitr/refs/tags/" + &tag_name;
+    if Path::new(&tag_path).exists() {
+        return Err(GitrError::TagAlreadyExistsError(tag_name.clone()));
+    }
+    let tag = Tag::new(tag_name, tag_message, current_commit, cliente.clone())?;
+    tag.save(cliente.clone())?;
+    file_manager::write_file(tag_path, tag.get_hash())?;
+    Ok(())
+}
+
+pub fn get_tags_str(cliente: String) -> Result<String, GitrError> {
+    let tags = file_manager::get_tags(cliente)?;
+    let mut tag_str = String::new();
+    for t in tags {
+        tag_str.push_str(&(t + "\n"))
+    }
+    Ok(tag_str.strip_suffix('\n').unwrap_or("").to_string())
+}
+/***************************
+ ***************************
+ *    CLONE FUNCTIONS
+ **************************
+ **************************/
+
+pub fn write_reference_from_cloning(
+    references: Vec<(String, String)>,
+    _ref_disc: String,
+    cliente: String,
+) -> Result<(), GitrError> {
+    let repo = file_manager::get_current_repo(cliente.clone())?;
+    for reference in &references[1..] {
+        let path_str = repo.clone() + "/gitr/" + &reference.1.clone();
+        if references[0].0 == reference.0 {
+            file_manager::update_head(&reference.1.clone(), cliente.clone())?;
+        }
+        let into_hash = reference.0.clone();
+        file_manager::write_file(path_str, into_hash)?;
+    }
+    Ok(())
+}
+
+pub fn clone_connect_to_server(address: String) -> Result<TcpStream, GitrError> {
+    let socket = match TcpStream::connect(address) {
+        Ok(socket) => socket,
+        Err(_) => {
+            return Err(GitrError::InvalidArgumentError(
+                "address".to_string(),
+                "localhost:9418".to_string(),
+            ))
+        }
+    };
+    Ok(socket)
+}
+
+pub fn clone_send_git_upload_pack(socket: &mut TcpStream) -> Result<usize, GitrError> {
+    match socket.write("0031git-upload-pack /mi-repo\0host=localhost:9418\0".as_bytes()) {
+        //51 to hexa =
+        Ok(bytes) => Ok(bytes),
+        Err(e) => Err(GitrError::SocketError(
+            "clone_send_git_upload_pack()".to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+pub fn clone_read_reference_discovery(socket: &mut TcpStream) -> Result<String, GitrError> {
+    let mut buffer = [0; 1024];
+    let mut response = String::new();
+    loop {
+        let bytes_read = match socket.read(&mut buffer) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                return Err(GitrError::SocketError(
+                    "clone_read_reference_discovery()".to_string(),
+                    e.to_string(),
+                ))
+            }
+        };
+        let received_message = String::from_utf8_lossy(&buffer[..bytes_read]).to_string();
+        if bytes_read == 0 || received_message == "0000" {
+            break;
+        }
+        response.push_str(&received_message);
+    }
+    Ok(response)
+}
+
+pub fn write_socket(socket: &mut TcpStream, message: &[u8]) -> Result<(), GitrError> {
+    match socket.write(message) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(GitrError::SocketError(
+            "write_socket()".to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+pub fn read_socket(socket: &mut TcpStream, buffer: &mut [u8]) -> Result<(), GitrError> {
+    let bytes_read = match socket.read(buffer) {
         Ok(bytes) => bytes,
         Err(e) => {
             return Err(GitrError::SocketError(
@@ -1534,119 +5449,517 @@ pub fn read_socket(socket: &mut TcpStream, buffer: &mut [u8]) -> Result<(), Gitr
             ))
         }
     };
-    let _received_data = String::from_utf8_lossy(&buffer[..bytes_read]);
+    let _received_data = String::from_utf8_lossy(&buffer[..bytes_read]);
+    Ok(())
+}
+
+/***************************
+ ***************************
+ *    PULL FUNCTIONS
+ **************************
+ **************************/
+
+pub fn handshake(orden: String, cliente: String) -> Result<TcpStream, GitrError> {
+    handshake_remote(orden, file_manager::get_remote(cliente)?)
+}
+
+/// like handshake, but with the remote (url/name) given instead of the configured one
+pub fn handshake_remote(orden: String, remote: String) -> Result<TcpStream, GitrError> {
+    let url_n_name = remote.split('/').collect::<Vec<&str>>();
+    if url_n_name.len() != 2 {
+        return Err(GitrError::InvalidArgumentError(
+            tr("remote.missing", &[]),
+            "url/name".to_string(),
+        ));
+    }
+    let msj = format!("{} /{}\0host={}\0", orden, url_n_name[1], url_n_name[0]);
+    let msj = format!("{:04x}{}", msj.len() + 4, msj);
+    let mut stream = match TcpStream::connect(url_n_name[0]) {
+        Ok(s) => s,
+        Err(e) => {
+            println!("Error: {}", e);
+            return Err(GitrError::ConnectionError);
+        }
+    };
+    match stream.write(msj.as_bytes()) {
+        Ok(_) => (),
+        Err(e) => {
+            println!("Error: {}", e);
+            return Err(GitrError::ConnectionError);
+        }
+    };
+    Ok(stream)
+}
+
+pub fn protocol_reference_discovery(
+    stream: &mut TcpStream,
+) -> Result<Vec<(String, String)>, GitrError> {
+    let mut buffer = Vec::new();
+    while !buffer.ends_with("0000".as_bytes()) {
+        if buffer.starts_with("Error".as_bytes()) {
+            println!("{}", String::from_utf8_lossy(&buffer));
+            return Err(GitrError::ConnectionError);
+        }
+        let aux = match read_long_stream(stream) {
+            Ok(buf) => buf,
+            Err(e) => {
+                println!("{}",e);
+                return Err(GitrError::ConnectionError);
+            }
+        };
+        buffer.extend(aux);
+    }
+    let ref_disc = String::from_utf8_lossy(&buffer).to_string();
+    let hash_n_references = ref_discovery::discover_references(ref_disc)?;
+    Ok(hash_n_references)
+}
+
+pub fn protocol_wants_n_haves(
+    hash_n_references: Vec<(String, String)>,
+    stream: &mut TcpStream,
+    cliente: String,
+) -> Result<bool, GitrError> {
+    let mut refs_ids = file_manager::get_refs_ids("heads", cliente.clone())?;
+    refs_ids.append(file_manager::get_refs_ids("tags", cliente.clone())?.as_mut());
+    let want_message =
+        ref_discovery::assemble_want_message(&hash_n_references, refs_ids, cliente.clone())?;
+    match stream.write(want_message.as_bytes()) {
+        Ok(_) => (),
+        Err(e) => {
+            println!("Error: {}", e);
+            return Err(GitrError::ConnectionError);
+        }
+    };
+    if want_message == "0000" {
+        return Ok(false);
+    }
+    let _ = stream.write(&0_usize.to_be_bytes());
+
+    let mut buffer = [0; 1024];
+    match stream.read(&mut buffer) {
+        Ok(_n) => {
+            if String::from_utf8_lossy(&buffer).contains("Error") {
+                println!("Error: {}", String::from_utf8_lossy(&buffer));
+                return Ok(false);
+            }
+        }
+        Err(e) => {
+            println!("Error: {}", e);
+            return Err(GitrError::ConnectionError);
+        }
+    }
+    Ok(true)
+}
+
+pub fn pull_packfile(stream: &mut TcpStream, cliente: String) -> Result<(), GitrError> {
+    let mut buf = match ref_discovery::read_long_stream(stream) {
+        Ok(buf) => buf,
+        Err(e) => {
+            println!("Error: {}", e);
+            return Err(GitrError::ConnectionError);
+        }
+    };
+    if buf.is_empty() {
+        println!("{}", tr("fetch.empty_packfile", &[]));
+        return Ok(());
+    }
+    let pack_file_struct = PackFile::new_from_server_packfile(&mut buf)?;
+    for object in pack_file_struct.objects.iter() {
+        match object {
+            GitObject::Blob(blob) => blob.save(cliente.clone())?,
+            GitObject::Commit(commit) => commit.save(cliente.clone())?,
+            GitObject::Tree(tree) => tree.save(cliente.clone())?,
+            GitObject::Tag(tag) => tag.save(cliente.clone())?,
+        }
+    }
+    Ok(())
+}
+
+/***************************
+ ***************************
+ *    PUSH FUNCTIONS
+ **************************
+ **************************/
+
+pub fn reference_update_request(
+    stream: &mut TcpStream,
+    hash_n_references: Vec<(String, String)>,
+    cliente: String,
+) -> Result<(bool, Vec<String>, Vec<String>), GitrError> {
+    let ids_propios = (
+        file_manager::get_refs_ids("heads", cliente.clone())?,
+        file_manager::get_refs_ids("tags", cliente.clone())?,
+    ); // esta sacando de gitr/refs/heads y tags
+    let refs_propios = (
+        get_branches(cliente.clone())?,
+        get_tags(cliente.clone())?,
+    ); // tambien de gitr/refs/heads y tags
+    let (ref_upd, pkt_needed, pkt_ids) = match ref_discovery::reference_update_request(
+        hash_n_references.clone(),
+        ids_propios,
+        refs_propios,
+    ) {
+        Ok((ref_upd, pkt_needed, pkt_ids)) => (ref_upd, pkt_needed, pkt_ids),
+        Err(e) => {
+            match stream.write("0000".as_bytes()) {
+                Ok(_) => (),
+                Err(_) => {return Err(GitrError::ConnectionError)}
+            }
+            return Err(e);
+        }
+    };
+    
+    if ref_upd != "0000" {
+        if let Err(e) = run_pre_push_hook(&ref_upd, cliente.clone()) {
+            if stream.write("0000".as_bytes()).is_err() {
+                return Err(GitrError::ConnectionError);
+            }
+            return Err(e);
+        }
+    }
+    if let Err(e) = stream.write(ref_upd.as_bytes()) {
+        println!("Error: {}", e);
+        return Err(GitrError::ConnectionError);
+    };
+    if ref_upd == "0000" {
+        println!("client up to date");
+        return Ok((false, Vec::new(), Vec::new()));
+    }
+    // cada linea es "<largo><old> <new> <ref>"
+    let refs = ref_upd
+        .lines()
+        .filter_map(|linea| linea.get(4..)?.split(' ').nth(2))
+        .map(|referencia| referencia.to_string())
+        .collect();
+    Ok((pkt_needed, pkt_ids, refs))
+}
+
+/// pre-push recibe el remote y por stdin "<ref local> <hash local> <ref remota> <hash remoto>"
+/// por cada referencia que se va a actualizar
+fn run_pre_push_hook(ref_upd: &str, cliente: String) -> Result<(), GitrError> {
+    let mut entrada = String::new();
+    for linea in ref_upd.lines() {
+        let campos: Vec<&str> = linea.get(4..).unwrap_or("").split(' ').collect();
+        if let [viejo, nuevo, referencia] = campos.as_slice() {
+            entrada += &format!("{} {} {} {}\n", referencia, nuevo, referencia, viejo);
+        }
+    }
+    let remote = file_manager::get_remote(cliente.clone())?;
+    run_hook(
+        "pre-push",
+        &["origin".to_string(), remote],
+        &entrada,
+        cliente,
+    )
+}
+
+pub fn push_packfile(
+    stream: &mut TcpStream,
+    pkt_ids: Vec<String>,
+    hash_n_references: Vec<(String, String)>,
+    cliente: String,
+) -> Result<(), GitrError> {
+    let repo = file_manager::get_current_repo(cliente.clone())? + "/gitr";
+    let all_pkt_commits = Commit::get_parents(
+        pkt_ids.clone(),
+        hash_n_references.iter().map(|t| t.0.clone()).collect(),
+        repo.clone(),
+    )?;
+    let ids = Commit::get_objects_from_commits(all_pkt_commits, vec![], repo.clone())?;
+    let mut contents: Vec<Vec<u8>> = Vec::new();
+    for id in ids {
+        contents.push(file_manager::get_object_bytes(id, repo.clone())?)
+    }
+    let cont: Vec<(String, String, Vec<u8>)> =
+        crate::git_transport::pack_file::prepare_contents(contents.clone());
+    let pk = crate::git_transport::pack_file::create_packfile(cont.clone())?;
+    if let Err(e) = stream.write(&pk) {
+        println!("Error: {}", e);
+        return Err(GitrError::ConnectionError);
+    };
+    Ok(())
+}
+/// Lee el reporte del server despues del push: muestra la salida de sus hooks y
+/// devuelve error si rechazo alguna referencia
+pub fn read_push_report(stream: &mut TcpStream, refs: &[String]) -> Result<(), GitrError> {
+    let _ = stream.shutdown(std::net::Shutdown::Write);
+    let mut respuesta = String::new();
+    if stream.read_to_string(&mut respuesta).is_err() {
+        return Err(GitrError::ConnectionError);
+    }
+    let (salida, rechazadas) = parse_push_report(&respuesta, refs)?;
+    print!("{}", salida);
+    if !rechazadas.is_empty() {
+        return Err(GitrError::PushError(tr(
+            "push.rejected",
+            &[&rechazadas.join(", ")],
+        )));
+    }
     Ok(())
 }
 
+/// Parsea las pkt-lines del reporte del push. Devuelve lo que hay que mostrarle al
+/// usuario (la salida de los hooks y los rechazos) y las referencias rechazadas. Cada
+/// referencia pusheada tiene que tener su "ok" o "ng"; si falta alguna, el push fallo.
+pub fn parse_push_report(
+    respuesta: &str,
+    refs: &[String],
+) -> Result<(String, Vec<String>), GitrError> {
+    let invalida = || GitrError::PushError(tr("push.invalid_report", &[]));
+    let mut salida = String::new();
+    let mut rechazadas = vec![];
+    let mut aceptadas = vec![];
+    let mut resto = respuesta;
+    while resto.len() >= 4 && !resto.starts_with("0000") {
+        let largo = usize::from_str_radix(&resto[..4], 16).map_err(|_| invalida())?;
+        let linea = resto.get(4..largo).ok_or_else(invalida)?;
+        resto = &resto[largo..];
+        if let Some(rechazo) = linea.strip_prefix("ng ") {
+            salida.push_str(&format!("! [remote rejected] {}\n", rechazo.trim_end()));
+            rechazadas.push(rechazo.split(' ').next().unwrap_or("").to_string());
+        } else if let Some(aceptada) = linea.strip_prefix("ok ") {
+            aceptadas.push(aceptada.trim_end().to_string());
+        } else if linea.starts_with("remote: ") {
+            salida.push_str(linea);
+        }
+    }
+    if let Some(falta) = refs
+        .iter()
+        .find(|r| !aceptadas.contains(r) && !rechazadas.contains(r))
+    {
+        return Err(GitrError::PushError(tr("push.ref_not_reported", &[falta])));
+    }
+    Ok((salida, rechazadas))
+}
 /***************************
  ***************************
- *    PULL FUNCTIONS
+ *    BUNDLE FUNCTIONS
  **************************
  **************************/
 
-pub fn handshake(orden: String, cliente: String) -> Result<TcpStream, GitrError> {
+const BUNDLE_SIGNATURE: &str = "# v2 git bundle\n";
 
-    let remote = file_manager::get_remote(cliente.clone())?;
-    let url_n_name = remote.split('/').collect::<Vec<&str>>();
-    if url_n_name.len() != 2 {
-        return Err(GitrError::InvalidArgumentError(
-            "<no se recibio un remote>".to_string(),
-            "url/name".to_string(),
-        ));
+// un bundle es un header con los commits requeridos ("-<hash>") y las refs que
+// contiene ("<hash> <ref>"), una linea vacia y despues un packfile
+struct Bundle {
+    prerequisites: Vec<String>,
+    refs: Vec<(String, String)>,
+    pack: Vec<u8>,
+}
+
+fn read_bundle(path: &str) -> Result<Bundle, GitrError> {
+    let bytes = fs::read(path).map_err(|_| GitrError::FileReadError(path.to_string()))?;
+    let invalido = || GitrError::BundleError(tr("bundle.invalid", &[&path]));
+    if !bytes.starts_with(BUNDLE_SIGNATURE.as_bytes()) {
+        return Err(invalido());
     }
-    let msj = format!("{} /{}\0host={}\0", orden, url_n_name[1], url_n_name[0]);
-    let msj = format!("{:04x}{}", msj.len() + 4, msj);
-    let mut stream = match TcpStream::connect(url_n_name[0]) {
-        Ok(s) => s,
-        Err(e) => {
-            println!("Error: {}", e);
-            return Err(GitrError::ConnectionError);
-        }
+    let mut bundle = Bundle {
+        prerequisites: Vec::new(),
+        refs: Vec::new(),
+        pack: Vec::new(),
     };
-    match stream.write(msj.as_bytes()) {
-        Ok(_) => (),
-        Err(e) => {
-            println!("Error: {}", e);
-            return Err(GitrError::ConnectionError);
+    let mut pos = BUNDLE_SIGNATURE.len();
+    loop {
+        let fin = match bytes[pos..].iter().position(|b| *b == b'\n') {
+            Some(fin) => pos + fin,
+            None => return Err(invalido()),
+        };
+        let linea = String::from_utf8_lossy(&bytes[pos..fin]).to_string();
+        pos = fin + 1;
+        if linea.is_empty() {
+            break;
         }
-    };
-    Ok(stream)
+        match linea.strip_prefix('-') {
+            Some(requerido) => bundle
+                .prerequisites
+                .push(requerido.split(' ').next().unwrap_or_default().to_string()),
+            None => match linea.split_once(' ') {
+                Some((hash, referencia)) => {
+                    bundle.refs.push((hash.to_string(), referencia.to_string()))
+                }
+                None => return Err(invalido()),
+            },
+        }
+    }
+    bundle.pack = bytes[pos..].to_vec();
+    if bundle.pack.len() < 32 || !bundle.pack.starts_with(b"PACK") {
+        return Err(invalido());
+    }
+    Ok(bundle)
 }
 
-pub fn protocol_reference_discovery(
-    stream: &mut TcpStream,
-) -> Result<Vec<(String, String)>, GitrError> {
-    let mut buffer = Vec::new();
-    while !buffer.ends_with("0000".as_bytes()) {
-        if buffer.starts_with("Error".as_bytes()) {
-            println!("{}", String::from_utf8_lossy(&buffer));
-            return Err(GitrError::ConnectionError);
+/// Devuelve true si el path es un archivo bundle, para usarlo como remote.
+pub fn is_bundle(path: &str) -> bool {
+    let mut firma = [0u8; BUNDLE_SIGNATURE.len()];
+    match fs::File::open(path) {
+        Ok(mut archivo) => {
+            archivo.read_exact(&mut firma).is_ok() && firma == BUNDLE_SIGNATURE.as_bytes()
         }
-        let aux = match read_long_stream(stream) {
-            Ok(buf) => buf,
-            Err(e) => {
-                println!("{}",e);
-                return Err(GitrError::ConnectionError);
-            }
-        };
-        buffer.extend(aux);
+        Err(_) => false,
     }
-    let ref_disc = String::from_utf8_lossy(&buffer).to_string();
-    let hash_n_references = ref_discovery::discover_references(ref_disc)?;
-    Ok(hash_n_references)
 }
 
-pub fn protocol_wants_n_haves(
-    hash_n_references: Vec<(String, String)>,
-    stream: &mut TcpStream,
-    cliente: String,
-) -> Result<bool, GitrError> {
-    let mut refs_ids = file_manager::get_refs_ids("heads", cliente.clone())?;
-    refs_ids.append(file_manager::get_refs_ids("tags", cliente.clone())?.as_mut());
-    let want_message =
-        ref_discovery::assemble_want_message(&hash_n_references, refs_ids, cliente.clone())?;
-    match stream.write(want_message.as_bytes()) {
-        Ok(_) => (),
-        Err(e) => {
-            println!("Error: {}", e);
-            return Err(GitrError::ConnectionError);
+// (hash, nombre completo) de la ref a la que se refiere el rev, si es una ref
+fn get_bundle_ref(rev: &str, cliente: String) -> Result<Option<(String, String)>, GitrError> {
+    let repo = get_current_repo(cliente.clone())?;
+    if rev == "HEAD" {
+        return Ok(Some((get_current_commit(cliente)?, "HEAD".to_string())));
+    }
+    for referencia in [
+        format!("refs/heads/{}", rev),
+        format!("refs/tags/{}", rev),
+        rev.to_string(),
+    ] {
+        let path = format!("{}/gitr/{}", repo, referencia);
+        if referencia.starts_with("refs/") && Path::new(&path).is_file() {
+            let hash = file_manager::read_file(path)?.trim().to_string();
+            return Ok(Some((hash, referencia)));
         }
-    };
-    if want_message == "0000" {
-        return Ok(false);
     }
-    let _ = stream.write(&0_usize.to_be_bytes());
+    Ok(None)
+}
 
-    let mut buffer = [0; 1024];
-    match stream.read(&mut buffer) {
-        Ok(_n) => {
-            if String::from_utf8_lossy(&buffer).contains("Error") {
-                println!("Error: {}", String::from_utf8_lossy(&buffer));
-                return Ok(false);
+/// Escribe en el archivo un bundle con las refs pedidas y los objetos de los commits
+/// alcanzables desde ellas que no lo son desde los excluidos (`A..B`, `^A`).
+pub fn bundle_create(file: String, revs: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    let mut incluidos = Vec::new();
+    let mut excluidos = Vec::new();
+    for rev in revs.iter() {
+        if rev == "--all" {
+            incluidos.extend(file_manager::get_branches(cliente.clone())?);
+            incluidos.extend(file_manager::get_tags(cliente.clone()).unwrap_or_default());
+        } else if let Some((desde, hasta)) = rev.split_once("..") {
+            excluidos.push(desde.to_string());
+            incluidos.push(hasta.to_string());
+        } else if let Some(excluido) = rev.strip_prefix('^') {
+            excluidos.push(excluido.to_string());
+        } else {
+            incluidos.push(rev.clone());
+        }
+    }
+
+    let mut refs: Vec<(String, String)> = Vec::new();
+    let mut puntas = Vec::new();
+    for rev in incluidos {
+        puntas.push(resolve_commit(rev.clone(), cliente.clone())?);
+        if let Some(referencia) = get_bundle_ref(&rev, cliente.clone())? {
+            if !refs.contains(&referencia) {
+                refs.push(referencia);
             }
         }
-        Err(e) => {
-            println!("Error: {}", e);
-            return Err(GitrError::ConnectionError);
+    }
+    let mut alcanzables = HashSet::new();
+    for punta in puntas {
+        alcanzables.extend(get_ancestors(&punta, cliente.clone())?);
+    }
+    for excluido in excluidos {
+        let excluido = resolve_commit(excluido, cliente.clone())?;
+        for commit in get_ancestors(&excluido, cliente.clone())? {
+            alcanzables.remove(&commit);
         }
     }
-    Ok(true)
-}
+    if refs.is_empty() || alcanzables.is_empty() {
+        return Err(GitrError::BundleError(tr("bundle.empty", &[])));
+    }
 
-pub fn pull_packfile(stream: &mut TcpStream, cliente: String) -> Result<(), GitrError> {
-    let mut buf = match ref_discovery::read_long_stream(stream) {
-        Ok(buf) => buf,
-        Err(e) => {
-            println!("Error: {}", e);
-            return Err(GitrError::ConnectionError);
+    // los padres que quedaron afuera son los commits que necesita quien reciba el bundle
+    let mut requeridos = Vec::new();
+    for commit in alcanzables.iter() {
+        for parent in get_log_parents(commit, cliente.clone())? {
+            if !alcanzables.contains(&parent) && !requeridos.contains(&parent) {
+                requeridos.push(parent);
+            }
         }
+    }
+    requeridos.sort();
+
+    let repo = get_current_repo(cliente.clone())? + "/gitr";
+    let mut ids = alcanzables.into_iter().collect::<Vec<String>>();
+    ids.extend(refs.iter().map(|(hash, _)| hash.clone()));
+    let objetos_requeridos = match requeridos.is_empty() {
+        true => vec![],
+        false => Commit::get_objects_from_commits(requeridos.clone(), vec![], repo.clone())?,
     };
-    if buf.is_empty() {
-        println!("Error: packfile vacío");
-        return Ok(());
+    let mut contents = Vec::new();
+    for id in Commit::get_objects_from_commits(ids, objetos_requeridos, repo.clone())? {
+        contents.push(file_manager::get_object_bytes(id, repo.clone())?);
     }
-    let pack_file_struct = PackFile::new_from_server_packfile(&mut buf)?;
-    for object in pack_file_struct.objects.iter() {
+    let pack = crate::git_transport::pack_file::create_packfile(
+        crate::git_transport::pack_file::prepare_contents(contents),
+    )?;
+
+    let mut header = BUNDLE_SIGNATURE.to_string();
+    for requerido in requeridos {
+        header.push_str(&format!(
+            "-{} {}\n",
+            requerido,
+            get_log_subject_and_body(&requerido, cliente.clone())?.0
+        ));
+    }
+    for (hash, referencia) in refs {
+        header.push_str(&format!("{} {}\n", hash, referencia));
+    }
+    header.push('\n');
+    let mut bytes = header.into_bytes();
+    bytes.extend(pack);
+    fs::write(&file, bytes).map_err(|_| GitrError::FileWriteError(file))
+}
+
+fn bundle_heads(bundle: &Bundle) -> String {
+    bundle
+        .refs
+        .iter()
+        .map(|(hash, referencia)| format!("{} {}\n", hash, referencia))
+        .collect()
+}
+
+/// Lista las refs que contiene el bundle.
+pub fn bundle_list_heads(file: String) -> Result<String, GitrError> {
+    Ok(bundle_heads(&read_bundle(&file)?))
+}
+
+/// Verifica que el packfile del bundle este completo y que el repo tenga todos los
+/// commits que el bundle requiere.
+pub fn bundle_verify(file: String, cliente: String) -> Result<String, GitrError> {
+    let mut bundle = read_bundle(&file)?;
+    let (datos, checksum) = bundle.pack.split_at(bundle.pack.len() - 20);
+    if sha1hashing2(datos.to_vec()) != checksum {
+        return Err(GitrError::BundleError(tr("bundle.corrupt_pack", &[&file])));
+    }
+    for requerido in bundle.prerequisites.iter() {
+        if read_object_body(requerido, cliente.clone()).is_err() {
+            return Err(GitrError::BundleError(tr(
+                "bundle.missing_prerequisite",
+                &[&requerido],
+            )));
+        }
+    }
+    crate::git_transport::pack_file::read_pack_file(&mut bundle.pack)?;
+
+    let mut res = match bundle.refs.len() {
+        1 => tr("bundle.contains_ref", &[]),
+        n => tr("bundle.contains_refs", &[&n]),
+    } + "\n";
+    res.push_str(&bundle_heads(&bundle));
+    match bundle.prerequisites.len() {
+        0 => res.push_str(&tr("bundle.complete_history", &[])),
+        1 => res.push_str(&tr("bundle.requires_ref", &[])),
+        n => res.push_str(&tr("bundle.requires_refs", &[&n])),
+    }
+    res.push('\n');
+    for requerido in bundle.prerequisites.iter() {
+        res.push_str(&format!("{}\n", requerido));
+    }
+    res.push_str(&format!("{}\n", tr("bundle.ok", &[&file])));
+    Ok(res)
+}
+
+/// Guarda los objetos del bundle como si vinieran de un remote y devuelve sus refs
+/// en el mismo formato que el reference discovery, con HEAD primero.
+pub fn fetch_bundle(file: String, cliente: String) -> Result<Vec<(String, String)>, GitrError> {
+    bundle_verify(file.clone(), cliente.clone())?;
+    let mut bundle = read_bundle(&file)?;
+    for object in crate::git_transport::pack_file::read_pack_file(&mut bundle.pack)?.iter() {
         match object {
             GitObject::Blob(blob) => blob.save(cliente.clone())?,
             GitObject::Commit(commit) => commit.save(cliente.clone())?,
@@ -1654,144 +5967,459 @@ pub fn pull_packfile(stream: &mut TcpStream, cliente: String) -> Result<(), Gitr
             GitObject::Tag(tag) => tag.save(cliente.clone())?,
         }
     }
-    Ok(())
+    // sin HEAD en el bundle se usa la primera branch
+    let mut refs = bundle.refs;
+    let head = refs
+        .iter()
+        .position(|(_, referencia)| referencia == "HEAD")
+        .map(|i| refs.remove(i))
+        .or_else(|| {
+            refs.iter()
+                .find(|(_, referencia)| referencia.starts_with("refs/heads/"))
+                .map(|(hash, _)| (hash.clone(), "HEAD".to_string()))
+        });
+    if let Some((hash, _)) = head.as_ref() {
+        file_manager::write_file(
+            get_current_repo(cliente)? + "/gitr/FETCH_HEAD",
+            hash.clone(),
+        )?;
+    }
+    Ok(head.into_iter().chain(refs).collect())
+}
+
+/*******************
+ * REBASE FUNCTIONS
+ * *****************/
+
+/// directory inside gitr where the state of an ongoing rebase is kept
+fn rebase_state_dir(cliente: String) -> Result<String, GitrError> {
+    Ok(get_current_repo(cliente)? + "/gitr/rebase-merge")
+}
+
+fn write_rebase_state(file: &str, data: String, cliente: String) -> Result<(), GitrError> {
+    file_manager::write_file(rebase_state_dir(cliente)? + "/" + file, data)
+}
+
+fn read_rebase_state(file: &str, cliente: String) -> Result<String, GitrError> {
+    file_manager::read_file(rebase_state_dir(cliente)? + "/" + file)
+}
+
+/// returns true if there is a rebase stopped waiting for --continue, --skip or --abort
+pub fn rebase_in_progress(cliente: String) -> Result<bool, GitrError> {
+    Ok(Path::new(&rebase_state_dir(cliente)?).is_dir())
+}
+
+/// returns the files of the working directory that still have conflict markers
+fn files_with_conflict_markers(cliente: String) -> Result<Vec<String>, GitrError> {
+    let repo = get_current_repo(cliente)?;
+    let mut conflicted = vec![];
+    for file in visit_dirs(Path::new(&repo)) {
+        let data = match fs::read_to_string(&file) {
+            Ok(data) => data,
+            Err(_) => continue,
+        };
+        if data.contains("<<<<<<< HEAD") && data.contains(">>>>>>> BRANCH") {
+            conflicted.push(file);
+        }
+    }
+    Ok(conflicted)
+}
+
+/// adds the working directory and writes a new commit on top of HEAD with the message of commit_old
+fn commit_rebased(commit_old: String, cliente: String) -> Result<(), GitrError> {
+    commands_fn::add(vec![".".to_string()], cliente.clone())?;
+    let (tree_map, tree_order) = get_hashmap_for_checkout(cliente.clone())?;
+    let final_tree = create_trees(tree_map, tree_order[0].clone(), cliente.clone())?;
+    let last_commit = get_current_commit(cliente.clone())?;
+    let message = file_manager::get_commit_message(commit_old, cliente.clone())?;
+    let commit = Commit::new(
+        final_tree.get_hash(),
+        vec![last_commit],
+        get_current_username(cliente.clone()),
+        get_current_username(cliente.clone()),
+        message.trim_end().to_string(),
+        cliente.clone(),
+    )?;
+    commit.save(cliente.clone())?;
+    let dir = get_current_repo(cliente.clone())? + "/gitr/" + &get_head(cliente.clone())?;
+    file_manager::write_file(dir, commit.get_hash())?;
+    Ok(())
+}
+
+/// applies the commits left in the todo file one by one, stopping on the first conflict
+fn apply_rebase_todo(cliente: String) -> Result<(), GitrError> {
+    loop {
+        let todo = read_rebase_state("todo", cliente.clone())?;
+        let mut pending = todo.lines().filter(|l| !l.is_empty());
+        let commit_old = match pending.next() {
+            Some(commit) => commit.to_string(),
+            None => break,
+        };
+        write_rebase_state(
+            "todo",
+            pending.collect::<Vec<&str>>().join("\n"),
+            cliente.clone(),
+        )?;
+
+        let last_commit = get_current_commit(cliente.clone())?;
+        let mut parent =
+            file_manager::get_parent_commit(commit_old.clone(), cliente.clone())?[0].clone();
+        if parent == "None" {
+            parent = "".to_string();
+        }
+        let (hubo_conflict, archivos_conflict) =
+            three_way_merge(parent, last_commit, commit_old.clone(), cliente.clone())?;
+        if hubo_conflict || !archivos_conflict.is_empty() {
+            write_rebase_state("current", commit_old.clone(), cliente.clone())?;
+            let message = file_manager::get_commit_message(commit_old.clone(), cliente.clone())?;
+            println!(
+                "{}",
+                tr(
+                    "rebase.conflict",
+                    &[&&commit_old[..7], &message.lines().next().unwrap_or("")]
+                )
+            );
+            for archivo in archivos_conflict {
+                println!("\t{}", tr("rebase.both_modified", &[&archivo]));
+            }
+            println!("{}", tr("rebase.resolve_hint", &[]));
+            println!("{}", tr("rebase.skip_hint", &[]));
+            println!("{}", tr("rebase.abort_hint", &[]));
+            return Ok(());
+        }
+        commit_rebased(commit_old, cliente.clone())?;
+    }
+    let head_name = read_rebase_state("head-name", cliente.clone())?;
+    file_manager::remove_directory(rebase_state_dir(cliente)?)?;
+    println!("{}", tr("rebase.success", &[&head_name]));
+    Ok(())
+}
+
+/// saves the rebase state, moves the branch onto origin_name and starts applying the commits
+pub fn create_rebase_commits(
+    to_rebase_commits: Vec<String>,
+    origin_name: String,
+    cliente: String,
+) -> Result<(), GitrError> {
+    let onto: String = get_commit(origin_name, cliente.clone())?;
+    let orig_head = get_current_commit(cliente.clone())?;
+    let head = get_head(cliente.clone())?;
+    let repo = get_current_repo(cliente.clone())?;
+
+    file_manager::create_directory(&rebase_state_dir(cliente.clone())?)?;
+    write_rebase_state("head-name", head.clone(), cliente.clone())?;
+    write_rebase_state("onto", onto.clone(), cliente.clone())?;
+    write_rebase_state("orig-head", orig_head.clone(), cliente.clone())?;
+    let todo = to_rebase_commits
+        .iter()
+        .rev()
+        .cloned()
+        .collect::<Vec<String>>()
+        .join("\n");
+    write_rebase_state("todo", todo, cliente.clone())?;
+    file_manager::write_file(repo.clone() + "/gitr/ORIG_HEAD", orig_head)?;
+
+    file_manager::write_file(repo + "/gitr/" + &head, onto.clone())?;
+    update_working_directory(onto, cliente.clone())?;
+    apply_rebase_todo(cliente)
+}
+
+/// rebase --continue: commits the resolved conflict and keeps applying the remaining commits
+pub fn rebase_continue(cliente: String) -> Result<(), GitrError> {
+    if !rebase_in_progress(cliente.clone())? {
+        return Err(GitrError::NoRebaseInProgress);
+    }
+    if let Ok(current) = read_rebase_state("current", cliente.clone()) {
+        let conflicted = files_with_conflict_markers(cliente.clone())?;
+        if !conflicted.is_empty() {
+            return Err(GitrError::UnresolvedConflicts(conflicted.join(", ")));
+        }
+        commit_rebased(current, cliente.clone())?;
+        file_manager::remove_file(rebase_state_dir(cliente.clone())? + "/current")?;
+    }
+    apply_rebase_todo(cliente)
+}
+
+/// rebase --skip: drops the commit that caused the conflict and keeps applying the remaining commits
+pub fn rebase_skip(cliente: String) -> Result<(), GitrError> {
+    if !rebase_in_progress(cliente.clone())? {
+        return Err(GitrError::NoRebaseInProgress);
+    }
+    if read_rebase_state("current", cliente.clone()).is_ok() {
+        update_working_directory(get_current_commit(cliente.clone())?, cliente.clone())?;
+        file_manager::remove_file(rebase_state_dir(cliente.clone())? + "/current")?;
+    }
+    apply_rebase_todo(cliente)
+}
+
+/// rebase --abort: restores the branch and the working directory to ORIG_HEAD
+pub fn rebase_abort(cliente: String) -> Result<(), GitrError> {
+    if !rebase_in_progress(cliente.clone())? {
+        return Err(GitrError::NoRebaseInProgress);
+    }
+    let head_name = read_rebase_state("head-name", cliente.clone())?;
+    let orig_head = read_rebase_state("orig-head", cliente.clone())?;
+    let repo = get_current_repo(cliente.clone())?;
+    file_manager::write_file(repo + "/gitr/" + &head_name, orig_head.clone())?;
+    file_manager::update_head(&head_name, cliente.clone())?;
+    update_working_directory(orig_head, cliente.clone())?;
+    file_manager::remove_directory(rebase_state_dir(cliente)?)?;
+    Ok(())
+}
+
+/*******************
+ * BISECT FUNCTIONS
+ * *****************/
+
+/// files inside gitr where the state of an ongoing bisect is kept (BISECT_START, BISECT_BAD, ...)
+fn bisect_state_path(file: &str, cliente: String) -> Result<String, GitrError> {
+    Ok(get_current_repo(cliente)? + "/gitr/BISECT_" + file)
+}
+
+fn read_bisect_state(file: &str, cliente: String) -> Result<String, GitrError> {
+    let path = bisect_state_path(file, cliente)?;
+    match Path::new(&path).is_file() {
+        true => Ok(file_manager::read_file(path)?.trim().to_string()),
+        false => Ok(String::new()),
+    }
+}
+
+fn write_bisect_state(file: &str, data: String, cliente: String) -> Result<(), GitrError> {
+    file_manager::write_file(bisect_state_path(file, cliente)?, data)
+}
+
+fn append_bisect_state(file: &str, line: String, cliente: String) -> Result<(), GitrError> {
+    let mut data = read_bisect_state(file, cliente.clone())?;
+    if !data.is_empty() {
+        data.push('\n');
+    }
+    data.push_str(&line);
+    write_bisect_state(file, data, cliente)
+}
+
+/// returns true if there is a bisect started and not reset yet
+pub fn bisect_in_progress(cliente: String) -> Result<bool, GitrError> {
+    Ok(Path::new(&bisect_state_path("START", cliente)?).is_file())
+}
+
+// "[hash] subject" of a commit
+fn bisect_commit_line(commit: &str, cliente: String) -> Result<String, GitrError> {
+    let (subject, _) = get_log_subject_and_body(commit, cliente)?;
+    Ok(format!("[{}] {}", commit, subject))
+}
+
+/// bisect start [<bad> [<good>...]]: saves the current head and marks the commits received
+pub fn bisect_start(revs: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    if bisect_in_progress(cliente.clone())? {
+        return Err(GitrError::BisectInProgress);
+    }
+    let mut commits = Vec::new();
+    for rev in revs.iter() {
+        commits.push(resolve_commit(rev.clone(), cliente.clone())?);
+    }
+    write_bisect_state("START", get_head(cliente.clone())?, cliente.clone())?;
+    write_bisect_state("LOG", "bisect start".to_string(), cliente.clone())?;
+    let mut res = String::new();
+    for (i, commit) in commits.into_iter().enumerate() {
+        let term = if i == 0 { "bad" } else { "good" };
+        res = bisect_mark(term, vec![commit], cliente.clone())?;
+    }
+    Ok(res)
+}
+
+/// bisect good|bad|skip [<rev>...]: marks the commits (HEAD by default) and checks out the next one to test
+pub fn bisect_mark(term: &str, revs: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    if !bisect_in_progress(cliente.clone())? {
+        return Err(GitrError::NoBisectInProgress);
+    }
+    let mut commits = Vec::new();
+    for rev in revs.iter() {
+        commits.push(resolve_commit(rev.clone(), cliente.clone())?);
+    }
+    if commits.is_empty() {
+        commits.push(get_current_commit(cliente.clone())?);
+    }
+    for commit in commits {
+        match term {
+            "bad" => write_bisect_state("BAD", commit.clone(), cliente.clone())?,
+            "good" => append_bisect_state("GOOD", commit.clone(), cliente.clone())?,
+            _ => append_bisect_state("SKIP", commit.clone(), cliente.clone())?,
+        }
+        let line = format!(
+            "# {}: {}\nbisect {} {}",
+            term,
+            bisect_commit_line(&commit, cliente.clone())?,
+            term,
+            commit
+        );
+        append_bisect_state("LOG", line, cliente.clone())?;
+    }
+    bisect_next(cliente)
+}
+
+// commits that are ancestors of bad (bad included) and not of any good one, newest first
+fn bisect_candidates(
+    bad: &str,
+    goods: &[String],
+    cliente: String,
+) -> Result<Vec<String>, GitrError> {
+    let excluidos = match goods.is_empty() {
+        true => HashSet::new(),
+        false => file_manager::rev_list(goods.to_vec(), cliente.clone())?
+            .into_iter()
+            .collect::<HashSet<String>>(),
+    };
+    Ok(file_manager::rev_list(vec![bad.to_string()], cliente)?
+        .into_iter()
+        .filter(|commit| !excluidos.contains(commit))
+        .collect())
+}
+
+// picks the commit that best halves the candidates, checks it out and describes what is left
+fn bisect_next(cliente: String) -> Result<String, GitrError> {
+    let bad = read_bisect_state("BAD", cliente.clone())?;
+    let goods = read_bisect_state("GOOD", cliente.clone())?
+        .lines()
+        .map(|l| l.to_string())
+        .collect::<Vec<String>>();
+    if bad.is_empty() || goods.is_empty() {
+        let falta = match (bad.is_empty(), goods.is_empty()) {
+            (true, true) => tr("bisect.waiting_both", &[]),
+            (true, false) => tr("bisect.waiting_bad", &[]),
+            _ => tr("bisect.waiting_good", &[]),
+        };
+        return Ok(format!("status: {}\n", falta));
+    }
+    let skips = read_bisect_state("SKIP", cliente.clone())?
+        .lines()
+        .map(|l| l.to_string())
+        .collect::<HashSet<String>>();
+    let candidatos = bisect_candidates(&bad, &goods, cliente.clone())?;
+    if candidatos.len() <= 1 {
+        let res = format!(
+            "{}\n{}",
+            tr("bisect.first_bad", &[&bad]),
+            file_manager::format_commit_log_entry(bad.clone(), cliente.clone())?
+        );
+        append_bisect_state(
+            "LOG",
+            format!(
+                "# first bad commit: {}",
+                bisect_commit_line(&bad, cliente.clone())?
+            ),
+            cliente,
+        )?;
+        return Ok(res);
+    }
+    let probables = candidatos
+        .iter()
+        .filter(|commit| **commit != bad && !skips.contains(*commit))
+        .collect::<Vec<&String>>();
+    if probables.is_empty() {
+        let mut res = tr("bisect.only_skipped", &[]) + "\n";
+        for commit in candidatos.iter() {
+            res.push_str(&format!("{}\n", commit));
+        }
+        return Ok(res + &tr("bisect.cannot_continue", &[]) + "\n");
+    }
+
+    let conjunto = candidatos.iter().collect::<HashSet<&String>>();
+    let total = candidatos.len();
+    let mut mejor = (probables[0].clone(), 0);
+    for commit in probables {
+        let ancestros = file_manager::rev_list(vec![commit.clone()], cliente.clone())?
+            .iter()
+            .filter(|ancestro| conjunto.contains(ancestro))
+            .count();
+        let puntaje = ancestros.min(total - ancestros);
+        if puntaje > mejor.1 {
+            mejor = (commit.clone(), puntaje);
+        }
+    }
+    let siguiente = mejor.0;
+    write_bisect_state("HEAD", siguiente.clone(), cliente.clone())?;
+    file_manager::update_head(&"BISECT_HEAD".to_string(), cliente.clone())?;
+    update_working_directory(siguiente.clone(), cliente.clone())?;
+
+    let restantes = (total - 1) / 2;
+    let pasos = usize::BITS - restantes.leading_zeros();
+    Ok(format!(
+        "{}\n{}\n",
+        tr("bisect.bisecting", &[&restantes, &pasos]),
+        bisect_commit_line(&siguiente, cliente)?
+    ))
 }
 
-/***************************
- ***************************
- *    PUSH FUNCTIONS
- **************************
- **************************/
-
-pub fn reference_update_request(
-    stream: &mut TcpStream,
-    hash_n_references: Vec<(String, String)>,
-    cliente: String,
-) -> Result<(bool, Vec<String>), GitrError> {
-    let ids_propios = (
-        file_manager::get_refs_ids("heads", cliente.clone())?,
-        file_manager::get_refs_ids("tags", cliente.clone())?,
-    ); // esta sacando de gitr/refs/heads y tags
-    let refs_propios = (
-        get_branches(cliente.clone())?,
-        get_tags(cliente.clone())?,
-    ); // tambien de gitr/refs/heads y tags
-    let (ref_upd, pkt_needed, pkt_ids) = match ref_discovery::reference_update_request(
-        hash_n_references.clone(),
-        ids_propios,
-        refs_propios,
-    ) {
-        Ok((ref_upd, pkt_needed, pkt_ids)) => (ref_upd, pkt_needed, pkt_ids),
-        Err(e) => {
-            match stream.write("0000".as_bytes()) {
-                Ok(_) => (),
-                Err(_) => {return Err(GitrError::ConnectionError)}
-            }
-            return Err(e);
+/// bisect reset: goes back to the head saved by bisect start and removes the bisect state
+pub fn bisect_reset(cliente: String) -> Result<String, GitrError> {
+    if !bisect_in_progress(cliente.clone())? {
+        return Err(GitrError::NoBisectInProgress);
+    }
+    let head = read_bisect_state("START", cliente.clone())?;
+    file_manager::update_head(&head, cliente.clone())?;
+    let commit = get_current_commit(cliente.clone())?;
+    update_working_directory(commit.clone(), cliente.clone())?;
+    for file in ["START", "BAD", "GOOD", "SKIP", "LOG", "HEAD"] {
+        let path = bisect_state_path(file, cliente.clone())?;
+        if Path::new(&path).is_file() {
+            file_manager::remove_file(path)?;
         }
-    };
-    
-    if let Err(e) = stream.write(ref_upd.as_bytes()) {
-        println!("Error: {}", e);
-        return Err(GitrError::ConnectionError);
-    };
-    if ref_upd == "0000" {
-        println!("client up to date");
-        return Ok((false, Vec::new()));
     }
-    Ok((pkt_needed, pkt_ids))
+    Ok(format!(
+        "{}\n",
+        tr(
+            "bisect.previous_head",
+            &[&bisect_commit_line(&commit, cliente)?]
+        )
+    ))
 }
 
-pub fn push_packfile(
-    stream: &mut TcpStream,
-    pkt_ids: Vec<String>,
-    hash_n_references: Vec<(String, String)>,
-    cliente: String,
-) -> Result<(), GitrError> {
-    let repo = file_manager::get_current_repo(cliente.clone())? + "/gitr";
-    let all_pkt_commits = Commit::get_parents(
-        pkt_ids.clone(),
-        hash_n_references.iter().map(|t| t.0.clone()).collect(),
-        repo.clone(),
-    )?;
-    let ids = Commit::get_objects_from_commits(all_pkt_commits, vec![], repo.clone())?;
-    let mut contents: Vec<Vec<u8>> = Vec::new();
-    for id in ids {
-        contents.push(file_manager::get_object_bytes(id, repo.clone())?)
+/// bisect log: the marks made since bisect start
+pub fn bisect_log(cliente: String) -> Result<String, GitrError> {
+    if !bisect_in_progress(cliente.clone())? {
+        return Err(GitrError::NoBisectInProgress);
     }
-    let cont: Vec<(String, String, Vec<u8>)> =
-        crate::git_transport::pack_file::prepare_contents(contents.clone());
-    let pk = crate::git_transport::pack_file::create_packfile(cont.clone())?;
-    if let Err(e) = stream.write(&pk) {
-        println!("Error: {}", e);
-        return Err(GitrError::ConnectionError);
-    };
-    Ok(())
+    Ok(read_bisect_state("LOG", cliente)? + "\n")
 }
-/*******************
- * REBASE FUNCTIONS
- * *****************/
 
-fn check_conflicts_and_get_tree(
-    origin_commit: String,
-    branch_commit: String,
-    base_commit: String,
-    cliente: String,
-) -> Result<String, GitrError> {
-    let (hubo_conflict, _) =
-        three_way_merge(base_commit, branch_commit, origin_commit, cliente.clone())?;
-    if hubo_conflict {
-        loop {
-            println!("conflicts detected, please resolve them and then run '--continue'");
-            print!("$ ");
-            let mut input = String::new();
-            match io::stdin().read_line(&mut input) {
-                Ok(_) => (),
-                Err(_e) => return Err(GitrError::InputError),
-            }
-            if input.trim() == "--continue" {
-                break;
+/// bisect run <cmd>...: runs the command on each commit to test and marks it by its exit code:
+/// 0 good, 125 skip, 1 to 127 bad; anything else stops the bisect run
+pub fn bisect_run(command: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    if !bisect_in_progress(cliente.clone())? {
+        return Err(GitrError::NoBisectInProgress);
+    }
+    if read_bisect_state("BAD", cliente.clone())?.is_empty()
+        || read_bisect_state("GOOD", cliente.clone())?.is_empty()
+    {
+        return Err(GitrError::BisectRunError(tr("bisect.run_needs_terms", &[])));
+    }
+    let repo = get_current_repo(cliente.clone())?;
+    let mut res = String::new();
+    loop {
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(command.join(" "))
+            .current_dir(&repo)
+            .status()
+            .map_err(|e| GitrError::BisectRunError(e.to_string()))?;
+        let term = match status.code() {
+            Some(0) => "good",
+            Some(125) => "skip",
+            Some(code) if (1..128).contains(&code) => "bad",
+            _ => {
+                return Err(GitrError::BisectRunError(tr(
+                    "error.command_exited_with",
+                    &[&command.join(" "), &status],
+                )))
             }
+        };
+        // si bisect_mark no hizo checkout de otro commit ya no queda nada para probar
+        let anterior = read_bisect_state("HEAD", cliente.clone())?;
+        res.push_str(&bisect_mark(term, vec![], cliente.clone())?);
+        if read_bisect_state("HEAD", cliente.clone())? == anterior {
+            return Ok(res);
         }
     }
-    commands_fn::add(vec![".".to_string()], cliente.clone())?;
-    let (tree_map, tree_order) = get_hashmap_for_checkout(cliente.clone())?;
-    let final_tree = create_trees(tree_map, tree_order[0].clone(), cliente.clone())?;
-    Ok(final_tree.get_hash())
-}
-
-pub fn create_rebase_commits(
-    to_rebase_commits: Vec<String>,
-    origin_name: String,
-    cliente: String,
-    commit_base: String,
-) -> Result<(), GitrError> {
-    let mut last_commit: String = get_commit(origin_name, cliente.clone())?;
-    let head = get_head(cliente.clone())?;
-    let path = get_current_repo(cliente.clone())? + "/gitr/" + &head;
-    file_manager::write_file(path, last_commit.clone())?;
-    for commit_old in to_rebase_commits.iter().rev() {
-        let main_tree = check_conflicts_and_get_tree(
-            last_commit.clone(),
-            commit_old.to_string(),
-            commit_base.clone(),
-            cliente.clone(),
-        )?;
-        let message = file_manager::get_commit_message(commit_old.clone(), cliente.clone())?;
-        let commit = Commit::new(
-            main_tree.clone(),
-            vec![last_commit.clone()],
-            cliente.clone(),
-            cliente.clone(),
-            message.clone(),
-            cliente.clone(),
-        )?;
-        commit.save(cliente.clone())?;
-        let dir = get_current_repo(cliente.clone())? + "/gitr/" + &get_head(cliente.clone())?;
-        file_manager::write_file(dir, commit.get_hash())?;
-        last_commit = commit.get_hash();
-    }
-    Ok(())
 }
 
 /*******************
@@ -1808,28 +6436,228 @@ fn path_is_ignored(paths: Vec<String>, client: String) -> Result<bool, GitrError
     Ok(true)
 }
 
-pub fn check_ignore_(paths: Vec<String>, client: String) -> Result<Vec<String>, GitrError> {
-    let path = armar_path("gitrignore".to_string(), client.clone())?;
-    let gitignore = match file_manager::read_file(path){
-        Ok(data) => data,
-        Err(_) => return Ok(vec![]),
+const CHECK_IGNORE_USAGE: &str = "check-ignore [-v] <path>...";
+
+// una linea de un archivo gitrignore
+#[derive(Clone)]
+pub struct IgnoreRule {
+    // archivo del que salio la regla y numero de linea
+    pub origen: String,
+    pub linea: usize,
+    // la linea tal cual esta en el archivo
+    pub texto: String,
+    patron: String,
+    pub negada: bool,
+    solo_dirs: bool,
+    anclada: bool,
+    // directorio (relativo al repo) del gitrignore, vacio o terminado en '/'
+    base: String,
+}
+
+/// Matchea un glob de gitrignore contra un path: `*` y `?` no matchean '/', `[...]` es
+/// una clase (negada con `!` o `^`) y `**` matchea cualquier cantidad de directorios.
+fn glob_match(patron: &[char], path: &[char]) -> bool {
+    match patron {
+        [] => path.is_empty(),
+        ['*', '*', '/', resto @ ..] => {
+            glob_match(resto, path)
+                || path
+                    .iter()
+                    .enumerate()
+                    .any(|(i, c)| *c == '/' && glob_match(resto, &path[i + 1..]))
+        }
+        ['*', '*'] => true,
+        ['*', resto @ ..] => {
+            let hasta = path.iter().position(|c| *c == '/').unwrap_or(path.len());
+            (0..=hasta).any(|i| glob_match(resto, &path[i..]))
+        }
+        ['?', resto @ ..] => !path.is_empty() && path[0] != '/' && glob_match(resto, &path[1..]),
+        ['[', clase @ ..] => {
+            let negada = matches!(clase.first(), Some('!' | '^'));
+            let clase = &clase[negada as usize..];
+            // un ']' al principio es parte de la clase
+            let Some(fin) = clase.iter().skip(1).position(|c| *c == ']').map(|i| i + 1) else {
+                return !path.is_empty() && path[0] == '[' && glob_match(&patron[1..], &path[1..]);
+            };
+            let Some(c) = path.first().filter(|c| **c != '/') else {
+                return false;
+            };
+            let mut en_clase = false;
+            let mut i = 0;
+            while i < fin {
+                if i + 2 < fin && clase[i + 1] == '-' {
+                    en_clase |= clase[i] <= *c && *c <= clase[i + 2];
+                    i += 3;
+                } else {
+                    en_clase |= clase[i] == *c;
+                    i += 1;
+                }
+            }
+            en_clase != negada && glob_match(&clase[fin + 1..], &path[1..])
+        }
+        ['\\', c, resto @ ..] | [c, resto @ ..] => {
+            !path.is_empty() && path[0] == *c && glob_match(resto, &path[1..])
+        }
+    }
+}
+
+// parsea las reglas de un gitrignore cuyo directorio (relativo al repo) es base
+fn parse_ignore_rules(data: &str, origen: &str, base: &str) -> Vec<IgnoreRule> {
+    let mut reglas = Vec::new();
+    for (i, texto) in data.lines().enumerate() {
+        let mut patron = texto.trim_end();
+        if patron.is_empty() || patron.starts_with('#') {
+            continue;
+        }
+        let negada = patron.starts_with('!');
+        if negada {
+            patron = &patron[1..];
+        }
+        if patron.starts_with("\\#") || patron.starts_with("\\!") {
+            patron = &patron[1..];
+        }
+        let solo_dirs = patron.ends_with('/');
+        let patron = patron.trim_end_matches('/');
+        // un '/' al principio o en el medio ancla el patron al directorio del archivo
+        let anclada = patron.contains('/');
+        if patron.is_empty() {
+            continue;
+        }
+        reglas.push(IgnoreRule {
+            origen: origen.to_string(),
+            linea: i + 1,
+            texto: texto.to_string(),
+            patron: patron.trim_start_matches('/').to_string(),
+            negada,
+            solo_dirs,
+            anclada,
+            base: base.to_string(),
+        });
+    }
+    reglas
+}
+
+// reglas que aplican a un path relativo al repo, de menor a mayor prioridad: el
+// gitrignore global del cliente, el de la raiz del repo y los de cada subdirectorio
+fn get_ignore_rules(path: &str, cliente: String) -> Result<Vec<IgnoreRule>, GitrError> {
+    let repo = file_manager::get_current_repo(cliente.clone())?;
+    let global = Config::load(&cliente)?
+        .get("core.excludesfile")
+        .unwrap_or(format!("{}/gitrignore", cliente));
+    let mut reglas = match file_manager::read_file(global.clone()) {
+        Ok(data) => parse_ignore_rules(&data, &global, ""),
+        Err(_) => vec![],
+    };
+    for base in parent_dirs(path) {
+        let origen = base.clone() + "gitrignore";
+        if let Ok(data) = file_manager::read_file(format!("{}/{}", repo, origen)) {
+            reglas.extend(parse_ignore_rules(&data, &origen, &base));
+        }
+    }
+    Ok(reglas)
+}
+
+// "a/b/file" -> ["", "a/", "a/b/"]
+fn parent_dirs(path: &str) -> Vec<String> {
+    let mut dirs = vec![String::new()];
+    let mut partes = path.split('/').collect::<Vec<&str>>();
+    partes.pop();
+    for parte in partes {
+        dirs.push(format!("{}{}/", dirs[dirs.len() - 1], parte));
+    }
+    dirs
+}
+
+// matchea un patron de un archivo que esta en el directorio base: los anclados contra
+// el path relativo a base y el resto contra el nombre del archivo
+fn pattern_matches(patron: &str, anclada: bool, base: &str, path: &str) -> bool {
+    let Some(relativo) = path.strip_prefix(base) else {
+        return false;
     };
-    let lineas_ignore: Vec<&str> = gitignore.split('\n').collect();
-    let mut lineas_full: Vec<String> = vec![];
-    let repo = file_manager::get_current_repo(client.clone())?;
-    for linea in lineas_ignore {
-        lineas_full.push(repo.to_owned() + linea);
+    let objetivo = match anclada {
+        true => relativo,
+        false => relativo.rsplit('/').next().unwrap_or(relativo),
+    };
+    glob_match(
+        &patron.chars().collect::<Vec<char>>(),
+        &objetivo.chars().collect::<Vec<char>>(),
+    )
+}
+
+fn ignore_rule_matches(regla: &IgnoreRule, path: &str, es_dir: bool) -> bool {
+    (!regla.solo_dirs || es_dir) && pattern_matches(&regla.patron, regla.anclada, &regla.base, path)
+}
+
+/// Devuelve la regla que decide si un path (con el repo adelante) se ignora: la
+/// ultima que lo matchea, o la que ignora alguno de sus directorios padre. Puede ser
+/// una regla negada, en cuyo caso el path no se ignora.
+pub fn get_ignore_match(full_path: &str, cliente: String) -> Result<Option<IgnoreRule>, GitrError> {
+    let repo = file_manager::get_current_repo(cliente.clone())?;
+    let Some(path) = full_path
+        .strip_prefix(&(repo.clone() + "/"))
+        .map(|p| p.trim_end_matches('/'))
+    else {
+        return Ok(None);
+    };
+    let reglas = get_ignore_rules(path, cliente)?;
+    let partes = path.split('/').collect::<Vec<&str>>();
+    for i in 1..=partes.len() {
+        let parcial = partes[..i].join("/");
+        let es_dir = i < partes.len() || Path::new(&format!("{}/{}", repo, parcial)).is_dir();
+        let regla = reglas
+            .iter()
+            .rev()
+            .find(|regla| ignore_rule_matches(regla, &parcial, es_dir));
+        // si un directorio padre se ignora no se puede volver a incluir lo que tiene adentro
+        match regla {
+            Some(regla) if i == partes.len() || !regla.negada => return Ok(Some(regla.clone())),
+            _ => continue,
+        }
     }
+    Ok(None)
+}
+
+// devuelve los paths (con el repo adelante) que se ignoran
+pub fn check_ignore_(paths: Vec<String>, client: String) -> Result<Vec<String>, GitrError> {
     let mut ignored_paths = vec![];
     for path in paths {
-        if lineas_full.contains(&path) {
-            println!("path: {} is ignored", path);
+        if get_ignore_match(&path, client.clone())?.is_some_and(|regla| !regla.negada) {
             ignored_paths.push(path);
         }
     }
     Ok(ignored_paths)
 }
 
+/// Lista los paths (relativos al repo) que se ignoran. Con -v muestra tambien la regla
+/// que los matchea, como "origen:linea:patron\tpath", incluyendo las negadas.
+pub fn get_check_ignore(flags: Vec<String>, cliente: String) -> Result<String, GitrError> {
+    let error = || GitrError::InvalidArgumentError(flags.join(" "), CHECK_IGNORE_USAGE.to_string());
+    let verbose = flags.iter().any(|f| f == "-v" || f == "--verbose");
+    let paths = flags
+        .iter()
+        .filter(|f| *f != "-v" && *f != "--verbose")
+        .collect::<Vec<&String>>();
+    if paths.is_empty() || paths.iter().any(|p| p.starts_with('-')) {
+        return Err(error());
+    }
+    let repo = file_manager::get_current_repo(cliente.clone())?;
+    let mut res = String::new();
+    for path in paths {
+        let full_path = format!("{}/{}", repo, path.trim_start_matches('/'));
+        match get_ignore_match(&full_path, cliente.clone())? {
+            Some(regla) if verbose => {
+                res += &format!(
+                    "{}:{}:{}\t{}\n",
+                    regla.origen, regla.linea, regla.texto, path
+                )
+            }
+            Some(regla) if !regla.negada => res += &format!("{}\n", path),
+            _ => {}
+        }
+    }
+    Ok(res)
+}
+
 pub fn armar_path(path: String, cliente: String) -> Result<String, GitrError> {
     let full_path = vec![
         file_manager::get_current_repo(cliente)?,
@@ -1839,6 +6667,168 @@ pub fn armar_path(path: String, cliente: String) -> Result<String, GitrError> {
     Ok(full_path.concat())
 }
 
+/*******************
+ * ATTRIBUTES FUNCTIONS
+ * *****************/
+
+// valor de un atributo: "attr", "-attr" o "attr=valor"
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttrValue {
+    Set,
+    Unset,
+    Value(String),
+}
+
+// una linea de un archivo gitrattributes. Los atributos con "!attr" quedan en None,
+// sin especificar
+struct AttrRule {
+    patron: String,
+    anclada: bool,
+    base: String,
+    atributos: Vec<(String, Option<AttrValue>)>,
+}
+
+// parsea las reglas de un gitrattributes cuyo directorio (relativo al repo) es base
+fn parse_attr_rules(data: &str, base: &str) -> Vec<AttrRule> {
+    let mut reglas = Vec::new();
+    for linea in data.lines() {
+        let mut campos = linea.split_whitespace();
+        let Some(patron) = campos.next().filter(|p| !p.starts_with('#')) else {
+            continue;
+        };
+        let mut atributos = Vec::new();
+        for campo in campos {
+            let (nombre, valor) = match campo.split_once('=') {
+                Some((nombre, valor)) => (nombre, Some(AttrValue::Value(valor.to_string()))),
+                None => match campo.chars().next() {
+                    Some('-') => (&campo[1..], Some(AttrValue::Unset)),
+                    Some('!') => (&campo[1..], None),
+                    _ => (campo, Some(AttrValue::Set)),
+                },
+            };
+            // binary es lo mismo que -diff -merge -text
+            if nombre == "binary" && valor == Some(AttrValue::Set) {
+                for macro_attr in ["diff", "merge", "text"] {
+                    atributos.push((macro_attr.to_string(), Some(AttrValue::Unset)));
+                }
+            }
+            atributos.push((nombre.to_string(), valor));
+        }
+        reglas.push(AttrRule {
+            patron: patron.trim_start_matches('/').to_string(),
+            anclada: patron.contains('/'),
+            base: base.to_string(),
+            atributos,
+        });
+    }
+    reglas
+}
+
+// aplica en orden las reglas que matchean el path, las ultimas pisan a las primeras
+fn apply_attr_rules(reglas: &[AttrRule], path: &str, atributos: &mut HashMap<String, AttrValue>) {
+    for regla in reglas
+        .iter()
+        .filter(|regla| pattern_matches(&regla.patron, regla.anclada, &regla.base, path))
+    {
+        for (nombre, valor) in regla.atributos.iter() {
+            match valor {
+                Some(valor) => atributos.insert(nombre.clone(), valor.clone()),
+                None => atributos.remove(nombre),
+            };
+        }
+    }
+}
+
+/// Nombres del archivo de atributos de cada directorio. Si estan los dos, las reglas de
+/// .gitrattributes se aplican despues y tienen prioridad.
+pub const ATTRIBUTES_FILES: [&str; 2] = ["gitrattributes", ".gitrattributes"];
+
+/// Devuelve los atributos de un path (con el repo adelante) segun el gitrattributes de
+/// la raiz del repo y los de sus subdirectorios, donde los mas profundos tienen prioridad.
+pub fn get_attributes(
+    full_path: &str,
+    cliente: String,
+) -> Result<HashMap<String, AttrValue>, GitrError> {
+    let repo = file_manager::get_current_repo(cliente)?;
+    let mut atributos = HashMap::new();
+    let Some(path) = full_path.strip_prefix(&(repo.clone() + "/")) else {
+        return Ok(atributos);
+    };
+    for base in parent_dirs(path) {
+        for nombre in ATTRIBUTES_FILES {
+            if let Ok(data) = file_manager::read_file(format!("{}/{}{}", repo, base, nombre)) {
+                apply_attr_rules(&parse_attr_rules(&data, &base), path, &mut atributos);
+            }
+        }
+    }
+    Ok(atributos)
+}
+
+// los finales de linea se normalizan con text, o con text=auto o eol en archivos que no
+// parecen binarios
+fn is_text_file(atributos: &HashMap<String, AttrValue>, data: &str) -> bool {
+    match atributos.get("text") {
+        Some(AttrValue::Set) => true,
+        Some(AttrValue::Unset) => false,
+        Some(AttrValue::Value(_)) => !data.contains('\0'),
+        None => atributos.contains_key("eol") && !data.contains('\0'),
+    }
+}
+
+/// Lee un archivo del working tree con el contenido que se guarda en su blob: los
+/// archivos de texto segun gitrattributes se guardan con finales de linea LF.
+pub fn read_worktree_file(full_path: &str, cliente: String) -> Result<String, GitrError> {
+    let data = file_manager::read_file(full_path.to_string())?;
+    Ok(
+        match is_text_file(&get_attributes(full_path, cliente)?, &data) {
+            true => data.replace("\r\n", "\n"),
+            false => data,
+        },
+    )
+}
+
+/// Convierte el contenido de un blob al que se escribe en el working tree: los archivos
+/// de texto con eol=crlf se escriben con finales de linea CRLF.
+pub fn smudge_file_data(
+    full_path: &str,
+    data: String,
+    cliente: String,
+) -> Result<String, GitrError> {
+    let atributos = get_attributes(full_path, cliente)?;
+    let crlf = atributos.get("eol") == Some(&AttrValue::Value("crlf".to_string()));
+    Ok(match crlf && is_text_file(&atributos, &data) {
+        true => data.replace("\r\n", "\n").replace('\n', "\r\n"),
+        false => data,
+    })
+}
+
+// lee las reglas de los gitrattributes de un tree, de la raiz hacia adentro
+fn get_tree_attr_rules(
+    tree: &str,
+    directorio: &str,
+    cliente: String,
+    reglas: &mut Vec<AttrRule>,
+) -> Result<(), GitrError> {
+    let (_, body) = read_object_body(tree, cliente.clone())?;
+    let entradas = get_tree_entries_data(&body, cliente.clone())?;
+    for archivo in ATTRIBUTES_FILES {
+        for (_, tipo, hash, nombre) in entradas.iter() {
+            if tipo == "blob" && nombre == archivo {
+                let data =
+                    file_manager::read_file_data_from_blob_hash(hash.clone(), cliente.clone())?;
+                reglas.extend(parse_attr_rules(&data, directorio));
+            }
+        }
+    }
+    for (_, tipo, hash, nombre) in entradas {
+        if tipo == "tree" {
+            let directorio = format!("{}{}/", directorio, nombre);
+            get_tree_attr_rules(&hash, &directorio, cliente.clone(), reglas)?;
+        }
+    }
+    Ok(())
+}
+
 /*******************
  *   LS-TREE FUNCTIONS
  * *****************/
@@ -2657,6 +7647,144 @@ mod check_ignore_tests {
         assert!(!index.contains(&"cliente/repo_ignore/folder/file3".to_string()));
         fs::remove_dir_all(path).unwrap();
     }
+
+    #[test]
+    fn test04_glob_match_soporta_comodines_clases_y_doble_asterisco() {
+        let glob = |patron: &str, path: &str| {
+            glob_match(
+                &patron.chars().collect::<Vec<char>>(),
+                &path.chars().collect::<Vec<char>>(),
+            )
+        };
+        assert!(glob("*.log", "debug.log"));
+        assert!(!glob("*.log", "logs/debug.log"));
+        assert!(glob("file?.txt", "file1.txt"));
+        assert!(!glob("file?.txt", "file10.txt"));
+        assert!(glob("file[0-9]", "file7"));
+        assert!(!glob("file[!0-9]", "file7"));
+        assert!(glob("[]a]", "]"));
+        assert!(glob("**/build", "build"));
+        assert!(glob("**/build", "a/b/build"));
+        assert!(glob("docs/**", "docs/a/b.md"));
+        assert!(glob("a/**/z", "a/z"));
+        assert!(glob("a/**/z", "a/b/c/z"));
+        assert!(!glob("a/**/z", "b/a/z"));
+        assert!(glob("\\*", "*"));
+        assert!(!glob("\\*", "x"));
+    }
+
+    #[serial]
+    #[test]
+    fn test05_check_ignore_con_negaciones_directorios_y_gitrignore_anidados() {
+        let path = Path::new(&"cliente");
+        if path.exists() {
+            fs::remove_dir_all(path).unwrap();
+        }
+        file_manager::create_directory(&"cliente".to_string()).unwrap();
+        let cliente = "cliente".to_string();
+        commands_fn::init(vec!["repo_ignore".to_string()], cliente.clone()).unwrap();
+        file_manager::write_file("cliente/gitrignore".to_string(), "*.tmp\n".to_string()).unwrap();
+        file_manager::write_file(
+            "cliente/repo_ignore/gitrignore".to_string(),
+            "# logs\n*.log\n!importante.log\nbuild/\n/raiz\ndocs/**/*.pdf\n".to_string(),
+        )
+        .unwrap();
+        fs::create_dir_all("cliente/repo_ignore/sub/build").unwrap();
+        fs::create_dir_all("cliente/repo_ignore/docs/a/b").unwrap();
+        file_manager::write_file(
+            "cliente/repo_ignore/sub/build".to_string() + "/x",
+            "".to_string(),
+        )
+        .unwrap();
+        file_manager::write_file(
+            "cliente/repo_ignore/sub/gitrignore".to_string(),
+            "!debug.log\nlocal\n".to_string(),
+        )
+        .unwrap();
+        file_manager::write_file("cliente/repo_ignore/build".to_string(), "".to_string()).unwrap();
+
+        let ignorado = |p: &str| {
+            !check_ignore_(vec![format!("cliente/repo_ignore/{}", p)], cliente.clone())
+                .unwrap()
+                .is_empty()
+        };
+        assert!(ignorado("a.log"));
+        assert!(ignorado("sub/x/a.log"));
+        assert!(!ignorado("importante.log"));
+        assert!(!ignorado("sub/debug.log"));
+        assert!(ignorado("debug.log"));
+        assert!(ignorado("sub/local"));
+        assert!(!ignorado("local"));
+        // build/ solo ignora directorios, y todo lo que tienen adentro
+        assert!(ignorado("sub/build"));
+        assert!(ignorado("sub/build/x"));
+        assert!(!ignorado("build"));
+        assert!(ignorado("raiz"));
+        assert!(!ignorado("sub/raiz"));
+        assert!(ignorado("docs/a/b/manual.pdf"));
+        assert!(ignorado("docs/manual.pdf"));
+        assert!(!ignorado("manual.pdf"));
+        assert!(ignorado("sub/cache.tmp"));
+        assert!(!ignorado("# logs"));
+
+        let salida = get_check_ignore(
+            vec![
+                "-v".to_string(),
+                "sub/a.log".to_string(),
+                "sub/debug.log".to_string(),
+                "x.tmp".to_string(),
+                "nada".to_string(),
+            ],
+            cliente.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            salida,
+            "gitrignore:2:*.log\tsub/a.log\nsub/gitrignore:1:!debug.log\tsub/debug.log\ncliente/gitrignore:1:*.tmp\tx.tmp\n"
+        );
+        let salida = get_check_ignore(
+            vec!["sub/a.log".to_string(), "sub/debug.log".to_string()],
+            cliente,
+        )
+        .unwrap();
+        assert_eq!(salida, "sub/a.log\n");
+        fs::remove_dir_all(path).unwrap();
+    }
 }
 
+#[cfg(test)]
+mod apply_patch_tests {
+    use super::*;
+
+    fn aplicar(patch: &str, contenido: &str) -> Result<String, GitrError> {
+        let patches = parse_patch(patch)?;
+        apply_hunks(contenido, &patches[0].hunks)
+    }
+
+    #[test]
+    fn test01_agregar_lineas_a_un_archivo_sin_newline_final() {
+        let patch =
+            "--- a/f\n+++ b/f\n@@ -1,2 +1,3 @@\n x\n-y\n\\ No newline at end of file\n+y\n+z\n";
+        let hunks = &parse_patch(patch).unwrap()[0].hunks;
+        assert!(hunks[0].sin_newline_viejo);
+        assert!(!hunks[0].sin_newline_nuevo);
+        assert_eq!(aplicar(patch, "x\ny").unwrap(), "x\ny\nz\n");
+        // el lado viejo no termina en salto de linea, asi que no aplica si el archivo si
+        assert!(aplicar(patch, "x\ny\nw\n").is_err());
+    }
 
+    #[test]
+    fn test02_sacar_el_newline_final() {
+        let patch =
+            "--- a/f\n+++ b/f\n@@ -1,3 +1,2 @@\n x\n-y\n-z\n+y\n\\ No newline at end of file\n";
+        let hunks = &parse_patch(patch).unwrap()[0].hunks;
+        assert!(!hunks[0].sin_newline_viejo);
+        assert!(hunks[0].sin_newline_nuevo);
+        assert_eq!(aplicar(patch, "x\ny\nz\n").unwrap(), "x\ny");
+        // un contexto sin newline final marca los dos lados
+        let patch = "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n-w\n+W\n y\n\\ No newline at end of file\n";
+        let hunks = &parse_patch(patch).unwrap()[0].hunks;
+        assert!(hunks[0].sin_newline_viejo && hunks[0].sin_newline_nuevo);
+        assert_eq!(aplicar(patch, "w\ny").unwrap(), "W\ny");
+    }
+}
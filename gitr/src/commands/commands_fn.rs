@@ -1,12 +1,16 @@
-use crate::file_manager::{commit_log, get_branches, get_refs_ids};
 use crate::file_manager::{
     delete_tag, get_current_commit, get_current_repo, update_working_directory,
 };
+use crate::file_manager::{get_branches, get_refs_ids};
 use crate::git_transport::ref_discovery::{self, check_push};
+use crate::i18n::tr;
 use crate::{file_manager, gitr_errors::GitrError};
+use std::fs;
+use std::io::{self, Write};
 use std::path::Path;
 
 use super::command_utils::{self, *};
+use super::flags::{flag, parse_flags, usage_error, valor, valor_opcional, Opcion};
 
 /***************************
  ***************************
@@ -25,7 +29,7 @@ pub fn init(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
     }
     file_manager::init_repository(&(cliente.clone() + "/" + &flags[0]))?;
     file_manager::update_current_repo(&flags[0], cliente)?;
-    println!("Initialized empty Gitr repository");
+    println!("{}", tr("init.done", &[]));
     Ok(())
 }
 
@@ -33,21 +37,9 @@ pub fn init(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
 pub fn hash_object(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
     // hash-object -w <file>
     // hash-object <file>
-    if flags.len() != 1 && flags.len() != 2 {
-        return Err(GitrError::InvalidArgumentError(
-            flags.join(" "),
-            "hash-object [<-w>] <file>".to_string(),
-        ));
-    }
-    let mut file_path = String::new();
-    let mut write = false;
-    if flags.len() == 1 {
-        file_path = flags[0].clone();
-    }
-    if flags.len() == 2 && flags[0] == "-w" {
-        file_path = flags[1].clone();
-        write = true;
-    }
+    let parseados = parse_flags(&flags, "hash-object [-w] <file>", &[flag(&["-w"])], 1..=1)?;
+    let mut file_path = parseados.args[0].clone();
+    let write = parseados.has("-w");
     println!("{}", get_object_hash(cliente, &mut file_path, write)?);
     Ok(())
 }
@@ -56,14 +48,14 @@ pub fn cat_file(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
     //cat-file -p <object-hash>
     //cat-file -t <object-hash>
     //cat-file -s <object-hash>
-    if flags.len() != 2 {
-        let flags_str = flags.join(" ");
-        return Err(GitrError::InvalidArgumentError(
-            flags_str,
-            "cat-file <[-t/-s/-p]> <object hash>".to_string(),
-        ));
-    }
-    let data_to_print = _cat_file(flags, cliente)?;
+    const USAGE: &str = "cat-file (-t | -s | -p) <object hash>";
+    const OPCIONES: [Opcion; 3] = [flag(&["-p"]), flag(&["-t"]), flag(&["-s"])];
+    let parseados = parse_flags(&flags, USAGE, &OPCIONES, 1..=1)?;
+    let tipo = ["-p", "-t", "-s"].into_iter().find(|f| parseados.has(f));
+    let Some(tipo) = tipo.filter(|_| parseados.count() == 1) else {
+        return Err(usage_error(&flags, USAGE, &OPCIONES));
+    };
+    let data_to_print = _cat_file(vec![tipo.to_string(), parseados.args[0].clone()], cliente)?;
     println!("{}", data_to_print);
 
     Ok(())
@@ -73,29 +65,19 @@ pub fn cat_file(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
 pub fn add(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
     // add <file-name>
     // add .
-    if flags.len() != 1 {
-        return Err(GitrError::InvalidArgumentError(
-            flags.join(" "),
-            "add <[file/.]>".to_string(),
-        ));
-    }
+    let parseados = parse_flags(&flags, "add <[file/.]>", &[], 1..=1)?;
     update_index_before_add(cliente.clone())?;
-    add_files_command(flags[0].clone(), cliente)?;
+    add_files_command(parseados.args[0].clone(), cliente)?;
 
     Ok(())
 }
 //Remove files from the index
 pub fn rm(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
     //rm <file>
-    if flags.len() != 1 {
-        return Err(GitrError::InvalidArgumentError(
-            flags.join(" "),
-            "rm <file>".to_string(),
-        ));
-    }
-    let removed = rm_from_index(&flags[0], cliente)?;
+    let parseados = parse_flags(&flags, "rm <file>", &[], 1..=1)?;
+    let removed = rm_from_index(&parseados.args[0], cliente)?;
     if removed {
-        println!("rm '{}'", flags[0]);
+        println!("rm '{}'", parseados.args[0]);
     } else {
         println!("Error: file not found");
     }
@@ -105,58 +87,54 @@ pub fn rm(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
 //Record changes to the repository
 pub fn commit(flags: Vec<String>, second_parent: String, cliente: String) -> Result<(), GitrError> {
     //commit -m <message-of-commit>
-    if flags[0] != "-m" || flags.len() < 2 {
-        return Err(GitrError::InvalidArgumentError(
-            flags.join(" "),
-            "commit -m <commit_message>".to_string(),
-        ));
-    }
+    const USAGE: &str = "commit -m <commit_message>";
+    const OPCIONES: [Opcion; 1] = [valor(&["-m", "--message"])];
+    let parseados = parse_flags(&flags, USAGE, &OPCIONES, 0..=0)?;
+    let message = match parseados.value("-m") {
+        Some(message) if !message.trim().is_empty() => message,
+        _ => return Err(usage_error(&flags, USAGE, &OPCIONES)),
+    };
     let index_path = file_manager::get_current_repo(cliente.clone())?.to_string() + "/gitr/index";
     if !Path::new(&index_path).exists() {
-        return status(flags, cliente.clone());
+        return status(vec![], cliente.clone());
     }
     let (not_staged, _, _) = get_untracked_notstaged_files(cliente.clone())?;
     let (new, mut modified) = get_tobe_commited_files(&not_staged, cliente.clone())?;
     let mut to_be_commited = new;
     to_be_commited.append(&mut modified);
     if to_be_commited.is_empty() {
-        println!("nothing to commit, working tree clean");
+        println!("{}", tr("status.clean", &[]));
         return Ok(());
     }
-    if flags[1].starts_with('\"') {
-        let message = &flags[1..];
-        let message = message.join(" ");
-        if !message.chars().any(|c| c != ' ' && c != '\"') {
-            return Err(GitrError::InvalidArgumentError(
-                flags.join(" "),
-                "commit -m \"commit_message\"".to_string(),
-            ));
-        }
-        get_tree_entries(message.to_string(), second_parent, cliente.clone())?;
-        print_commit_confirmation(message, cliente.clone())?;
-        Ok(())
-    } else {
-        Err(GitrError::InvalidArgumentError(
-            flags.join(" "),
-            "commit -m \"commit_message\"".to_string(),
-        ))
-    }
+    // sin user.email se falla antes de correr los hooks o escribir objetos
+    get_user_mail_from_config(cliente.clone())?;
+    command_utils::run_hook("pre-commit", &[], "", cliente.clone())?;
+    let message = command_utils::run_commit_msg_hook(message, cliente.clone())?;
+    get_tree_entries(message.to_string(), second_parent, cliente.clone())?;
+    print_commit_confirmation(message, cliente.clone())?;
+    command_utils::run_post_hook("post-commit", &[], cliente);
+    Ok(())
 }
 
 // Switch branches or restore working tree files
 pub fn checkout(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
-    if flags.is_empty() || flags.len() > 2 || (flags.len() == 2 && flags[0] != "-b") {
-        return Err(GitrError::InvalidArgumentError(
-            flags.join(" "),
-            "checkout <branch>".to_string(),
-        ));
-    }
+    let parseados = parse_flags(&flags, "checkout [-b] <branch>", &[flag(&["-b"])], 1..=1)?;
     commit_existing(cliente.clone())?;
-    let branch_to_checkout = get_branch_to_checkout(flags.clone(), cliente.clone())?;
+    let previous_commit = file_manager::get_current_commit(cliente.clone())?;
+    let mut args = parseados.args.clone();
+    if parseados.has("-b") {
+        args.insert(0, "-b".to_string());
+    }
+    let branch_to_checkout = get_branch_to_checkout(args, cliente.clone())?;
     let current_commit = file_manager::get_commit(branch_to_checkout.clone(), cliente.clone())?;
-    file_manager::update_working_directory(current_commit, cliente.clone())?;
+    file_manager::update_working_directory(current_commit.clone(), cliente.clone())?;
     let path_head = format!("refs/heads/{}", branch_to_checkout);
     file_manager::update_head(&path_head, cliente.clone())?;
+    command_utils::run_post_hook(
+        "post-checkout",
+        &[previous_commit, current_commit, "1".to_string()],
+        cliente,
+    );
 
     Ok(())
 }
@@ -164,125 +142,373 @@ pub fn checkout(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
 //Show commit logs
 pub fn log(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
     // log
+    // log -n <count>
+    // log --oneline --graph
+    // log --pretty=format:<format>
+    // log --author=<pattern> --grep=<pattern> --since=<date> --until=<date>
+    // log --date-order | --topo-order
+    // log --name-status
+    // log --json
+    // log <commit>... -- <path>...
     commit_existing(cliente.clone())?;
-    if flags.is_empty() {
-        let log_res = commit_log("-1".to_string(), cliente.clone())?;
-        print!("{}", log_res);
+    print!("{}", get_log(flags, cliente)?);
+    Ok(())
+}
+
+//Show commits, tags, trees and blobs
+pub fn show(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // show
+    // show <commit>
+    // show <tag>
+    // show <tree>
+    // show <commit>:<path>
+    // show --no-patch <commit>
+    // show --stat <commit>
+    print!("{}", get_show(flags, cliente)?);
+    Ok(())
+}
+
+//Show what commit last changed each line of a file
+pub fn blame(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // blame <file>
+    // blame <file> <commit>
+    commit_existing(cliente.clone())?;
+    print!("{}", get_blame(flags, cliente)?);
+    Ok(())
+}
+
+//Find the commit that introduced a bug using binary search
+pub fn bisect(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // bisect start [<bad> [<good>...]]
+    // bisect bad [<commit>]
+    // bisect good [<commit>...]
+    // bisect skip [<commit>...]
+    // bisect reset
+    // bisect log
+    // bisect run <cmd>...
+    commit_existing(cliente.clone())?;
+    let usage = "bisect [start [<bad> [<good>...]] | bad [<commit>] | good [<commit>...] | skip [<commit>...] | reset | log | run <cmd>...]";
+    let (subcomando, revs) = match flags.split_first() {
+        Some((subcomando, revs)) => (subcomando.as_str(), revs.to_vec()),
+        None => {
+            return Err(GitrError::InvalidArgumentError(
+                flags.join(" "),
+                usage.to_string(),
+            ))
+        }
+    };
+    let res = match subcomando {
+        "start" => command_utils::bisect_start(revs, cliente)?,
+        "bad" | "good" | "skip" => command_utils::bisect_mark(subcomando, revs, cliente)?,
+        "reset" if revs.is_empty() => command_utils::bisect_reset(cliente)?,
+        "log" if revs.is_empty() => command_utils::bisect_log(cliente)?,
+        "run" if !revs.is_empty() => command_utils::bisect_run(revs, cliente)?,
+        _ => {
+            return Err(GitrError::InvalidArgumentError(
+                flags.join(" "),
+                usage.to_string(),
+            ))
+        }
+    };
+    print!("{}", res);
+    Ok(())
+}
+
+//Print lines matching a pattern in tracked files, the index or a revision
+pub fn grep(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // grep <pattern>
+    // grep -n -i <pattern>
+    // grep -l <pattern> -- <paths>
+    // grep -c <pattern> <rev>
+    // grep --cached <pattern>
+    // grep -F -e <pattern>
+    print!("{}", get_grep(flags, cliente)?);
+    Ok(())
+}
+
+//Give a commit a human readable name based on the nearest reachable tag
+pub fn describe(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // describe
+    // describe <commit>
+    // describe --tags --dirty
+    // describe --long --always <commit>
+    commit_existing(cliente.clone())?;
+    print!("{}", get_describe(flags, cliente)?);
+    Ok(())
+}
+
+//Name commits relative to the branches and tags that reach them
+pub fn name_rev(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // name-rev <commit>...
+    // name-rev --tags <commit>...
+    // name-rev --name-only <commit>...
+    commit_existing(cliente.clone())?;
+    print!("{}", get_name_rev(flags, cliente)?);
+    Ok(())
+}
+
+//Create a tar or zip archive of the files in a tree
+pub fn archive(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // archive <tree-ish>
+    // archive --format=zip <tree-ish> <paths>
+    // archive --format=tar.gz --prefix=<dir>/ <tree-ish>
+    // archive -o <file> <tree-ish>
+    commit_existing(cliente.clone())?;
+    let (bytes, output) = get_archive(flags, cliente)?;
+    match output {
+        Some(output) => {
+            fs::write(&output, bytes).map_err(|_| GitrError::FileWriteError(output))?;
+        }
+        None => io::stdout()
+            .write_all(&bytes)
+            .map_err(|_| GitrError::FileWriteError("stdout".to_string()))?,
     }
-    if flags.len() == 2 && flags[0] == "-n" && flags[1].parse::<usize>().is_ok() {
-        let log_res = commit_log(flags[1].to_string(), cliente.clone())?;
-        print!("{}", log_res);
+    Ok(())
+}
+
+//Move objects and refs by archive, to transfer them without a network connection
+pub fn bundle(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // bundle create <file> <rev-range>...
+    // bundle create <file> --all
+    // bundle verify <file>
+    // bundle list-heads <file>
+    let usage = "bundle [create <file> <rev-range>... | verify <file> | list-heads <file>]";
+    match flags.iter().map(|f| f.as_str()).collect::<Vec<&str>>()[..] {
+        ["create", file, ref revs @ ..] if !revs.is_empty() => {
+            commit_existing(cliente.clone())?;
+            let revs = revs.iter().map(|rev| rev.to_string()).collect();
+            bundle_create(file.to_string(), revs, cliente)?;
+        }
+        ["verify", file] => print!("{}", bundle_verify(file.to_string(), cliente)?),
+        ["list-heads", file] => print!("{}", bundle_list_heads(file.to_string())?),
+        _ => {
+            return Err(GitrError::InvalidArgumentError(
+                flags.join(" "),
+                usage.to_string(),
+            ))
+        }
     }
     Ok(())
 }
 
+//Prepare each commit with its patch in one file per commit, formatted like an email
+pub fn format_patch(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // format-patch <since>
+    // format-patch <commit>..<commit>
+    // format-patch -<n>
+    // format-patch -o <dir> <rev-range>
+    // format-patch --stdout <rev-range>
+    commit_existing(cliente.clone())?;
+    print!("{}", get_format_patch(flags, cliente)?);
+    Ok(())
+}
+
+//Apply a series of patches from a mailbox, keeping the original authorship
+pub fn am(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // am <mbox>...
+    commit_existing(cliente.clone())?;
+    print!("{}", get_am(flags, cliente)?);
+    Ok(())
+}
+
+//Get and set repository or global options
+pub fn config(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // config <key>
+    // config <key> <value>
+    // config --global <key> <value>
+    // config --get-all <key>
+    // config --unset <key>
+    // config --list
+    print!("{}", get_config(flags, cliente)?);
+    Ok(())
+}
+
+//Apply a patch to the working tree and/or the index
+pub fn apply(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // apply <patch>
+    // apply --check <patch>
+    // apply --cached <patch>
+    // apply --index <patch>
+    // apply --3way <patch>
+    // apply -R <patch>
+    print!("{}", get_apply(flags, cliente)?);
+    Ok(())
+}
+
 // List, create, or delete branches
 pub fn branch(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
     //branch -m <origin_branch> <destination-branch>
     //branch
     //branch -d <branch-to-delete>
     //branch -l
+    //branch --format=<format>
     //branch <new-branch-name>
-    if flags.is_empty()
-        || (flags.len() == 1 && flags[0] == "-l")
-        || (flags.len() == 1 && flags[0] == "--list")
-    {
-        println!("{}", print_branches(cliente.clone())?);
+    const USAGE: &str =
+        "branch [-l] [--format=<format>] | branch <new-branch> | branch -d <branch> | branch -m <origin> <destination>";
+    const OPCIONES: [Opcion; 4] = [
+        flag(&["--list", "-l"]),
+        flag(&["-d", "--delete"]),
+        flag(&["-m", "--move"]),
+        valor(&["--format"]),
+    ];
+    let parseados = parse_flags(&flags, USAGE, &OPCIONES, 0..=2)?;
+    let args = &parseados.args;
+    if let Some(formato) = parseados.value("--format") {
+        let solo_lista = parseados.count() - usize::from(parseados.has("--list")) == 1;
+        if !solo_lista || !args.is_empty() {
+            return Err(usage_error(&flags, USAGE, &OPCIONES));
+        }
+        print!("{}", format_branches(&formato, cliente)?);
         return Ok(());
     }
-    commit_existing(cliente.clone())?;
-    if flags.len() == 2 && flags[0] == "-d" {
-        branch_delete_flag(flags[1].clone(), cliente.clone())?;
+    let opcion = ["--list", "-d", "-m"]
+        .into_iter()
+        .find(|f| parseados.has(f));
+    if parseados.count() > 1 {
+        return Err(usage_error(&flags, USAGE, &OPCIONES));
     }
-    if flags.len() == 3 && flags[0] == "-m" {
-        branch_move_flag(flags[1].clone(), flags[2].clone(), cliente.clone())?;
+    match (opcion, args.len()) {
+        (None | Some("--list"), 0) => {
+            println!("{}", print_branches(cliente.clone())?);
+            return Ok(());
+        }
+        (Some("-d"), 1) | (Some("-m"), 2) | (None, 1) => commit_existing(cliente.clone())?,
+        _ => return Err(usage_error(&flags, USAGE, &OPCIONES)),
     }
-    if flags.len() == 1 && flags[0] != "-l" && flags[0] != "--list" {
-        branch_newbranch_flag(flags[0].clone(), cliente.clone())?;
+    match opcion {
+        Some("-d") => branch_delete_flag(args[0].clone(), cliente.clone())?,
+        Some("-m") => branch_move_flag(args[0].clone(), args[1].clone(), cliente.clone())?,
+        _ => branch_newbranch_flag(args[0].clone(), cliente.clone())?,
     }
     Ok(())
 }
 
 pub fn ls_files(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
-    //ls-files --stage
-    if flags.is_empty() || flags[0] == "--cached" || flags[0] == "-c" {
-        let ls_files_res = get_ls_files_cached(cliente.clone())?;
-        print!("{}", ls_files_res);
-        return Ok(());
-    }
-    if flags[0] == "--stage" {
-        let res_output = file_manager::read_index(cliente.clone())?;
-        println!("{}", res_output);
-        return Ok(());
-    }
-    if flags[0] == "--deleted" {
-        let res_output = get_ls_files_deleted_modified(true, cliente.clone())?;
-        print!("{}", res_output);
-        return Ok(());
-    }
-    if flags[0] == "--modified" {
-        let res_output = get_ls_files_deleted_modified(false, cliente.clone())?;
-        print!("{}", res_output);
-        return Ok(());
+    //ls-files [--cached | --stage | --deleted | --modified] [-z]
+    const USAGE: &str = "ls-files [--cached | --stage | --deleted | --modified] [-z]";
+    const OPCIONES: [Opcion; 5] = [
+        flag(&["--cached", "-c"]),
+        flag(&["--stage", "-s"]),
+        flag(&["--deleted", "-d"]),
+        flag(&["--modified", "-m"]),
+        flag(&["-z"]),
+    ];
+    let parseados = parse_flags(&flags, USAGE, &OPCIONES, 0..=0)?;
+    let z = parseados.has("-z");
+    if parseados.count() - usize::from(z) > 1 {
+        return Err(usage_error(&flags, USAGE, &OPCIONES));
+    }
+    let entradas = if parseados.has("--stage") {
+        file_manager::read_index(cliente.clone())?
+            .lines()
+            .map(|linea| linea.to_string())
+            .collect()
+    } else if parseados.has("--deleted") {
+        get_ls_files_deleted_modified(true, cliente.clone())?
+    } else if parseados.has("--modified") {
+        get_ls_files_deleted_modified(false, cliente.clone())?
+    } else {
+        get_ls_files_cached(cliente.clone())?
+    };
+    // con -z cada entrada termina en NUL, para paths con espacios o saltos de linea
+    let fin = if z { '\0' } else { '\n' };
+    for entrada in entradas {
+        print!("{}{}", entrada, fin);
     }
-    Err(GitrError::InvalidArgumentError(
-        flags.join(" "),
-        "ls-files --stage".to_string(),
-    ))
+    Ok(())
 }
 
 pub fn clone(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // clone <remote-url>/<remote-name> <repo>
+    // clone <bundle-file> <repo>
     init(vec![flags[1].clone()], cliente.clone())?;
-    remote(vec![flags[0].clone()], cliente.clone())?;
+    if is_bundle(&flags[0]) {
+        file_manager::set_remote(&flags[0], cliente.clone())?;
+    } else {
+        remote(vec![flags[0].clone()], cliente.clone())?;
+    }
 
-    pullear(vec![], true, cliente)?;
+    pull(vec![], cliente)?;
     Ok(())
 }
 
 // Show the working tree status
-pub fn status(_flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+pub fn status(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // status
+    // status --porcelain[=v1|v2] [-z] [--branch]
+    const USAGE: &str = "status [--porcelain[=v1|v2]] [-z] [-b | --branch]";
+    const OPCIONES: [Opcion; 3] = [
+        valor_opcional(&["--porcelain"]),
+        flag(&["-z"]),
+        flag(&["--branch", "-b"]),
+    ];
+    let parseados = parse_flags(&flags, USAGE, &OPCIONES, 0..=0)?;
+    let version = parseados.value("--porcelain").unwrap_or("v1".to_string());
+    if !["v1", "v2"].contains(&version.as_str()) {
+        return Err(usage_error(&flags, USAGE, &OPCIONES));
+    }
+    if parseados.has("--porcelain") || parseados.has("-z") {
+        print!(
+            "{}",
+            get_status_porcelain(
+                &version,
+                parseados.has("-z"),
+                parseados.has("--branch"),
+                cliente
+            )?
+        );
+        return Ok(());
+    }
     let status = get_status(cliente.clone())?;
     println!("{}", status);
     Ok(())
 }
 
+// Show changes between commits, commit and working tree, etc
+pub fn diff(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
+    // diff [--cached] [-U<n>] [--stat|--numstat|--shortstat|--name-only|--name-status] [-w|-b] [<commit> [<commit>]] [-- <path>...]
+    print!("{}", get_diff(flags, cliente)?);
+    Ok(())
+}
+
 pub fn tag(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
-    if flags.is_empty() || (flags.len() == 1 && flags[0] == "-l") {
-        println!("{}", get_tags_str(cliente.clone())?);
-        return Ok(());
-    }
-    if flags.len() >= 4 && flags[0] == "-a" && flags[2] == "-m" {
-        let mut message = "".to_string();
-        if flags[3].starts_with('\"') {
-            let aux = &flags[3..];
-            message = aux.join(" ");
+    //tag [-l]
+    //tag <tag-name>
+    //tag -a <tag-name> -m <tag-message>
+    //tag -d <tag-name>
+    const USAGE: &str =
+        "tag [-l] | tag <tag-name> | tag -a <tag-name> -m <tag-message> | tag -d <tag-name>";
+    const OPCIONES: [Opcion; 4] = [
+        flag(&["--list", "-l"]),
+        flag(&["-a", "--annotate"]),
+        valor(&["-m", "--message"]),
+        flag(&["-d", "--delete"]),
+    ];
+    let parseados = parse_flags(&flags, USAGE, &OPCIONES, 0..=1)?;
+    let args = &parseados.args;
+    let message = parseados.value("-m");
+    match (parseados.count(), args.len()) {
+        (0, 0) => println!("{}", get_tags_str(cliente.clone())?),
+        (1, 0) if parseados.has("--list") => println!("{}", get_tags_str(cliente.clone())?),
+        (0, 1) => create_lightweight_tag(args[0].clone(), cliente.clone())?,
+        (1, 1) if parseados.has("-d") => println!("{}", delete_tag(args[0].clone(), cliente)?),
+        (2, 1) if parseados.has("-a") && message.is_some() => {
+            let message = message.unwrap_or_default();
             if !message.chars().any(|c| c != ' ' && c != '\"') {
-                return Err(GitrError::InvalidArgumentError(
-                    flags.join(" "),
-                    "tag -a <tag-name> -m \"tag-message\"".to_string(),
-                ));
+                return Err(usage_error(&flags, USAGE, &OPCIONES));
             }
+            create_annotated_tag(args[0].clone(), message, cliente.clone())?;
         }
-        create_annotated_tag(flags[1].clone(), message.clone(), cliente.clone())?;
-        return Ok(());
+        _ => return Err(usage_error(&flags, USAGE, &OPCIONES)),
     }
-    if flags.len() == 1 && flags[0] != "-l" {
-        create_lightweight_tag(flags[0].clone(), cliente.clone())?;
-        return Ok(());
-    }
-    if flags.len() == 2 && flags[0] == "-d" {
-        let res = delete_tag(flags[1].clone(), cliente)?;
-        println!("{}", res);
-        return Ok(());
-    }
-    Err(GitrError::InvalidArgumentError(
-        flags.join(" "),
-        "tag [-l] [-a <tag-name> -m <tag-message>] <tag-name>".to_string(),
-    ))
+    Ok(())
 }
 
 pub fn fetch(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
-    pullear(flags, false, cliente)
+    // fetch
+    // fetch <remote>
+    // fetch <bundle-file>
+    pullear(flags, false, "fetch [<remote> | <bundle-file>]", cliente)
 }
 
 pub fn merge(_flags: Vec<String>, cliente: String) -> Result<(bool, String, Vec<String>), GitrError> {
@@ -295,10 +521,8 @@ pub fn merge(_flags: Vec<String>, cliente: String) -> Result<(bool, String, Vec<
     
     let branch_name = _flags[0].clone();
 
-    let origin_name = file_manager::get_head(cliente.clone())?
-        .split('/')
-        .collect::<Vec<&str>>()[2]
-        .to_string();
+    let origin_name =
+        command_utils::get_head_branch(cliente.clone())?.ok_or(GitrError::BranchNotFound)?;
 
     match merge_(origin_name, branch_name, cliente.clone()) {
         Ok((hubo_conflict_res, branch_hash_res, archivos_conflict_res)) => Ok((hubo_conflict_res, branch_hash_res, archivos_conflict_res)),
@@ -317,11 +541,13 @@ pub fn merge_(origin_name: String, branch_name: String, cliente: String) -> Resu
         if origin_commits.contains(&commit) {
             if commit == origin_commits[0] {
                 println!(
-                    "Updating {}..{}",
-                    &origin_commits[0][..7],
-                    &branch_commits[0][..7]
+                    "{}",
+                    tr(
+                        "merge.updating",
+                        &[&&origin_commits[0][..7], &&branch_commits[0][..7]]
+                    )
                 );
-                println!("Fast-forward");
+                println!("{}", tr("merge.fast_forward", &[]));
                 fast_forward_merge(branch_name.clone(), cliente.clone())?;
                 break;
             }
@@ -332,28 +558,31 @@ pub fn merge_(origin_name: String, branch_name: String, cliente: String) -> Resu
                 cliente.clone(),
             )?;
             if !hubo_conflict {
-                println!("no hubo conflictos");
+                println!("{}", tr("merge.no_conflicts", &[]));
                 if cliente.contains('/'){
                     return Ok((hubo_conflict, branch_commits[0].clone(), archivos_conflict));
                 }
                 add(vec![".".to_string()], cliente.clone())?;
-                println!("no fallo en el add.");
+                println!("{}", tr("merge.added", &[]));
 
                 command_utils::create_merge_commit(
                     branch_name.clone(),
                     branch_commits[0].clone(),
-                    cliente,
+                    cliente.clone(),
                 )?;
             }
             break;
         }
     }
+    if !hubo_conflict {
+        command_utils::run_post_hook("post-merge", &["0".to_string()], cliente);
+    }
     Ok((hubo_conflict, branch_commits[0].clone(), archivos_conflict))
 }
 
 pub fn remote(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
     if flags.is_empty() {
-        let remote = file_manager::read_file(get_current_repo(cliente.clone())? + "/gitr/remote")?;
+        let remote = file_manager::get_remote(cliente.clone())?;
         println!("remote: {}", remote);
     } else {
         let parts: Vec<&str> = flags[0].split('/').collect();
@@ -363,39 +592,49 @@ pub fn remote(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
                 "remote <remote-url>/<remote-name>".to_string(),
             ));
         }
-        file_manager::write_file(
-            get_current_repo(cliente.clone())? + "/gitr/remote",
-            flags[0].clone(),
-        )?;
+        file_manager::set_remote(&flags[0], cliente.clone())?;
     }
     Ok(())
 }
 
+// trae los objetos del remote configurado, o del que se pasa (url/name o un bundle)
 fn pullear(
     flags: Vec<String>,
     actualizar_work_dir: bool,
+    usage: &str,
     cliente: String,
 ) -> Result<(), GitrError> {
-    if !flags.is_empty() {
-        return Err(GitrError::InvalidArgumentError(
-            flags.join(" "),
-            "pull <no-args>".to_string(),
-        ));
-    }
-
-    // ########## HANDSHAKE ##########
-    let mut stream = handshake("git-upload-pack".to_string(), cliente.clone())?;
-
-    //  ########## REFERENCE DISCOVERY ##########
-    let hash_n_references = protocol_reference_discovery(&mut stream)?;
+    let remote = match flags.as_slice() {
+        [] => file_manager::get_remote(cliente.clone())?,
+        [remote] if remote == "origin" => file_manager::get_remote(cliente.clone())?,
+        [remote] if !remote.starts_with('-') => remote.clone(),
+        _ => {
+            return Err(GitrError::InvalidArgumentError(
+                flags.join(" "),
+                usage.to_string(),
+            ))
+        }
+    };
 
-    // ########## WANTS N HAVES ##########
-    let pkt_needed =
-        protocol_wants_n_haves(hash_n_references.clone(), &mut stream, cliente.clone())?;
-    // ########## PACKFILE ##########
-    if pkt_needed {
-        pull_packfile(&mut stream, cliente.clone())?;
-    }
+    // ########## BUNDLE ##########
+    let hash_n_references = if is_bundle(&remote) {
+        fetch_bundle(remote, cliente.clone())?
+    } else {
+        // ########## HANDSHAKE ##########
+        let mut stream = handshake_remote("git-upload-pack".to_string(), remote)?;
+
+        //  ########## REFERENCE DISCOVERY ##########
+        let hash_n_references = protocol_reference_discovery(&mut stream)?;
+
+        // ########## WANTS N HAVES ##########
+        let pkt_needed =
+            protocol_wants_n_haves(hash_n_references.clone(), &mut stream, cliente.clone())?;
+        // ########## PACKFILE ##########
+        if pkt_needed {
+            pull_packfile(&mut stream, cliente.clone())?;
+        }
+        hash_n_references
+    };
     if actualizar_work_dir {
         file_manager::update_client_refs(
             hash_n_references.clone(),
@@ -409,7 +648,10 @@ fn pullear(
 }
 
 pub fn pull(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
-    pullear(flags, true, cliente)
+    // pull
+    // pull <remote>
+    // pull <bundle-file>
+    pullear(flags, true, "pull [<remote> | <bundle-file>]", cliente)
 }
 
 pub fn push(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
@@ -437,7 +679,7 @@ pub fn push(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
             return Err(e);
         }
     }
-    let (pkt_needed, pkt_ids) =
+    let (pkt_needed, pkt_ids, refs) =
         reference_update_request(&mut stream, hash_n_references.clone(), cliente.clone())?;
 
     // ########## PACKFILE ##########
@@ -445,16 +687,19 @@ pub fn push(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
         println!("pushing packfile");
         push_packfile(&mut stream, pkt_ids, hash_n_references, cliente)?;
     }
-    Ok(())
+    command_utils::read_push_report(&mut stream, &refs)
 }
 
 pub fn show_ref(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
-    if flags.len() > 1 || (flags.len() == 1 && flags[0] != "--head") {
-        return Err(GitrError::InvalidArgumentError(
-            flags.join(" "),
-            "show-ref [--head]".to_string(),
-        ));
-    }
+    //show-ref [--head] [--heads] [--tags] [-s | --hash]
+    const OPCIONES: [Opcion; 4] = [
+        flag(&["--head"]),
+        flag(&["--heads"]),
+        flag(&["--tags"]),
+        flag(&["--hash", "-s"]),
+    ];
+    let usage = "show-ref [--head] [--heads] [--tags] [-s | --hash]";
+    let parseados = parse_flags(&flags, usage, &OPCIONES, 0..=0)?;
     let refs = match ref_discovery::ref_discovery(&(get_current_repo(cliente.clone())? + "/gitr")) {
         Ok(refs) => refs.0,
         Err(e) => {
@@ -463,17 +708,23 @@ pub fn show_ref(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
         }
     };
     let refs = refs.strip_suffix("0000").unwrap_or(&refs);
-    let mut head = true;
+    let filtrar = parseados.has("--heads") || parseados.has("--tags");
     for refer in refs.lines() {
         let r = refer.split_at(4).1;
-        if head {
-            if flags.contains(&"--head".to_string()){
-                println!("{}", r);
+        let (hash, nombre) = r.split_once(' ').unwrap_or((r, ""));
+        let mostrar = match nombre {
+            "HEAD" => parseados.has("--head"),
+            _ if filtrar => {
+                (parseados.has("--heads") && nombre.starts_with("refs/heads/"))
+                    || (parseados.has("--tags") && nombre.starts_with("refs/tags/"))
             }
-            head = false;
-            continue;
+            _ => true,
+        };
+        match (mostrar, parseados.has("--hash")) {
+            (true, true) => println!("{}", hash),
+            (true, false) => println!("{}", r),
+            (false, _) => (),
         }
-        println!("{}", r);
     }
     Ok(())
 }
@@ -551,21 +802,37 @@ pub fn echo(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
 }
 
 pub fn rebase(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
-    let origin_name = flags[0].clone();
-    let branch_name = file_manager::get_head(cliente.clone())?
-        .split('/')
-        .collect::<Vec<&str>>()[2]
-        .to_string();
+    // rebase <branch>
+    // rebase --continue
+    // rebase --skip
+    // rebase --abort
+    const USAGE: &str = "rebase <branch> | rebase [--continue | --skip | --abort]";
+    const OPCIONES: [Opcion; 3] = [flag(&["--continue"]), flag(&["--skip"]), flag(&["--abort"])];
+    let parseados = parse_flags(&flags, USAGE, &OPCIONES, 0..=1)?;
+    match (parseados.count(), parseados.args.len()) {
+        (1, 0) if parseados.has("--continue") => return command_utils::rebase_continue(cliente),
+        (1, 0) if parseados.has("--skip") => return command_utils::rebase_skip(cliente),
+        (1, 0) if parseados.has("--abort") => return command_utils::rebase_abort(cliente),
+        (0, 1) => (),
+        _ => return Err(usage_error(&flags, USAGE, &OPCIONES)),
+    }
+    if command_utils::rebase_in_progress(cliente.clone())? {
+        return Err(GitrError::RebaseInProgress);
+    }
+    let origin_name = parseados.args[0].clone();
+    command_utils::run_hook("pre-rebase", &parseados.args, "", cliente.clone())?;
+    let branch_name =
+        command_utils::get_head_branch(cliente.clone())?.ok_or(GitrError::BranchNotFound)?;
     let branch_commits = command_utils::branch_commits_list(branch_name.clone(), cliente.clone())?;
     let origin_commits = command_utils::branch_commits_list(origin_name.clone(), cliente.clone())?;
     let mut to_rebase_commits: Vec<String> = vec![];
     for commit in branch_commits.clone() {
         if origin_commits.contains(&commit) {
             if commit == origin_commits[0] {
-                println!("nothing to rebase");
+                println!("{}", tr("rebase.nothing", &[]));
                 return Ok(());
             }
-            create_rebase_commits(to_rebase_commits, origin_name, cliente.clone(), commit)?;
+            create_rebase_commits(to_rebase_commits, origin_name, cliente.clone())?;
             break;
         }
         to_rebase_commits.push(commit);
@@ -573,20 +840,10 @@ pub fn rebase(flags: Vec<String>, cliente: String) -> Result<(), GitrError> {
     Ok(())
 }
 
-pub fn check_ignore(paths: Vec<String>, client: String) -> Result<(), GitrError> {
-    if paths.is_empty() {
-        return Err(GitrError::InvalidArgumentError(
-            paths.join(" "),
-            "check-ignore <path>".to_string(),
-        ));
-    }
-    let repo = file_manager::get_current_repo(client.clone())?;
-    let mut full_paths = vec![];
-    for linea in paths {
-        full_paths.push(repo.to_owned() + linea.as_str());
-    }
-    match command_utils::check_ignore_(full_paths, client) {
-        Ok(_) => Ok(()),
-        Err(e) => Err(e),
-    }
+//Show which paths are ignored and, with -v, the gitrignore rule that matched
+pub fn check_ignore(flags: Vec<String>, client: String) -> Result<(), GitrError> {
+    // check-ignore <path>...
+    // check-ignore -v <path>...
+    print!("{}", command_utils::get_check_ignore(flags, client)?);
+    Ok(())
 }
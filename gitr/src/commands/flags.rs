@@ -0,0 +1,209 @@
+use std::ops::RangeInclusive;
+
+use crate::gitr_errors::GitrError;
+use crate::i18n::tr;
+
+/// Una opcion que acepta un comando. El primer nombre es el que se usa para consultarla,
+/// los demas son alias (p.ej. ["--list", "-l"]).
+pub struct Opcion {
+    pub nombres: &'static [&'static str],
+    pub con_valor: bool,
+    pub valor_opcional: bool,
+}
+
+/// Opcion sin valor
+pub const fn flag(nombres: &'static [&'static str]) -> Opcion {
+    Opcion {
+        nombres,
+        con_valor: false,
+        valor_opcional: false,
+    }
+}
+
+/// Opcion que recibe un valor, como "-m <valor>" o "--format=<valor>"
+pub const fn valor(nombres: &'static [&'static str]) -> Opcion {
+    Opcion {
+        nombres,
+        con_valor: true,
+        valor_opcional: false,
+    }
+}
+
+/// Opcion que puede recibir un valor solo pegado con "=", como "--porcelain" o "--porcelain=v2"
+pub const fn valor_opcional(nombres: &'static [&'static str]) -> Opcion {
+    Opcion {
+        nombres,
+        con_valor: false,
+        valor_opcional: true,
+    }
+}
+
+/// Resultado de parsear los flags de un comando
+#[derive(Debug, Default)]
+pub struct Flags {
+    opciones: Vec<(&'static str, Option<String>)>,
+    pub args: Vec<String>,
+}
+
+impl Flags {
+    pub fn has(&self, nombre: &str) -> bool {
+        self.opciones.iter().any(|(n, _)| *n == nombre)
+    }
+
+    /// el ultimo valor que se paso para la opcion
+    pub fn value(&self, nombre: &str) -> Option<String> {
+        self.values(nombre).pop()
+    }
+
+    pub fn values(&self, nombre: &str) -> Vec<String> {
+        self.opciones
+            .iter()
+            .filter(|(n, _)| *n == nombre)
+            .filter_map(|(_, v)| v.clone())
+            .collect()
+    }
+
+    /// cantidad de opciones distintas que se pasaron
+    pub fn count(&self) -> usize {
+        let mut nombres: Vec<&str> = self.opciones.iter().map(|(n, _)| *n).collect();
+        nombres.sort();
+        nombres.dedup();
+        nombres.len()
+    }
+}
+
+/// Error de uso con la lista de opciones validas del comando
+pub fn usage_error(flags: &[String], usage: &str, opciones: &[Opcion]) -> GitrError {
+    let validas: Vec<String> = opciones
+        .iter()
+        .map(|opcion| {
+            let nombres = opcion.nombres.join(" | ");
+            if opcion.con_valor {
+                format!("{} {}", nombres, tr("usage.value", &[]))
+            } else if opcion.valor_opcional {
+                format!("{}[={}]", nombres, tr("usage.value", &[]))
+            } else {
+                nombres
+            }
+        })
+        .collect();
+    let mut uso = usage.to_string();
+    if !validas.is_empty() {
+        uso += &format!(
+            "\n    {}",
+            tr("usage.valid_options", &[&validas.join(", ")])
+        );
+    }
+    GitrError::InvalidArgumentError(flags.join(" "), uso)
+}
+
+/// Separa las opciones de los argumentos. Acepta "-x <valor>", "--opcion <valor>" y
+/// "--opcion=<valor>"; despues de "--" todo es argumento. Falla si la cantidad de
+/// argumentos no esta en cant_args.
+pub fn parse_flags(
+    flags: &[String],
+    usage: &str,
+    opciones: &[Opcion],
+    cant_args: RangeInclusive<usize>,
+) -> Result<Flags, GitrError> {
+    let error = || usage_error(flags, usage, opciones);
+    let mut parseados = Flags::default();
+    let mut iter = flags.iter();
+    while let Some(flag) = iter.next() {
+        if flag == "--" {
+            parseados.args.extend(iter.by_ref().cloned());
+            break;
+        }
+        if !flag.starts_with('-') || flag == "-" {
+            parseados.args.push(flag.clone());
+            continue;
+        }
+        let (nombre, valor_pegado) = match flag.split_once('=') {
+            Some((nombre, valor)) if flag.starts_with("--") => (nombre, Some(valor.to_string())),
+            _ => (flag.as_str(), None),
+        };
+        let opcion = opciones
+            .iter()
+            .find(|opcion| opcion.nombres.contains(&nombre))
+            .ok_or_else(error)?;
+        let valor = match (opcion.con_valor, valor_pegado) {
+            (true, Some(valor)) => Some(valor),
+            (true, None) => Some(iter.next().ok_or_else(error)?.clone()),
+            (false, None) => None,
+            (false, Some(valor)) if opcion.valor_opcional => Some(valor),
+            (false, Some(_)) => return Err(error()),
+        };
+        parseados.opciones.push((opcion.nombres[0], valor));
+    }
+    if !cant_args.contains(&parseados.args.len()) {
+        return Err(error());
+    }
+    Ok(parseados)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPCIONES: [Opcion; 4] = [
+        flag(&["--list", "-l"]),
+        valor(&["-m", "--message"]),
+        flag(&["-d"]),
+        valor_opcional(&["--porcelain"]),
+    ];
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test01_opciones_valores_y_argumentos() {
+        let flags = parse_flags(
+            &args(&[
+                "-l",
+                "uno",
+                "--message=hola mundo",
+                "-m",
+                "chau",
+                "--",
+                "-d",
+            ]),
+            "cmd",
+            &OPCIONES,
+            0..=2,
+        )
+        .unwrap();
+        assert!(flags.has("--list"));
+        assert!(!flags.has("-d"));
+        assert_eq!(flags.value("-m"), Some("chau".to_string()));
+        assert_eq!(flags.values("-m"), vec!["hola mundo", "chau"]);
+        assert_eq!(flags.args, vec!["uno", "-d"]);
+        assert_eq!(flags.count(), 2);
+    }
+
+    #[test]
+    fn test02_errores_de_uso_listan_las_opciones() {
+        let error = parse_flags(&args(&["-x"]), "cmd [-l]", &OPCIONES, 0..=1).unwrap_err();
+        assert_eq!(
+            error,
+            GitrError::InvalidArgumentError(
+                "-x".to_string(),
+                "cmd [-l]\n    Valid options: --list | -l, -m | --message <value>, -d, --porcelain[=<value>]"
+                    .to_string()
+            )
+        );
+        assert!(parse_flags(&args(&["-m"]), "cmd", &OPCIONES, 0..=1).is_err());
+        assert!(parse_flags(&args(&["--list=si"]), "cmd", &OPCIONES, 0..=1).is_err());
+        assert!(parse_flags(&args(&["a", "b"]), "cmd", &OPCIONES, 0..=1).is_err());
+    }
+
+    #[test]
+    fn test03_valor_opcional_solo_con_igual() {
+        let flags = parse_flags(&args(&["--porcelain", "v2"]), "cmd", &OPCIONES, 0..=1).unwrap();
+        assert!(flags.has("--porcelain"));
+        assert_eq!(flags.value("--porcelain"), None);
+        assert_eq!(flags.args, vec!["v2"]);
+        let flags = parse_flags(&args(&["--porcelain=v2"]), "cmd", &OPCIONES, 0..=1).unwrap();
+        assert_eq!(flags.value("--porcelain"), Some("v2".to_string()));
+    }
+}
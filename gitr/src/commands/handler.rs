@@ -1,8 +1,58 @@
+use std::{path::Path, process::Command};
+
 use super::commands_fn;
-use crate::{gitr_errors::GitrError, logger};
+use crate::{config::Config, file_manager, gitr_errors::GitrError, i18n::tr, logger};
+
+// atajos que se pueden redefinir con [alias] en la config
+const DEFAULT_ALIASES: [(&str, &str); 5] = [
+    ("h", "hash-object"),
+    ("c", "cat-file"),
+    ("lr", "list-repos"),
+    ("gtr", "go-to-repo"),
+    ("cr", "cur-repo"),
+];
 
-pub fn parse_input(input: String) -> Vec<String> {
-    return input.split_whitespace().map(|s| s.to_string()).collect();
+/// Separa la entrada en argumentos como un shell: respeta las comillas simples y dobles
+/// y los escapes con \. Un "--" queda como argumento para que lo interprete cada comando.
+pub fn parse_input(input: String) -> Result<Vec<String>, GitrError> {
+    let mut argv = Vec::new();
+    let mut actual: Option<String> = None;
+    let mut comilla: Option<char> = None;
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match (comilla, c) {
+            (Some(abierta), c) if c == abierta => comilla = None,
+            (Some('"'), '\\') => {
+                // dentro de comillas dobles el \ solo escapa ", \, $ y `
+                let siguiente = chars.next().unwrap_or('\\');
+                let token = actual.get_or_insert_with(String::new);
+                if !matches!(siguiente, '"' | '\\' | '$' | '`') {
+                    token.push('\\');
+                }
+                token.push(siguiente);
+            }
+            (Some(_), c) => actual.get_or_insert_with(String::new).push(c),
+            (None, '\'' | '"') => {
+                comilla = Some(c);
+                actual.get_or_insert_with(String::new);
+            }
+            (None, '\\') => {
+                if let Some(siguiente) = chars.next().filter(|c| *c != '\n') {
+                    actual.get_or_insert_with(String::new).push(siguiente);
+                }
+            }
+            (None, c) if c.is_whitespace() => argv.extend(actual.take()),
+            (None, c) => actual.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if let Some(comilla) = comilla {
+        return Err(GitrError::InvalidArgumentError(
+            input.trim().to_string(),
+            tr("input.unclosed_quote", &[&comilla]),
+        ));
+    }
+    argv.extend(actual);
+    Ok(argv)
 }
 
 /// ["command", "flag1", "flag2", ...]
@@ -11,6 +61,17 @@ pub fn command_handler(
     hubo_conflict: bool,
     branch_hash: String,
     client: String,
+) -> Result<(bool, String), GitrError> {
+    dispatch(argv, hubo_conflict, branch_hash, client, &mut vec![])
+}
+
+// alias_vistos son los alias que ya se expandieron, para cortar la recursion
+fn dispatch(
+    argv: Vec<String>,
+    hubo_conflict: bool,
+    branch_hash: String,
+    client: String,
+    alias_vistos: &mut Vec<String>,
 ) -> Result<(bool, String), GitrError> {
     if argv.is_empty() {
         return Ok((false, "".to_string()));
@@ -27,10 +88,11 @@ pub fn command_handler(
     };
 
     match command.as_str() {
-        "hash-object" | "h" => commands_fn::hash_object(flags, client)?, //"h" para testear mas rapido mientras la implementamos
-        "cat-file" | "c" => commands_fn::cat_file(flags, client)?,
+        "hash-object" => commands_fn::hash_object(flags, client)?,
+        "cat-file" => commands_fn::cat_file(flags, client)?,
         "init" => commands_fn::init(flags, client)?,
         "status" => commands_fn::status(flags, client)?,
+        "diff" => commands_fn::diff(flags, client)?,
         "add" => {
             commands_fn::add(flags, client)?;
             return Ok((hubo_conflict, branch_hash));
@@ -46,14 +108,24 @@ pub fn command_handler(
         }
         "checkout" => commands_fn::checkout(flags, client)?,
         "log" => commands_fn::log(flags, client)?,
+        "show" => commands_fn::show(flags, client)?,
+        "blame" => commands_fn::blame(flags, client)?,
+        "bisect" => commands_fn::bisect(flags, client)?,
+        "grep" => commands_fn::grep(flags, client)?,
+        "describe" => commands_fn::describe(flags, client)?,
+        "name-rev" => commands_fn::name_rev(flags, client)?,
+        "archive" => commands_fn::archive(flags, client)?,
+        "bundle" => commands_fn::bundle(flags, client)?,
+        "format-patch" => commands_fn::format_patch(flags, client)?,
+        "am" => commands_fn::am(flags, client)?,
+        "apply" => commands_fn::apply(flags, client)?,
+        "config" => commands_fn::config(flags, client)?,
         "clone" => commands_fn::clone(flags, client)?,
         "fetch" => commands_fn::fetch(flags, client)?,
         "merge" => {
             let (hubo_conflict_res, branch_hash_res, _) = commands_fn::merge(flags, client)?;
             if hubo_conflict_res {
-                println!(
-                    "\x1b[33mHubo un conflicto, por favor resuelvalo antes de continuar\x1b[0m"
-                );
+                println!("\x1b[33m{}\x1b[0m", tr("merge.conflict_hint", &[]));
             }
             return Ok((true, branch_hash_res));
         }
@@ -69,18 +141,105 @@ pub fn command_handler(
         "check-ignore" => commands_fn::check_ignore(flags, client)?,
         "q" => return Ok((false, "".to_string())),
         "l" => logger::log(flags)?,
-        "list-repos" | "lr" => commands_fn::list_repos(client),
-        "go-to-repo" | "gtr" => commands_fn::go_to_repo(flags, client)?,
-        "cur-repo" | "cr" => commands_fn::print_current_repo(client)?,
+        "list-repos" => commands_fn::list_repos(client),
+        "go-to-repo" => commands_fn::go_to_repo(flags, client)?,
+        "cur-repo" => commands_fn::print_current_repo(client)?,
         "echo" => commands_fn::echo(flags, client)?,
-        _ => {
-            let message = format!("invalid command: {}", command);
-            return Err(GitrError::InvalidArgumentError(
-                message,
-                "usage: gitr <command> [<args>]".to_string(),
-            ));
-        }
+        _ => return run_alias(argv, hubo_conflict, branch_hash, client, alias_vistos),
     }
 
     Ok((false, "".to_string()))
 }
+
+// busca el alias en la config y si no esta, en los atajos por defecto
+fn get_alias(nombre: &str, client: &str) -> Option<String> {
+    let config = Config::load(client).unwrap_or_default();
+    config.get(&format!("alias.{}", nombre)).or_else(|| {
+        DEFAULT_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == nombre)
+            .map(|(_, comando)| comando.to_string())
+    })
+}
+
+// expande el alias y despacha el comando resultante. Los alias que empiezan con '!'
+// se corren en un shell con los argumentos al final
+fn run_alias(
+    argv: Vec<String>,
+    hubo_conflict: bool,
+    branch_hash: String,
+    client: String,
+    alias_vistos: &mut Vec<String>,
+) -> Result<(bool, String), GitrError> {
+    let command = argv[0].clone();
+    let Some(valor) = get_alias(&command, &client) else {
+        let message = format!("invalid command: {}", command);
+        return Err(GitrError::InvalidArgumentError(
+            message,
+            "usage: gitr <command> [<args>]".to_string(),
+        ));
+    };
+    if alias_vistos.contains(&command) {
+        alias_vistos.push(command);
+        return Err(GitrError::AliasError(tr(
+            "alias.recursion",
+            &[&alias_vistos.join(" -> ")],
+        )));
+    }
+    alias_vistos.push(command.clone());
+    if let Some(shell) = valor.strip_prefix('!') {
+        let repo = file_manager::get_current_repo(client.clone()).unwrap_or(".".to_string());
+        let dir = if Path::new(&repo).is_dir() {
+            repo
+        } else {
+            ".".to_string()
+        };
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$@\"", shell))
+            .arg(&command)
+            .args(&argv[1..])
+            .current_dir(dir)
+            .status()
+            .map_err(|e| GitrError::AliasError(format!("{}: {}", command, e)))?;
+        if !status.success() {
+            return Err(GitrError::AliasError(tr(
+                "error.command_exited_with",
+                &[&command, &status],
+            )));
+        }
+        return Ok((false, "".to_string()));
+    }
+    let mut expandido = parse_input(valor)?;
+    expandido.extend_from_slice(&argv[1..]);
+    dispatch(expandido, hubo_conflict, branch_hash, client, alias_vistos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Vec<String> {
+        parse_input(input.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test01_parse_input_respeta_comillas_y_escapes() {
+        assert_eq!(parse("  add   a.txt \n"), vec!["add", "a.txt"]);
+        assert_eq!(
+            parse("commit -m \"fix parser bug\""),
+            vec!["commit", "-m", "fix parser bug"]
+        );
+        assert_eq!(
+            parse("add 'con espacios.txt' otro\\ archivo"),
+            vec!["add", "con espacios.txt", "otro archivo"]
+        );
+        assert_eq!(
+            parse(r#"commit -m "dijo \"hola\" a\b" -- ''"#),
+            vec!["commit", "-m", "dijo \"hola\" a\\b", "--", ""]
+        );
+        assert_eq!(parse("a'b'\"c\"d"), vec!["abcd"]);
+        assert!(parse_input("commit -m \"sin cerrar".to_string()).is_err());
+        assert!(parse_input("add 'sin cerrar".to_string()).is_err());
+    }
+}
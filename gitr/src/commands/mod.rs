@@ -1,3 +1,4 @@
 pub mod command_utils;
 pub mod commands_fn;
+pub mod flags;
 pub mod handler;
@@ -0,0 +1,331 @@
+use std::fmt;
+
+use crate::{file_manager, gitr_errors::GitrError, i18n::tr};
+
+/// Nivel de un archivo de configuracion. Al leer, cada nivel pisa al anterior.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigLevel {
+    System,
+    Global,
+    Local,
+}
+
+impl ConfigLevel {
+    /// Path del archivo del nivel: el del directorio donde corre gitr, el del cliente
+    /// o el del repo actual.
+    pub fn path(&self, cliente: &str) -> Result<String, GitrError> {
+        match self {
+            ConfigLevel::System => Ok("gitrconfig".to_string()),
+            ConfigLevel::Global => Ok(format!("{}/gitrconfig", cliente)),
+            ConfigLevel::Local => {
+                Ok(file_manager::get_current_repo(cliente.to_string())? + "/gitr/config")
+            }
+        }
+    }
+}
+
+/// Configuracion en formato INI, con secciones "[user]" o con subseccion
+/// "[remote \"origin\"]". Las claves se escriben "seccion.clave" o
+/// "seccion.subseccion.clave"; la seccion y la clave no distinguen mayusculas.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    // (clave normalizada, valor) en el orden en que aparecen
+    entradas: Vec<(String, String)>,
+}
+
+fn error(motivo: String) -> GitrError {
+    GitrError::ConfigError(motivo)
+}
+
+/// Normaliza una clave: la seccion y el nombre van en minuscula, la subseccion se
+/// deja como esta.
+pub fn normalize_key(clave: &str) -> Result<String, GitrError> {
+    let invalida = || error(tr("config.invalid_key", &[&clave]));
+    let (seccion, resto) = clave.split_once('.').ok_or_else(invalida)?;
+    let (subseccion, nombre) = match resto.rsplit_once('.') {
+        Some((subseccion, nombre)) => (Some(subseccion), nombre),
+        None => (None, resto),
+    };
+    let valido = |s: &str| {
+        s.starts_with(|c: char| c.is_ascii_alphabetic())
+            && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+    if !valido(seccion) || !valido(nombre) {
+        return Err(invalida());
+    }
+    Ok(match subseccion {
+        Some(subseccion) => format!(
+            "{}.{}.{}",
+            seccion.to_lowercase(),
+            subseccion,
+            nombre.to_lowercase()
+        ),
+        None => format!("{}.{}", seccion.to_lowercase(), nombre.to_lowercase()),
+    })
+}
+
+// valor de una linea "clave = valor": saca comentarios, comillas y escapes
+fn parse_value(valor: &str) -> Option<String> {
+    let mut res = String::new();
+    // hasta donde el valor no se puede recortar por estar entre comillas o escapado
+    let mut protegido = 0;
+    let mut entre_comillas = false;
+    let mut chars = valor.trim_start().chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => entre_comillas = !entre_comillas,
+            '\\' => {
+                res.push(match chars.next()? {
+                    'n' => '\n',
+                    't' => '\t',
+                    c => c,
+                });
+            }
+            '#' | ';' if !entre_comillas => break,
+            c => res.push(c),
+        }
+        if entre_comillas || c == '\\' {
+            protegido = res.len();
+        }
+    }
+    if entre_comillas {
+        return None;
+    }
+    let largo = res.trim_end().len().max(protegido);
+    res.truncate(largo);
+    Some(res)
+}
+
+// el valor entre comillas si al leerlo cambiaria
+fn format_value(valor: &str) -> String {
+    let escapado = valor
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t");
+    match escapado != valor || valor.trim() != valor || valor.contains('#') || valor.contains(';') {
+        true => format!("\"{}\"", escapado),
+        false => valor.to_string(),
+    }
+}
+
+impl Config {
+    pub fn parse(data: &str) -> Result<Config, GitrError> {
+        let mut entradas = Vec::new();
+        let mut seccion: Option<String> = None;
+        for (i, linea) in data.lines().enumerate() {
+            let invalida = || error(tr("config.invalid_line", &[&(i + 1), &linea]));
+            let linea = linea.trim();
+            if linea.is_empty() || linea.starts_with('#') || linea.starts_with(';') {
+                continue;
+            }
+            if let Some(header) = linea.strip_prefix('[') {
+                let (header, _) = header.split_once(']').ok_or_else(invalida)?;
+                seccion = Some(match header.split_once(' ') {
+                    Some((nombre, subseccion)) => {
+                        let subseccion = subseccion
+                            .trim()
+                            .strip_prefix('"')
+                            .and_then(|s| s.strip_suffix('"'))
+                            .ok_or_else(invalida)?
+                            .replace("\\\"", "\"")
+                            .replace("\\\\", "\\");
+                        format!("{}.{}", nombre.to_lowercase(), subseccion)
+                    }
+                    None => header.to_lowercase(),
+                });
+                continue;
+            }
+            let seccion = seccion.as_ref().ok_or_else(invalida)?;
+            // una clave sin valor es un booleano en true
+            let (nombre, valor) = match linea.split_once('=') {
+                Some((nombre, valor)) => (nombre.trim(), parse_value(valor).ok_or_else(invalida)?),
+                None => (linea, "true".to_string()),
+            };
+            let clave =
+                normalize_key(&format!("{}.{}", seccion, nombre)).map_err(|_| invalida())?;
+            entradas.push((clave, valor));
+        }
+        Ok(Config { entradas })
+    }
+
+    /// Lee un archivo de configuracion. Si no existe devuelve una configuracion vacia.
+    pub fn read(path: &str) -> Result<Config, GitrError> {
+        match std::fs::read_to_string(path) {
+            Ok(data) => Config::parse(&data).map_err(|e| error(format!("{}: {}", path, e))),
+            Err(_) => Ok(Config::default()),
+        }
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), GitrError> {
+        file_manager::write_file(path.to_string(), self.to_string())
+    }
+
+    /// Junta la configuracion de todos los niveles. Fuera de un repo no hay nivel local.
+    pub fn load(cliente: &str) -> Result<Config, GitrError> {
+        let mut config = Config::default();
+        for nivel in [ConfigLevel::System, ConfigLevel::Global, ConfigLevel::Local] {
+            if let Ok(path) = nivel.path(cliente) {
+                config.entradas.extend(Config::read(&path)?.entradas);
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn entries(&self) -> &[(String, String)] {
+        &self.entradas
+    }
+
+    /// Devuelve el ultimo valor de la clave.
+    pub fn get(&self, clave: &str) -> Option<String> {
+        self.get_all(clave).pop()
+    }
+
+    pub fn get_all(&self, clave: &str) -> Vec<String> {
+        let Ok(clave) = normalize_key(clave) else {
+            return vec![];
+        };
+        self.entradas
+            .iter()
+            .filter(|(c, _)| *c == clave)
+            .map(|(_, valor)| valor.clone())
+            .collect()
+    }
+
+    pub fn get_bool(&self, clave: &str) -> Result<Option<bool>, GitrError> {
+        match self.get(clave).map(|v| v.to_lowercase()).as_deref() {
+            None => Ok(None),
+            Some("true" | "yes" | "on" | "1") => Ok(Some(true)),
+            Some("false" | "no" | "off" | "0" | "") => Ok(Some(false)),
+            Some(valor) => Err(error(tr("config.not_a_bool", &[&clave, &valor]))),
+        }
+    }
+
+    /// Valor entero, con sufijos k, m o g opcionales.
+    pub fn get_int(&self, clave: &str) -> Result<Option<i64>, GitrError> {
+        let Some(valor) = self.get(clave) else {
+            return Ok(None);
+        };
+        let invalido = || error(tr("config.not_an_int", &[&clave, &valor]));
+        let (numero, factor) = match valor.to_lowercase().chars().last() {
+            Some('k') => (&valor[..valor.len() - 1], 1 << 10),
+            Some('m') => (&valor[..valor.len() - 1], 1 << 20),
+            Some('g') => (&valor[..valor.len() - 1], 1 << 30),
+            _ => (valor.as_str(), 1),
+        };
+        let numero = numero.trim().parse::<i64>().map_err(|_| invalido())?;
+        Ok(Some(numero.checked_mul(factor).ok_or_else(invalido)?))
+    }
+
+    /// Cambia el valor de la clave, o la agrega si no existia.
+    pub fn set(&mut self, clave: &str, valor: &str) -> Result<(), GitrError> {
+        let clave = normalize_key(clave)?;
+        match self.entradas.iter().rposition(|(c, _)| *c == clave) {
+            Some(i) => self.entradas[i].1 = valor.to_string(),
+            None => self.entradas.push((clave, valor.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Borra todos los valores de la clave. Devuelve false si no habia ninguno.
+    pub fn unset(&mut self, clave: &str) -> Result<bool, GitrError> {
+        let clave = normalize_key(clave)?;
+        let antes = self.entradas.len();
+        self.entradas.retain(|(c, _)| *c != clave);
+        Ok(self.entradas.len() != antes)
+    }
+}
+
+// escribe las entradas agrupadas por seccion, en el orden en que aparecen
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut secciones: Vec<(&str, Vec<(&str, &str)>)> = Vec::new();
+        for (clave, valor) in self.entradas.iter() {
+            let Some((seccion, nombre)) = clave.rsplit_once('.') else {
+                continue;
+            };
+            match secciones.iter_mut().find(|(s, _)| *s == seccion) {
+                Some((_, entradas)) => entradas.push((nombre, valor)),
+                None => secciones.push((seccion, vec![(nombre, valor)])),
+            }
+        }
+        for (seccion, entradas) in secciones {
+            match seccion.split_once('.') {
+                Some((nombre, subseccion)) => writeln!(
+                    f,
+                    "[{} \"{}\"]",
+                    nombre,
+                    subseccion.replace('\\', "\\\\").replace('"', "\\\"")
+                )?,
+                None => writeln!(f, "[{}]", seccion)?,
+            }
+            for (nombre, valor) in entradas {
+                writeln!(f, "\t{} = {}", nombre, format_value(valor))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test00_parsea_secciones_subsecciones_y_valores() {
+        let config = Config::parse(
+            "# comentario\n[user]\n\tname = Juan Perez ; otro comentario\n\tEMAIL=juan@mail.com\n\
+             [remote \"Origin\"]\n\turl = \"  con espacios # y numeral \"\n\
+             [core]\n\tbare\n\tquoted = a\\\"b\\\\c\n",
+        )
+        .unwrap();
+        assert_eq!(config.get("user.name").unwrap(), "Juan Perez");
+        assert_eq!(config.get("USER.Email").unwrap(), "juan@mail.com");
+        assert_eq!(
+            config.get("remote.Origin.url").unwrap(),
+            "  con espacios # y numeral "
+        );
+        assert_eq!(config.get("remote.origin.url"), None);
+        assert_eq!(config.get_bool("core.bare").unwrap(), Some(true));
+        assert_eq!(config.get("core.quoted").unwrap(), "a\"b\\c");
+        assert!(Config::parse("clave = sin seccion\n").is_err());
+        assert!(Config::parse("[user\nname = x\n").is_err());
+        assert!(Config::parse("[user]\nname = \"sin cerrar\n").is_err());
+    }
+
+    #[test]
+    fn test01_getters_tipados() {
+        let config = Config::parse(
+            "[pack]\n\tsize = 2k\n\tno = off\n\tmal = quizas\n[x]\n\ty = 1\n\ty = 2\n",
+        )
+        .unwrap();
+        assert_eq!(config.get_int("pack.size").unwrap(), Some(2048));
+        assert_eq!(config.get_bool("pack.no").unwrap(), Some(false));
+        assert!(config.get_bool("pack.mal").is_err());
+        assert!(config.get_int("pack.mal").is_err());
+        assert_eq!(config.get_int("pack.nada").unwrap(), None);
+        assert_eq!(config.get_all("x.y"), vec!["1", "2"]);
+        assert_eq!(config.get("x.y").unwrap(), "2");
+    }
+
+    #[test]
+    fn test02_set_unset_y_escritura() {
+        let mut config = Config::parse("[user]\n\tname = a\n").unwrap();
+        config.set("user.name", "b").unwrap();
+        config
+            .set("remote.origin.url", "localhost:9418/repo")
+            .unwrap();
+        config.set("core.comment", " # ").unwrap();
+        assert!(config.set("sinpunto", "x").is_err());
+        let escrito = config.to_string();
+        assert_eq!(
+            escrito,
+            "[user]\n\tname = b\n[remote \"origin\"]\n\turl = localhost:9418/repo\n[core]\n\tcomment = \" # \"\n"
+        );
+        let releido = Config::parse(&escrito).unwrap();
+        assert_eq!(releido.get("core.comment").unwrap(), " # ");
+        assert!(config.unset("user.name").unwrap());
+        assert!(!config.unset("user.name").unwrap());
+        assert_eq!(config.get("user.name"), None);
+    }
+}
@@ -1,4 +1,15 @@
-use std::cmp::max;
+use std::collections::{HashMap, HashSet};
+
+/// Similitud minima (en %) para considerar que un archivo fue renombrado o copiado.
+pub const RENAME_THRESHOLD: usize = 50;
+
+/// Maximo de apariciones de una linea para que el algoritmo histogram la use como ancla.
+const MAX_APARICIONES_HISTOGRAM: usize = 64;
+
+/// Se agrega a la ultima linea cuando el archivo no termina en salto de linea,
+/// asi "x" y "x\n" se consideran lineas distintas.
+const MARCA_SIN_NEWLINE: char = '\0';
+
 #[derive(Clone, Debug)]
 pub struct Diff {
     pub lineas_eliminadas: Vec<(usize, String)>,
@@ -7,12 +18,112 @@ pub struct Diff {
     pub lineas_extra: usize,
 }
 
-#[derive(Clone, Debug)]
-struct Celda {
-    valor: usize,
-    es_match: bool,
-    fila: usize,
-    columna: usize,
+/// Hunk de un diff unificado. Cada linea empieza con ' ', '-' o '+', o con '\'
+/// cuando la linea anterior no termina en salto de linea.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    pub lineas: Vec<(char, String)>,
+}
+
+impl Hunk {
+    pub fn header(&self) -> String {
+        let rango = |start: usize, len: usize| match len {
+            1 => format!("{}", start),
+            _ => format!("{},{}", start, len),
+        };
+        format!(
+            "@@ -{} +{} @@",
+            rango(self.old_start, self.old_len),
+            rango(self.new_start, self.new_len)
+        )
+    }
+}
+
+/// Hunk de un diff combinado, el de un merge contra todos sus padres. Cada linea
+/// lleva una columna por padre con '+', '-' o ' '.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CombinedHunk {
+    pub parent_ranges: Vec<(usize, usize)>,
+    pub new_start: usize,
+    pub new_len: usize,
+    pub lineas: Vec<(String, String)>,
+}
+
+impl CombinedHunk {
+    pub fn header(&self) -> String {
+        let arrobas = "@".repeat(self.parent_ranges.len() + 1);
+        let rangos = self
+            .parent_ranges
+            .iter()
+            .map(|(start, len)| format!("-{},{}", start, len))
+            .collect::<Vec<String>>()
+            .join(" ");
+        format!(
+            "{} {} +{},{} {}",
+            arrobas, rangos, self.new_start, self.new_len, arrobas
+        )
+    }
+}
+
+/// Como se comparan las lineas al armar un diff unificado.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LineCompare {
+    pub algoritmo: DiffAlgorithm,
+    /// -w: ignora todos los espacios
+    pub ignore_all_space: bool,
+    /// -b: ignora cambios en la cantidad de espacios
+    pub ignore_space_change: bool,
+    /// no muestra cambios que solo agregan o borran lineas en blanco
+    pub ignore_blank_lines: bool,
+}
+
+impl LineCompare {
+    /// Devuelve la linea tal como se compara, segun los flags de espacios.
+    fn clave(&self, linea: &str) -> String {
+        if self.ignore_all_space {
+            return linea.chars().filter(|c| !c.is_whitespace()).collect();
+        }
+        if self.ignore_space_change {
+            let (linea, marca) = match linea.strip_suffix(MARCA_SIN_NEWLINE) {
+                Some(linea) => (linea, MARCA_SIN_NEWLINE.to_string()),
+                None => (linea, String::new()),
+            };
+            let mut clave = String::new();
+            for c in linea.trim_end().chars() {
+                match c.is_whitespace() {
+                    true if clave.ends_with(' ') => {}
+                    true => clave.push(' '),
+                    false => clave.push(c),
+                }
+            }
+            return clave + &marca;
+        }
+        linea.to_string()
+    }
+}
+
+/// Algoritmo usado para alinear las lineas de base y new.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DiffAlgorithm {
+    #[default]
+    Myers,
+    Patience,
+    Histogram,
+}
+
+impl DiffAlgorithm {
+    pub fn from_name(name: &str) -> Option<DiffAlgorithm> {
+        match name {
+            "myers" | "default" => Some(DiffAlgorithm::Myers),
+            "patience" => Some(DiffAlgorithm::Patience),
+            "histogram" => Some(DiffAlgorithm::Histogram),
+            _ => None,
+        }
+    }
 }
 
 fn empty_diff() -> Diff {
@@ -24,147 +135,391 @@ fn empty_diff() -> Diff {
     }
 }
 
-fn valor_match(matriz: &[Vec<Celda>], i: usize, j: usize) -> usize {
-    if i == 0 || j == 0 || (i, j) == (0, 0) {
-        1
-    } else {
-        matriz[i - 1][j - 1].valor + 1
+/// Reemplaza cada linea por un id, asi las comparaciones son entre enteros.
+fn interning<'a>(base_lines: &[&'a str], new_lines: &[&'a str]) -> (Vec<usize>, Vec<usize>) {
+    let mut ids: HashMap<&'a str, usize> = HashMap::new();
+    let mut base = Vec::with_capacity(base_lines.len());
+    let mut new = Vec::with_capacity(new_lines.len());
+    for (lines, destino) in [(base_lines, &mut base), (new_lines, &mut new)] {
+        for line in lines {
+            let next_id = ids.len();
+            destino.push(*ids.entry(line).or_insert(next_id));
+        }
     }
+    (base, new)
 }
 
-fn valor_unmatch(matriz: &[Vec<Celda>], i: usize, j: usize) -> usize {
-    if i == 0 && j == 0 {
-        0
-    } else if i == 0 {
-        return matriz[i][j - 1].valor;
-    } else if j == 0 {
-        return matriz[i - 1][j].valor;
-    } else {
-        return max(matriz[i - 1][j].valor, matriz[i][j - 1].valor);
+/// Saca el prefijo y sufijo en comun, agregando sus lineas a los matches.
+/// Devuelve el largo del prefijo y del sufijo.
+fn recortar_extremos(
+    a: &[usize],
+    b: &[usize],
+    a0: usize,
+    b0: usize,
+    matches: &mut Vec<(usize, usize)>,
+) -> (usize, usize) {
+    let mut prefijo = 0;
+    while prefijo < a.len() && prefijo < b.len() && a[prefijo] == b[prefijo] {
+        matches.push((a0 + prefijo, b0 + prefijo));
+        prefijo += 1;
+    }
+    let mut sufijo = 0;
+    while sufijo < a.len() - prefijo
+        && sufijo < b.len() - prefijo
+        && a[a.len() - 1 - sufijo] == b[b.len() - 1 - sufijo]
+    {
+        sufijo += 1;
     }
+    (prefijo, sufijo)
 }
 
-fn get_diff(
-    matriz_coincidencias: Vec<Vec<Celda>>,
-    len_columna: usize,
-    len_fila: usize,
-) -> (Vec<usize>, Vec<usize>) {
-    let mut stack = Vec::new();
+fn agregar_sufijo(
+    a_len: usize,
+    b_len: usize,
+    sufijo: usize,
+    a0: usize,
+    b0: usize,
+    matches: &mut Vec<(usize, usize)>,
+) {
+    for i in 0..sufijo {
+        matches.push((a0 + a_len - sufijo + i, b0 + b_len - sufijo + i));
+    }
+}
 
-    let mut j = len_columna;
-    let mut i = len_fila;
+/// Busca el "middle snake" de Myers: el tramo diagonal que esta en la mitad del
+/// camino de edicion minimo. Devuelve (x_inicio, y_inicio, x_fin, y_fin).
+fn middle_snake(a: &[usize], b: &[usize]) -> (usize, usize, usize, usize) {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let delta = n - m;
+    let impar = delta % 2 != 0;
+    let max = (n + m + 1) / 2;
+    let offset = max + 1;
+    let mut v_forward = vec![0isize; (2 * max + 3) as usize];
+    let mut v_backward = vec![0isize; (2 * max + 3) as usize];
+    let idx = |k: isize| (k + offset) as usize;
 
-    let mut num_bloque_actual = matriz_coincidencias[j][i].valor;
-    loop {
-        if i == 0 && j == 0 {
-            if matriz_coincidencias[j][i].es_match {
-                stack.push(matriz_coincidencias[j][i].clone());
+    for d in 0..=max {
+        let mut k = -d;
+        while k <= d {
+            let mut x = if k == -d || (k != d && v_forward[idx(k - 1)] < v_forward[idx(k + 1)]) {
+                v_forward[idx(k + 1)]
+            } else {
+                v_forward[idx(k - 1)] + 1
+            };
+            let mut y = x - k;
+            let (x_inicio, y_inicio) = (x, y);
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
             }
-            break;
+            v_forward[idx(k)] = x;
+            let c = delta - k;
+            if impar && c >= -(d - 1) && c < d && x + v_backward[idx(c)] >= n {
+                return (x_inicio as usize, y_inicio as usize, x as usize, y as usize);
+            }
+            k += 2;
         }
 
-        if matriz_coincidencias[j][i].es_match {
-            stack.push(matriz_coincidencias[j][i].clone());
-            num_bloque_actual -= 1;
-            i = i.saturating_sub(1);
-            j = j.saturating_sub(1);
-        } else {
-            if j == 0 && matriz_coincidencias[j][i - 1].valor == num_bloque_actual {
-                i = i.saturating_sub(1);
-                j = j.saturating_sub(1);
-                continue;
+        let mut c = -d;
+        while c <= d {
+            let mut x = if c == -d || (c != d && v_backward[idx(c - 1)] < v_backward[idx(c + 1)]) {
+                v_backward[idx(c + 1)]
+            } else {
+                v_backward[idx(c - 1)] + 1
+            };
+            let mut y = x - c;
+            let (x_inicio, y_inicio) = (x, y);
+            while x < n && y < m && a[(n - 1 - x) as usize] == b[(m - 1 - y) as usize] {
+                x += 1;
+                y += 1;
             }
-
-            if i == 0 && matriz_coincidencias[j - 1][i].valor == num_bloque_actual {
-                i = i.saturating_sub(1);
-                j = j.saturating_sub(1);
-                continue;
+            v_backward[idx(c)] = x;
+            let k = delta - c;
+            if !impar && k >= -d && k <= d && x + v_forward[idx(k)] >= n {
+                return (
+                    (n - x) as usize,
+                    (m - y) as usize,
+                    (n - x_inicio) as usize,
+                    (m - y_inicio) as usize,
+                );
             }
+            c += 2;
+        }
+    }
+    (0, 0, 0, 0)
+}
 
-            if matriz_coincidencias[j - 1][i - 1].valor == num_bloque_actual {
-                i = i.saturating_sub(1);
-                j = j.saturating_sub(1);
-                continue;
-            } else {
-                let mut k = j;
-                let mut la_encontre_yendo_arriba = false;
-
-                loop {
-                    if matriz_coincidencias[k][i].es_match {
-                        la_encontre_yendo_arriba = true;
-                        j = k;
-
-                        stack.push(matriz_coincidencias[j][i].clone());
-                        i = i.saturating_sub(1);
-                        j = j.saturating_sub(1);
-
-                        num_bloque_actual -= 1;
-
-                        break;
-                    }
-
-                    if matriz_coincidencias[k - 1][i].valor != num_bloque_actual {
-                        break;
-                    }
+/// Myers en espacio lineal: divide el problema por el middle snake y recursa.
+fn myers(a: &[usize], b: &[usize], a0: usize, b0: usize, matches: &mut Vec<(usize, usize)>) {
+    let (prefijo, sufijo) = recortar_extremos(a, b, a0, b0, matches);
+    let a_medio = &a[prefijo..a.len() - sufijo];
+    let b_medio = &b[prefijo..b.len() - sufijo];
+    if !a_medio.is_empty() && !b_medio.is_empty() {
+        let (x_inicio, y_inicio, x_fin, y_fin) = middle_snake(a_medio, b_medio);
+        let (a1, b1) = (a0 + prefijo, b0 + prefijo);
+        myers(&a_medio[..x_inicio], &b_medio[..y_inicio], a1, b1, matches);
+        for i in 0..(x_fin - x_inicio) {
+            matches.push((a1 + x_inicio + i, b1 + y_inicio + i));
+        }
+        myers(
+            &a_medio[x_fin..],
+            &b_medio[y_fin..],
+            a1 + x_fin,
+            b1 + y_fin,
+            matches,
+        );
+    }
+    agregar_sufijo(a.len(), b.len(), sufijo, a0, b0, matches);
+}
 
-                    k = k.saturating_sub(1);
-                }
-                if !la_encontre_yendo_arriba {
-                    let mut k = i;
+/// Subsecuencia creciente mas larga de los indices de b (patience sorting).
+fn subsecuencia_creciente(pares: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut pilas: Vec<usize> = vec![];
+    let mut anterior: Vec<Option<usize>> = vec![None; pares.len()];
+    for (i, par) in pares.iter().enumerate() {
+        let pos = pilas.partition_point(|&p| pares[p].1 < par.1);
+        if pos > 0 {
+            anterior[i] = Some(pilas[pos - 1]);
+        }
+        if pos == pilas.len() {
+            pilas.push(i);
+        } else {
+            pilas[pos] = i;
+        }
+    }
+    let mut resultado = vec![];
+    let mut actual = pilas.last().copied();
+    while let Some(i) = actual {
+        resultado.push(pares[i]);
+        actual = anterior[i];
+    }
+    resultado.reverse();
+    resultado
+}
 
-                    loop {
-                        if matriz_coincidencias[j][k].es_match {
-                            i = k;
-                            stack.push(matriz_coincidencias[j][i].clone());
-                            i = i.saturating_sub(1);
-                            j = j.saturating_sub(1);
+/// Patience: ancla el diff en las lineas que aparecen una sola vez en ambos lados.
+fn patience(a: &[usize], b: &[usize], a0: usize, b0: usize, matches: &mut Vec<(usize, usize)>) {
+    let (prefijo, sufijo) = recortar_extremos(a, b, a0, b0, matches);
+    let a_medio = &a[prefijo..a.len() - sufijo];
+    let b_medio = &b[prefijo..b.len() - sufijo];
+    let (a1, b1) = (a0 + prefijo, b0 + prefijo);
+    if !a_medio.is_empty() && !b_medio.is_empty() {
+        let mut apariciones: HashMap<usize, (usize, usize, usize, usize)> = HashMap::new();
+        for (i, linea) in a_medio.iter().enumerate() {
+            let entrada = apariciones.entry(*linea).or_insert((0, 0, i, 0));
+            entrada.0 += 1;
+        }
+        for (j, linea) in b_medio.iter().enumerate() {
+            if let Some(entrada) = apariciones.get_mut(linea) {
+                entrada.1 += 1;
+                entrada.3 = j;
+            }
+        }
+        let mut unicas = apariciones
+            .values()
+            .filter(|(en_a, en_b, _, _)| *en_a == 1 && *en_b == 1)
+            .map(|(_, _, i, j)| (*i, *j))
+            .collect::<Vec<(usize, usize)>>();
+        unicas.sort();
+        let anclas = subsecuencia_creciente(&unicas);
 
-                            num_bloque_actual -= 1;
+        if anclas.is_empty() {
+            myers(a_medio, b_medio, a1, b1, matches);
+        } else {
+            let (mut i_prev, mut j_prev) = (0, 0);
+            for (i, j) in anclas {
+                patience(
+                    &a_medio[i_prev..i],
+                    &b_medio[j_prev..j],
+                    a1 + i_prev,
+                    b1 + j_prev,
+                    matches,
+                );
+                matches.push((a1 + i, b1 + j));
+                (i_prev, j_prev) = (i + 1, j + 1);
+            }
+            patience(
+                &a_medio[i_prev..],
+                &b_medio[j_prev..],
+                a1 + i_prev,
+                b1 + j_prev,
+                matches,
+            );
+        }
+    }
+    agregar_sufijo(a.len(), b.len(), sufijo, a0, b0, matches);
+}
 
-                            break;
+/// Histogram: ancla el diff en la region en comun que contiene la linea menos repetida de base.
+fn histogram(a: &[usize], b: &[usize], a0: usize, b0: usize, matches: &mut Vec<(usize, usize)>) {
+    let (prefijo, sufijo) = recortar_extremos(a, b, a0, b0, matches);
+    let a_medio = &a[prefijo..a.len() - sufijo];
+    let b_medio = &b[prefijo..b.len() - sufijo];
+    let (a1, b1) = (a0 + prefijo, b0 + prefijo);
+    if !a_medio.is_empty() && !b_medio.is_empty() {
+        let mut posiciones: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (i, linea) in a_medio.iter().enumerate() {
+            posiciones.entry(*linea).or_default().push(i);
+        }
+        // (apariciones, inicio_a, inicio_b, largo)
+        let mut mejor: Option<(usize, usize, usize, usize)> = None;
+        let mut j = 0;
+        while j < b_medio.len() {
+            let mut siguiente_j = j + 1;
+            if let Some(en_a) = posiciones.get(&b_medio[j]) {
+                let es_candidata = match mejor {
+                    Some((apariciones, _, _, _)) => en_a.len() <= apariciones,
+                    None => en_a.len() <= MAX_APARICIONES_HISTOGRAM,
+                };
+                if es_candidata {
+                    for &i in en_a {
+                        let (mut ai, mut bj) = (i, j);
+                        while ai > 0 && bj > 0 && a_medio[ai - 1] == b_medio[bj - 1] {
+                            ai -= 1;
+                            bj -= 1;
                         }
-
-                        if matriz_coincidencias[j][k - 1].valor != num_bloque_actual {
-                            break;
+                        let mut largo = 0;
+                        while ai + largo < a_medio.len()
+                            && bj + largo < b_medio.len()
+                            && a_medio[ai + largo] == b_medio[bj + largo]
+                        {
+                            largo += 1;
                         }
-                        k = k.saturating_sub(1);
+                        let mejora = match mejor {
+                            Some((apariciones, _, _, mejor_largo)) => {
+                                en_a.len() < apariciones || largo > mejor_largo
+                            }
+                            None => true,
+                        };
+                        if mejora {
+                            mejor = Some((en_a.len(), ai, bj, largo));
+                        }
+                        siguiente_j = siguiente_j.max(bj + largo);
                     }
                 }
             }
+            j = siguiente_j;
+        }
+
+        match mejor {
+            Some((_, ai, bj, largo)) => {
+                histogram(&a_medio[..ai], &b_medio[..bj], a1, b1, matches);
+                for k in 0..largo {
+                    matches.push((a1 + ai + k, b1 + bj + k));
+                }
+                histogram(
+                    &a_medio[ai + largo..],
+                    &b_medio[bj + largo..],
+                    a1 + ai + largo,
+                    b1 + bj + largo,
+                    matches,
+                );
+            }
+            None => myers(a_medio, b_medio, a1, b1, matches),
         }
     }
-    let base_numbers = stack.iter().map(|x| x.fila).collect::<Vec<usize>>();
-    let new_numbers = stack.iter().map(|x| x.columna).collect::<Vec<usize>>();
+    agregar_sufijo(a.len(), b.len(), sufijo, a0, b0, matches);
+}
 
-    let mut lineas_eliminadas = Vec::new();
-    for i in 0..(len_columna + 1) {
-        if !base_numbers.contains(&i) {
-            lineas_eliminadas.push(i);
+/// Agrupa las lineas cambiadas segun cuantas lineas sin cambios tienen antes.
+/// El hueco i de base se corresponde con el hueco i de new.
+fn huecos(cambiada: &[bool]) -> Vec<Vec<usize>> {
+    let mut huecos = vec![vec![]];
+    for (i, c) in cambiada.iter().enumerate() {
+        match (c, huecos.last_mut()) {
+            (true, Some(hueco)) => hueco.push(i),
+            _ => huecos.push(vec![]),
         }
     }
+    huecos
+}
 
-    let mut lineas_agregadas = Vec::new();
-    for i in 0..(len_fila + 1) {
-        if !new_numbers.contains(&i) {
-            lineas_agregadas.push(i);
+/// Para cada hueco donde solo cambio el otro lado, intenta traer una linea cambiada
+/// de x deslizandola sobre lineas iguales, asi eliminaciones y agregados quedan juntos.
+fn completar_huecos(x: &[usize], x_cambiada: &mut [bool], huecos_y: &[Vec<usize>]) {
+    let mut huecos_x = huecos(x_cambiada);
+    let mut siguiente_no_vacio = vec![None; huecos_x.len()];
+    for g in (0..huecos_x.len().saturating_sub(1)).rev() {
+        siguiente_no_vacio[g] = match huecos_x[g + 1].is_empty() {
+            true => siguiente_no_vacio[g + 1],
+            false => Some(g + 1),
+        };
+    }
+    let mut anterior_no_vacio: Option<usize> = None;
+    for g in 0..huecos_x.len() {
+        if huecos_y[g].is_empty() || !huecos_x[g].is_empty() {
+            if !huecos_x[g].is_empty() {
+                anterior_no_vacio = Some(g);
+            }
+            continue;
+        }
+        if let Some(g_prev) = anterior_no_vacio {
+            let p = huecos_x[g_prev][huecos_x[g_prev].len() - 1];
+            let q = p + (g - g_prev);
+            let sigue_emparejado = huecos_x[g_prev].len() > 1 || huecos_y[g_prev].is_empty();
+            if sigue_emparejado && x[p..=q].iter().all(|linea| *linea == x[p]) {
+                x_cambiada[p] = false;
+                x_cambiada[q] = true;
+                huecos_x[g_prev].pop();
+                huecos_x[g].push(q);
+                anterior_no_vacio = Some(g);
+                continue;
+            }
+        }
+        if let Some(g_next) = siguiente_no_vacio[g] {
+            if huecos_x[g_next].is_empty() {
+                continue;
+            }
+            let p = huecos_x[g_next][0];
+            let q = p - (g_next - g);
+            let sigue_emparejado = huecos_x[g_next].len() > 1 || huecos_y[g_next].is_empty();
+            if sigue_emparejado && x[q..=p].iter().all(|linea| *linea == x[p]) {
+                x_cambiada[p] = false;
+                x_cambiada[q] = true;
+                huecos_x[g_next].remove(0);
+                huecos_x[g].push(q);
+                anterior_no_vacio = Some(g);
+            }
         }
     }
+}
 
-    (lineas_eliminadas, lineas_agregadas)
+/// Marca que lineas de base fueron eliminadas y que lineas de new fueron agregadas.
+fn lineas_cambiadas(
+    base_lines: &[&str],
+    new_lines: &[&str],
+    algoritmo: DiffAlgorithm,
+) -> (Vec<bool>, Vec<bool>) {
+    let (a, b) = interning(base_lines, new_lines);
+    let mut matches = Vec::new();
+    match algoritmo {
+        DiffAlgorithm::Myers => myers(&a, &b, 0, 0, &mut matches),
+        DiffAlgorithm::Patience => patience(&a, &b, 0, 0, &mut matches),
+        DiffAlgorithm::Histogram => histogram(&a, &b, 0, 0, &mut matches),
+    }
+    let mut a_cambiada = vec![true; a.len()];
+    let mut b_cambiada = vec![true; b.len()];
+    for (i, j) in matches {
+        a_cambiada[i] = false;
+        b_cambiada[j] = false;
+    }
+    completar_huecos(&a, &mut a_cambiada, &huecos(&b_cambiada));
+    completar_huecos(&b, &mut b_cambiada, &huecos(&a_cambiada));
+    (a_cambiada, b_cambiada)
 }
 
 impl Diff {
     pub fn new(base: String, new: String) -> Diff {
+        Diff::new_with_algorithm(base, new, DiffAlgorithm::default())
+    }
+
+    pub fn new_with_algorithm(base: String, new: String, algoritmo: DiffAlgorithm) -> Diff {
         if base == new {
             return empty_diff();
         }
         if base.is_empty() {
-            let mut only_add_diff = Diff {
-                lineas_eliminadas: vec![],
-                lineas_agregadas: vec![],
-                lineas: vec![],
-                lineas_extra: 0,
-            };
+            let mut only_add_diff = empty_diff();
             let new_lines = new.split('\n').collect::<Vec<&str>>();
             for (i, line) in new_lines.iter().enumerate() {
                 only_add_diff.lineas.push((i, true, line.to_string()));
@@ -174,66 +529,29 @@ impl Diff {
         let base_lines = base.lines().collect::<Vec<&str>>();
         let new_lines = new.lines().collect::<Vec<&str>>();
 
-        let mut matriz_coincidencias: Vec<Vec<Celda>> = vec![vec![]];
-
-        for (i, base_line) in base_lines.iter().enumerate() {
-            for (j, new_line) in new_lines.iter().enumerate() {
-                if base_line == new_line {
-                    let next_value = valor_match(&matriz_coincidencias, i, j);
-                    matriz_coincidencias[i].push(Celda {
-                        valor: next_value,
-                        es_match: true,
-                        //valor_matcheado: base_line.to_string(),
-                        fila: i,
-                        columna: j,
-                    });
-                } else {
-                    let next_value = valor_unmatch(&matriz_coincidencias, i, j);
-                    matriz_coincidencias[i].push(Celda {
-                        valor: next_value,
-                        es_match: false,
-                        fila: i,
-                        columna: j,
-                    });
-                }
-            }
-
-            matriz_coincidencias.push(vec![]);
-        }
-
-        let (indices_lineas_eliminadas, indices_lineas_agregadas) = get_diff(
-            matriz_coincidencias,
-            base_lines.len() - 1,
-            new_lines.len() - 1,
-        );
+        let (base_cambiada, new_cambiada) = lineas_cambiadas(&base_lines, &new_lines, algoritmo);
 
         let mut lineas_eliminadas = Vec::new();
         let mut lineas_agregadas = Vec::new();
-
-        let mut lineas = Vec::new();
-
         for (i, line) in base_lines.iter().enumerate() {
-            if indices_lineas_eliminadas.contains(&i) {
-                lineas.push((i, false, line.to_string()));
+            if base_cambiada[i] {
+                lineas_eliminadas.push((i, line.to_string()));
             }
         }
         for (i, line) in new_lines.iter().enumerate() {
-            if indices_lineas_agregadas.contains(&i) {
-                lineas.push((i, true, line.to_string()));
+            if new_cambiada[i] {
+                lineas_agregadas.push((i, line.to_string()));
             }
         }
-        lineas.sort_by(|a, b| a.0.cmp(&b.0)); //ordeno ascendente
 
-        for (i, line) in base_lines.iter().enumerate() {
-            if indices_lineas_eliminadas.contains(&i) {
-                lineas_eliminadas.push((i, line.to_string()));
-            }
+        let mut lineas = Vec::new();
+        for (i, line) in lineas_eliminadas.iter() {
+            lineas.push((*i, false, line.clone()));
         }
-        for (i, line) in new_lines.iter().enumerate() {
-            if indices_lineas_agregadas.contains(&i) {
-                lineas_agregadas.push((i, line.to_string()));
-            }
+        for (i, line) in lineas_agregadas.iter() {
+            lineas.push((*i, true, line.clone()));
         }
+        lineas.sort_by_key(|linea| linea.0); //ordeno ascendente
 
         Diff {
             lineas_eliminadas,
@@ -261,12 +579,399 @@ impl Diff {
         }
         linea
     }
+
+    /// Recorre old y new en orden y devuelve (operacion, indice en old, indice en new).
+    fn operaciones(&self, old_len: usize, new_len: usize) -> Vec<(char, usize, usize)> {
+        let eliminadas = self
+            .lineas_eliminadas
+            .iter()
+            .map(|l| l.0)
+            .collect::<HashSet<usize>>();
+        let agregadas = self
+            .lineas_agregadas
+            .iter()
+            .map(|l| l.0)
+            .collect::<HashSet<usize>>();
+        let mut operaciones = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < old_len || j < new_len {
+            if i < old_len && eliminadas.contains(&i) {
+                operaciones.push(('-', i, j));
+                i += 1;
+            } else if j < new_len && (agregadas.contains(&j) || i >= old_len) {
+                operaciones.push(('+', i, j));
+                j += 1;
+            } else if j >= new_len {
+                operaciones.push(('-', i, j));
+                i += 1;
+            } else {
+                operaciones.push((' ', i, j));
+                i += 1;
+                j += 1;
+            }
+        }
+        operaciones
+    }
+
+    /// Agrupa los cambios en hunks, juntando los que quedan a menos de 2 * contexto lineas.
+    /// Con ignore_blank_lines, los grupos de cambios que son solo lineas en blanco
+    /// no generan hunks (pero se muestran si quedan dentro de otro hunk).
+    pub fn hunks(
+        &self,
+        old_lines: &[String],
+        new_lines: &[String],
+        contexto: usize,
+        ignore_blank_lines: bool,
+    ) -> Vec<Hunk> {
+        let operaciones = self.operaciones(old_lines.len(), new_lines.len());
+        let linea_de = |(op, i, j): &(char, usize, usize)| match op {
+            '+' => &new_lines[*j],
+            _ => &old_lines[*i],
+        };
+        let mut cambios = Vec::new();
+        let mut grupo = Vec::new();
+        for (k, op) in operaciones.iter().enumerate() {
+            if op.0 != ' ' {
+                grupo.push(k);
+            }
+            if op.0 == ' ' || k == operaciones.len() - 1 {
+                let solo_blancos = grupo.iter().all(|g| {
+                    linea_de(&operaciones[*g])
+                        .trim_end_matches(MARCA_SIN_NEWLINE)
+                        .trim()
+                        .is_empty()
+                });
+                if !(ignore_blank_lines && solo_blancos) {
+                    cambios.append(&mut grupo);
+                }
+                grupo.clear();
+            }
+        }
+
+        let mut hunks = Vec::new();
+        let mut k = 0;
+        while k < cambios.len() {
+            let inicio = cambios[k].saturating_sub(contexto);
+            while k + 1 < cambios.len() && cambios[k + 1] - cambios[k] <= 2 * contexto + 1 {
+                k += 1;
+            }
+            let fin = (cambios[k] + contexto).min(operaciones.len() - 1);
+            k += 1;
+
+            let mut hunk = Hunk {
+                old_start: operaciones[inicio].1,
+                old_len: 0,
+                new_start: operaciones[inicio].2,
+                new_len: 0,
+                lineas: Vec::new(),
+            };
+            for operacion in operaciones[inicio..=fin].iter() {
+                let op = &operacion.0;
+                match op {
+                    '+' => hunk.new_len += 1,
+                    '-' => hunk.old_len += 1,
+                    _ => {
+                        hunk.old_len += 1;
+                        hunk.new_len += 1;
+                    }
+                }
+                let linea = linea_de(operacion);
+                match linea.strip_suffix(MARCA_SIN_NEWLINE) {
+                    Some(linea) => {
+                        hunk.lineas.push((*op, linea.to_string()));
+                        hunk.lineas
+                            .push(('\\', " No newline at end of file".to_string()));
+                    }
+                    None => hunk.lineas.push((*op, linea.clone())),
+                }
+            }
+            if hunk.old_len > 0 {
+                hunk.old_start += 1;
+            }
+            if hunk.new_len > 0 {
+                hunk.new_start += 1;
+            }
+            hunks.push(hunk);
+        }
+        hunks
+    }
+}
+
+/// Separa un archivo en lineas, marcando la ultima si no termina en salto de linea.
+fn lineas_con_marca(texto: &str) -> Vec<String> {
+    let mut lineas = texto
+        .lines()
+        .map(|l| l.to_string())
+        .collect::<Vec<String>>();
+    if !texto.is_empty() && !texto.ends_with('\n') {
+        if let Some(ultima) = lineas.last_mut() {
+            ultima.push(MARCA_SIN_NEWLINE);
+        }
+    }
+    lineas
+}
+
+/// Arma los hunks del diff unificado entre old y new, con `contexto` lineas
+/// sin cambios alrededor de cada cambio.
+pub fn unified_hunks(old: &str, new: &str, contexto: usize, comparacion: LineCompare) -> Vec<Hunk> {
+    let old_lines = lineas_con_marca(old);
+    let new_lines = lineas_con_marca(new);
+    let diff = match old_lines.is_empty() {
+        true => {
+            let mut only_add_diff = empty_diff();
+            for (i, line) in new_lines.iter().enumerate() {
+                only_add_diff.lineas_agregadas.push((i, line.clone()));
+            }
+            only_add_diff
+        }
+        false => {
+            let claves = |lineas: &[String]| {
+                lineas
+                    .iter()
+                    .map(|l| comparacion.clave(l) + "\n")
+                    .collect::<String>()
+            };
+            Diff::new_with_algorithm(
+                claves(&old_lines),
+                claves(&new_lines),
+                comparacion.algoritmo,
+            )
+        }
+    };
+    diff.hunks(
+        &old_lines,
+        &new_lines,
+        contexto,
+        comparacion.ignore_blank_lines,
+    )
+}
+
+// una linea del diff combinado: columnas, texto y de que padres consume una linea
+struct LineaCombinada {
+    columnas: Vec<char>,
+    linea: String,
+    consume: Vec<bool>,
+    es_resultado: bool,
+}
+
+/// Arma los hunks del diff combinado entre el resultado de un merge y cada uno de
+/// sus padres, con `contexto` lineas sin cambios alrededor de cada cambio.
+pub fn combined_hunks(padres: &[&str], resultado: &str, contexto: usize) -> Vec<CombinedHunk> {
+    let lineas_resultado = lineas_con_marca(resultado);
+    let mut agregadas = vec![HashSet::new(); padres.len()];
+    let mut borradas: Vec<HashMap<usize, Vec<String>>> = vec![HashMap::new(); padres.len()];
+    for (p, padre) in padres.iter().enumerate() {
+        for hunk in unified_hunks(padre, resultado, 0, LineCompare::default()) {
+            let mut k = match hunk.new_len {
+                0 => hunk.new_start,
+                _ => hunk.new_start - 1,
+            };
+            let mut ultima_borrada = false;
+            for (op, linea) in hunk.lineas {
+                match op {
+                    '+' => {
+                        agregadas[p].insert(k);
+                        k += 1;
+                        ultima_borrada = false;
+                    }
+                    '-' => {
+                        borradas[p].entry(k).or_default().push(linea);
+                        ultima_borrada = true;
+                    }
+                    '\\' if ultima_borrada => {
+                        if let Some(linea) = borradas[p].get_mut(&k).and_then(|l| l.last_mut()) {
+                            linea.push(MARCA_SIN_NEWLINE);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    let mut lineas = Vec::new();
+    for k in 0..=lineas_resultado.len() {
+        for p in 0..padres.len() {
+            for linea in borradas[p].get(&k).into_iter().flatten() {
+                let mut columnas = vec![' '; padres.len()];
+                columnas[p] = '-';
+                let mut consume = vec![false; padres.len()];
+                consume[p] = true;
+                lineas.push(LineaCombinada {
+                    columnas,
+                    linea: linea.clone(),
+                    consume,
+                    es_resultado: false,
+                });
+            }
+        }
+        if let Some(linea) = lineas_resultado.get(k) {
+            let columnas = (0..padres.len())
+                .map(|p| if agregadas[p].contains(&k) { '+' } else { ' ' })
+                .collect::<Vec<char>>();
+            let consume = columnas.iter().map(|c| *c == ' ').collect();
+            lineas.push(LineaCombinada {
+                columnas,
+                linea: linea.clone(),
+                consume,
+                es_resultado: true,
+            });
+        }
+    }
+
+    // rangos de lineas a mostrar, juntando los que se tocan
+    let mut rangos: Vec<(usize, usize)> = Vec::new();
+    for (i, linea) in lineas.iter().enumerate() {
+        if linea.columnas.iter().all(|c| *c == ' ') {
+            continue;
+        }
+        let (desde, hasta) = (
+            i.saturating_sub(contexto),
+            (i + contexto + 1).min(lineas.len()),
+        );
+        match rangos.last_mut() {
+            Some(ultimo) if desde <= ultimo.1 => ultimo.1 = hasta,
+            _ => rangos.push((desde, hasta)),
+        }
+    }
+
+    let inicio = |antes: usize, largo: usize| if largo == 0 { antes } else { antes + 1 };
+    let mut hunks = Vec::new();
+    for (desde, hasta) in rangos {
+        let parent_ranges = (0..padres.len())
+            .map(|p| {
+                let antes = lineas[..desde].iter().filter(|l| l.consume[p]).count();
+                let largo = lineas[desde..hasta].iter().filter(|l| l.consume[p]).count();
+                (inicio(antes, largo), largo)
+            })
+            .collect();
+        let antes = lineas[..desde].iter().filter(|l| l.es_resultado).count();
+        let new_len = lineas[desde..hasta]
+            .iter()
+            .filter(|l| l.es_resultado)
+            .count();
+        let mut hunk = CombinedHunk {
+            parent_ranges,
+            new_start: inicio(antes, new_len),
+            new_len,
+            lineas: vec![],
+        };
+        for linea in lineas[desde..hasta].iter() {
+            let columnas = linea.columnas.iter().collect::<String>();
+            match linea.linea.strip_suffix(MARCA_SIN_NEWLINE) {
+                Some(texto) => {
+                    hunk.lineas.push((columnas, texto.to_string()));
+                    hunk.lineas
+                        .push(("\\".to_string(), " No newline at end of file".to_string()));
+                }
+                None => hunk.lineas.push((columnas, linea.linea.clone())),
+            }
+        }
+        hunks.push(hunk);
+    }
+    hunks
+}
+
+/// Para cada linea de new, el indice de la linea de old de la que viene sin cambios,
+/// o None si es una linea nueva.
+pub fn matching_lines(old: &str, new: &str) -> Vec<Option<usize>> {
+    let cantidad = lineas_con_marca(new).len();
+    let mut res = vec![None; cantidad];
+    let (mut i, mut j) = (0, 0);
+    for hunk in unified_hunks(old, new, 0, LineCompare::default()) {
+        let inicio = match hunk.new_len {
+            0 => hunk.new_start,
+            _ => hunk.new_start - 1,
+        };
+        while j < inicio {
+            res[j] = Some(i);
+            i += 1;
+            j += 1;
+        }
+        i += hunk.old_len;
+        j += hunk.new_len;
+    }
+    while j < cantidad {
+        res[j] = Some(i);
+        i += 1;
+        j += 1;
+    }
+    res
+}
+
+/// Porcentaje de lineas en comun entre dos archivos, de 0 a 100. Un archivo vacio no se
+/// parece a ninguno, ni siquiera a otro vacio, para no emparejar archivos sin relacion.
+pub fn similarity(old: &str, new: &str) -> usize {
+    if old.is_empty() || new.is_empty() {
+        return 0;
+    }
+    if old == new {
+        return 100;
+    }
+    let (old_len, new_len) = (old.lines().count(), new.lines().count());
+    if old_len == 0 || new_len == 0 {
+        return 0;
+    }
+    let diff = Diff::new(old.to_string(), new.to_string());
+    let en_comun = old_len - diff.lineas_eliminadas.len();
+    en_comun * 200 / (old_len + new_len)
+}
+
+/// Empareja cada destino con el origen mas parecido, si la similitud llega al umbral.
+/// Para renames cada origen se usa una sola vez; para copias se puede repetir.
+/// Devuelve (indice del origen, indice del destino, similitud).
+pub fn detect_renames(
+    origenes: &[&str],
+    destinos: &[&str],
+    umbral: usize,
+    copias: bool,
+) -> Vec<(usize, usize, usize)> {
+    let mut candidatos = Vec::new();
+    for (i, origen) in origenes.iter().enumerate() {
+        let origen_len = origen.lines().count();
+        for (j, destino) in destinos.iter().enumerate() {
+            if origen.is_empty() || destino.is_empty() {
+                continue;
+            }
+            // cota de la similitud segun la cantidad de lineas, para no calcular diffs de mas
+            let destino_len = destino.lines().count();
+            let maxima = match origen_len + destino_len {
+                0 => 100,
+                total => origen_len.min(destino_len) * 200 / total,
+            };
+            if maxima < umbral && origen != destino {
+                continue;
+            }
+            let score = similarity(origen, destino);
+            if score >= umbral {
+                candidatos.push((i, j, score));
+            }
+        }
+    }
+    candidatos.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)).then(a.1.cmp(&b.1)));
+
+    let mut origenes_usados = HashSet::new();
+    let mut destinos_usados = HashSet::new();
+    let mut pares = Vec::new();
+    for (i, j, score) in candidatos {
+        if destinos_usados.contains(&j) || (!copias && origenes_usados.contains(&i)) {
+            continue;
+        }
+        origenes_usados.insert(i);
+        destinos_usados.insert(j);
+        pares.push((i, j, score));
+    }
+    pares
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn indices(lineas: &[(usize, String)]) -> Vec<usize> {
+        lineas.iter().map(|l| l.0).collect()
+    }
+
     #[test]
     fn test00_diff_entre_string_iguales_esta_vacio() {
         let base = "hola".to_string();
@@ -274,4 +979,207 @@ mod tests {
         let diff = Diff::new(base, new);
         assert!(diff.lineas_eliminadas.is_empty());
     }
+
+    #[test]
+    fn test01_diff_con_una_linea_cambiada_en_el_medio() {
+        let base = "hola\ncomo\nestas\n".to_string();
+        let new = "hola\nque\nestas\n".to_string();
+        let diff = Diff::new(base, new);
+        assert_eq!(diff.lineas_eliminadas, vec![(1, "como".to_string())]);
+        assert_eq!(diff.lineas_agregadas, vec![(1, "que".to_string())]);
+        assert_eq!(
+            diff.lineas,
+            vec![(1, false, "como".to_string()), (1, true, "que".to_string())]
+        );
+    }
+
+    #[test]
+    fn test02_diff_con_new_vacio_elimina_todas_las_lineas() {
+        let base = "hola\ncomo\n".to_string();
+        let diff = Diff::new(base, "".to_string());
+        assert_eq!(indices(&diff.lineas_eliminadas), vec![0, 1]);
+        assert!(diff.lineas_agregadas.is_empty());
+    }
+
+    #[test]
+    fn test03_los_tres_algoritmos_encuentran_un_diff_minimo() {
+        let base = "a\nb\nc\na\nb\nb\na\n".to_string();
+        let new = "c\nb\na\nb\na\nc\n".to_string();
+        for algoritmo in [
+            DiffAlgorithm::Myers,
+            DiffAlgorithm::Patience,
+            DiffAlgorithm::Histogram,
+        ] {
+            let diff = Diff::new_with_algorithm(base.clone(), new.clone(), algoritmo);
+            let base_lines = base.lines().count();
+            let new_lines = new.lines().count();
+            let en_comun = base_lines - diff.lineas_eliminadas.len();
+            assert_eq!(en_comun, new_lines - diff.lineas_agregadas.len());
+            if algoritmo == DiffAlgorithm::Myers {
+                assert_eq!(
+                    diff.lineas_eliminadas.len() + diff.lineas_agregadas.len(),
+                    5
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test04_patience_ancla_en_lineas_unicas() {
+        let base = "}\nfn uno() {\n}\n".to_string();
+        let new = "}\nfn dos() {\n}\nfn uno() {\n}\n".to_string();
+        let diff = Diff::new_with_algorithm(base, new, DiffAlgorithm::Patience);
+        assert!(diff.lineas_eliminadas.is_empty());
+        assert_eq!(indices(&diff.lineas_agregadas), vec![1, 2]);
+    }
+
+    #[test]
+    fn test05_diff_de_archivos_grandes_usa_memoria_lineal() {
+        let base = (0..50_000)
+            .map(|i| format!("linea {}", i))
+            .collect::<Vec<String>>()
+            .join("\n");
+        let new = base.replace("linea 25000\n", "linea cambiada\n");
+        let diff = Diff::new(base, new);
+        assert_eq!(
+            diff.lineas_eliminadas,
+            vec![(25000, "linea 25000".to_string())]
+        );
+        assert_eq!(
+            diff.lineas_agregadas,
+            vec![(25000, "linea cambiada".to_string())]
+        );
+    }
+
+    #[test]
+    fn test06_las_eliminaciones_quedan_junto_a_los_agregados() {
+        let base = "hola\n\n\n\nlinea de base\n\n\n\nchau\n".to_string();
+        let new = "hola\n\n\n\nlinea de origin\n\n\nlinea agregada\nchau\n".to_string();
+        let diff = Diff::new(base, new);
+        assert_eq!(indices(&diff.lineas_eliminadas), vec![4, 7]);
+        assert_eq!(indices(&diff.lineas_agregadas), vec![4, 7]);
+    }
+
+    #[test]
+    fn test07_hunks_con_contexto_separan_cambios_lejanos() {
+        let old = (1..=20).map(|i| format!("{}\n", i)).collect::<String>();
+        let new = (1..=20)
+            .map(|i| match i {
+                2 => "dos\n".to_string(),
+                18 => "dieciocho\n".to_string(),
+                _ => format!("{}\n", i),
+            })
+            .collect::<String>();
+        let hunks = unified_hunks(&old, &new, 3, LineCompare::default());
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].header(), "@@ -1,5 +1,5 @@");
+        assert_eq!(hunks[1].header(), "@@ -15,6 +15,6 @@");
+        assert_eq!(hunks[0].lineas[1], ('-', "2".to_string()));
+        assert_eq!(hunks[0].lineas[2], ('+', "dos".to_string()));
+        let hunks = unified_hunks(&old, &new, 10, LineCompare::default());
+        assert_eq!(hunks.len(), 1);
+    }
+
+    #[test]
+    fn test08_hunk_de_archivo_nuevo_y_sin_newline_final() {
+        let hunks = unified_hunks("", "hola\nchau", 3, LineCompare::default());
+        assert_eq!(hunks[0].header(), "@@ -0,0 +1,2 @@");
+        assert_eq!(
+            hunks[0].lineas,
+            vec![
+                ('+', "hola".to_string()),
+                ('+', "chau".to_string()),
+                ('\\', " No newline at end of file".to_string())
+            ]
+        );
+        let hunks = unified_hunks("hola", "hola\n", 3, LineCompare::default());
+        assert_eq!(hunks[0].header(), "@@ -1 +1 @@");
+        assert_eq!(hunks[0].lineas.len(), 3);
+    }
+
+    #[test]
+    fn test09_hunks_ignorando_espacios_y_lineas_en_blanco() {
+        let old = "fn main() {\n    uno();\n}\n";
+        let new = "fn  main() {\n\tuno();\n\n}\n";
+        let mut comparacion = LineCompare {
+            ignore_space_change: true,
+            ..LineCompare::default()
+        };
+        let hunks = unified_hunks(old, new, 3, comparacion);
+        assert_eq!(hunks[0].lineas[2], ('+', "".to_string()));
+        assert_eq!(hunks[0].lineas.len(), 4);
+        comparacion.ignore_blank_lines = true;
+        assert!(unified_hunks(old, new, 3, comparacion).is_empty());
+        let comparacion = LineCompare {
+            ignore_all_space: true,
+            ..LineCompare::default()
+        };
+        assert!(unified_hunks("a b\n", "ab\n", 3, comparacion).is_empty());
+        assert_eq!(
+            unified_hunks("a b\n", "ab\n", 3, LineCompare::default()).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test10_similitud_y_deteccion_de_renames() {
+        let original = "uno\ndos\ntres\ncuatro\n";
+        assert_eq!(similarity(original, original), 100);
+        assert_eq!(similarity(original, "uno\ndos\ntres\ncinco\n"), 75);
+        assert_eq!(similarity(original, ""), 0);
+        assert_eq!(similarity("", ""), 0);
+        assert!(detect_renames(&[""], &[""], 0, false).is_empty());
+
+        let origenes = [original, "a\nb\n"];
+        let destinos = [
+            "x\ny\n",
+            "uno\ndos\ntres\ncinco\n",
+            "uno\ndos\ntres\ncuatro\n",
+        ];
+        assert_eq!(
+            detect_renames(&origenes, &destinos, RENAME_THRESHOLD, false),
+            vec![(0, 2, 100)]
+        );
+        assert_eq!(
+            detect_renames(&origenes, &destinos, RENAME_THRESHOLD, true),
+            vec![(0, 2, 100), (0, 1, 75)]
+        );
+        assert!(detect_renames(&origenes, &destinos, 90, true).len() == 1);
+    }
+
+    #[test]
+    fn test11_diff_combinado_marca_de_que_padre_viene_cada_cambio() {
+        let base = "uno\ndos\ntres\n";
+        let padre1 = "uno\nDOS\ntres\n";
+        let padre2 = "uno\ndos\ntres\ncuatro\n";
+        let resultado = "uno\nDOS\ntres\ncuatro\nmerge\n";
+
+        let hunks = combined_hunks(&[padre1, padre2], resultado, 1);
+
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].header(), "@@@ -1,3 -1,4 +1,5 @@@");
+        let lineas = hunks[0]
+            .lineas
+            .iter()
+            .map(|(columnas, linea)| format!("{}{}", columnas, linea))
+            .collect::<Vec<String>>();
+        assert_eq!(
+            lineas,
+            vec!["  uno", " -dos", " +DOS", "  tres", "+ cuatro", "++merge"]
+        );
+        assert!(combined_hunks(&[base, base], base, 3).is_empty());
+    }
+
+    #[test]
+    fn test12_matching_lines_relaciona_las_lineas_sin_cambios() {
+        let old = "uno\ndos\ntres\ncuatro\n";
+        let new = "cero\nuno\ntres\nTRES\ncuatro\n";
+
+        assert_eq!(
+            matching_lines(old, new),
+            vec![None, Some(0), Some(2), None, Some(3)]
+        );
+        assert_eq!(matching_lines("", "a\n"), vec![None]);
+        assert_eq!(matching_lines("a\nb\n", "a\nb\n"), vec![Some(0), Some(1)]);
+    }
 }
@@ -1,6 +1,8 @@
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 
-use crate::commands::command_utils::flate2compress;
+use crate::commands::command_utils::{flate2compress, smudge_file_data, ATTRIBUTES_FILES};
+use crate::config::{Config, ConfigLevel};
 use crate::gitr_errors::GitrError;
 use crate::objects::pull_request::PullRequest;
 use crate::{file_manager, logger};
@@ -139,8 +141,11 @@ pub fn add_new_files_from_merge(
                     Err(_) => return Err(GitrError::FileWriteError(parent.display().to_string())),
                 };
             };
-            let raw_data = read_file_data_from_blob_hash(hash.to_string(), cliente.clone())?;
-            write_file(path.to_string(), raw_data)?;
+            let raw_data = read_blob_text(hash.to_string(), cliente.clone())?;
+            write_file(
+                path.to_string(),
+                smudge_file_data(path, raw_data, cliente.clone())?,
+            )?;
         }
     }
     Ok(())
@@ -225,6 +230,33 @@ pub fn read_file_data_from_blob_hash(hash: String, cliente: String) -> Result<St
     Ok(raw_data.to_string())
 }
 
+/// Lee un blob como texto. read_object guarda cada byte como un char, asi que hay que
+/// juntar los bytes de nuevo y decodificarlos como UTF-8, igual que los archivos del
+/// working tree.
+pub fn read_blob_text(hash: String, cliente: String) -> Result<String, GitrError> {
+    Ok(blob_data_to_utf8(&read_file_data_from_blob_hash(hash, cliente)?))
+}
+
+/// Devuelve el tipo de un objeto ("blob", "tree", "commit" o "tag") leyendo solo su header
+pub fn read_object_type(hash: &String, cliente: String) -> Result<String, GitrError> {
+    let path = parse_object_hash(hash, get_current_repo(cliente)?, true)?;
+    let mut tipo = String::new();
+    for byte in deflate_file(path)? {
+        match byte {
+            Ok(b' ') => return Ok(tipo),
+            Ok(byte) => tipo.push(byte as char),
+            Err(_) => return Err(GitrError::CompressionError),
+        }
+    }
+    Err(GitrError::ObjectNotFound(hash.clone()))
+}
+
+/// Decodifica como UTF-8 los datos de un objeto leido con read_object
+pub fn blob_data_to_utf8(data: &str) -> String {
+    let bytes = data.chars().map(|c| c as u8).collect::<Vec<u8>>();
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
 // auxiliar function of read_object().
 fn get_object_data_with_bytes(bytes: Bytes<ZlibDecoder<File>>) -> Result<Vec<u8>, GitrError> {
     let mut object_data: Vec<u8> = Vec::new();
@@ -297,13 +329,25 @@ pub fn write_compressed_data(path: &str, data: &[u8]) -> Result<(), GitrError> {
     }
 }
 
+/// returns remote.origin.url from config, or the legacy gitr/remote file if it is not set
 pub fn get_remote(cliente: String) -> Result<String, GitrError> {
+    if let Some(url) = Config::load(&cliente)?.get("remote.origin.url") {
+        return Ok(url);
+    }
     let repo = get_current_repo(cliente)?;
     let path = repo + "/gitr/" + "remote";
     let remote = read_file(path)?;
     Ok(remote)
 }
 
+/// saves the remote as remote.origin.url in the repo config
+pub fn set_remote(url: &str, cliente: String) -> Result<(), GitrError> {
+    let path = ConfigLevel::Local.path(&cliente)?;
+    let mut config = Config::read(&path)?;
+    config.set("remote.origin.url", url)?;
+    config.write(&path)
+}
+
 ///receive compressed raw data from a file with his hash and write it in the objects folder
 pub fn write_object(data: Vec<u8>, hashed_name: String, cliente: String) -> Result<(), GitrError> {
     let log_msg = format!("writing object {}", hashed_name);
@@ -421,7 +465,7 @@ pub fn add_to_index(path: &String, hash: &String, cliente: String) -> Result<(),
         index = read_index(cliente.clone())?;
         let mut overwrited = false;
         for line in index.clone().lines() {
-            let attributes = line.split(' ').collect::<Vec<&str>>();
+            let attributes = line.splitn(4, ' ').collect::<Vec<&str>>();
 
             if attributes[3] == path {
                 let log_msg = format!("adding {} to index", path);
@@ -648,14 +692,14 @@ pub fn create_tree(path: String, hash: String, cliente: String) -> Result<(), Gi
             return Ok(());
         }
     };
-    for entry in raw_data.split('\n') {
+    for entry in attributes_first(raw_data) {
         let object = entry.split(' ').collect::<Vec<&str>>()[0];
         if object == "100644" {
             let path_completo = path.clone() + "/" + &parse_blob_path(entry.to_string().clone());
             let hash = parse_blob_hash(entry.to_string().clone());
             create_blob(path_completo, hash, cliente.clone())?;
         } else {
-            let _new_path_hash = entry.split(' ').collect::<Vec<&str>>()[1];
+            let _new_path_hash = entry.splitn(2, ' ').collect::<Vec<&str>>()[1];
             let new_path = _new_path_hash.split('\0').collect::<Vec<&str>>()[0];
             let hash = _new_path_hash.split('\0').collect::<Vec<&str>>()[1];
             create_tree(
@@ -667,15 +711,26 @@ pub fn create_tree(path: String, hash: String, cliente: String) -> Result<(), Gi
     }
     Ok(())
 }
+// the gitrattributes of each directory is written before the files it converts
+fn attributes_first(raw_data: &str) -> Vec<&str> {
+    let mut entries = raw_data.split('\n').collect::<Vec<&str>>();
+    entries.sort_by_key(|entry| {
+        !ATTRIBUTES_FILES
+            .iter()
+            .any(|nombre| entry.contains(&format!(" {}\0", nombre)))
+    });
+    entries
+}
+
 //auxiliar function for create_tree
 fn parse_blob_hash(blob_entry: String) -> String {
-    let _new_path_hash = blob_entry.split(' ').collect::<Vec<&str>>()[1];
+    let _new_path_hash = blob_entry.splitn(2, ' ').collect::<Vec<&str>>()[1];
     let hash = _new_path_hash.split('\0').collect::<Vec<&str>>()[1];
     hash.to_string()
 }
 //auxiliar function for create_tree
 fn parse_blob_path(blob_entry: String) -> String {
-    let _new_path_hash = blob_entry.split(' ').collect::<Vec<&str>>()[1];
+    let _new_path_hash = blob_entry.splitn(2, ' ').collect::<Vec<&str>>()[1];
     let new_path = _new_path_hash.split('\0').collect::<Vec<&str>>()[0];
     new_path.to_string()
 }
@@ -689,7 +744,8 @@ pub fn create_blob(path: String, hash: String, cliente: String) -> Result<(), Gi
     )?;
     let new_blob_only_data = new_blob.split('\0').collect::<Vec<&str>>()[1];
     add_to_index(&path, &hash, cliente.clone())?;
-    write_file(path.to_string(), new_blob_only_data.to_string())?;
+    let data = smudge_file_data(&path, blob_data_to_utf8(new_blob_only_data), cliente)?;
+    write_file(path.to_string(), data)?;
     Ok(())
 }
 
@@ -710,10 +766,10 @@ pub fn update_working_directory(commit: String, cliente: String) -> Result<(), G
         }
     };
     let repo = get_current_repo(cliente.clone())? + "/";
-    for entry in raw_data.split('\n') {
+    for entry in attributes_first(raw_data) {
         let object: &str = entry.split(' ').collect::<Vec<&str>>()[0];
         if object == "40000" {
-            let _new_path_hash = entry.split(' ').collect::<Vec<&str>>()[1];
+            let _new_path_hash = entry.splitn(2, ' ').collect::<Vec<&str>>()[1];
             let new_path = repo.clone() + _new_path_hash.split('\0').collect::<Vec<&str>>()[0];
             let hash = _new_path_hash.split('\0').collect::<Vec<&str>>()[1];
             create_tree(new_path.to_string(), hash.to_string(), cliente.clone())?;
@@ -834,6 +890,25 @@ pub fn get_commit_author(commit: String, cliente: String) -> Result<String, Gitr
     Ok(author.to_string())
 }
 
+//receives a commit and returns its commiter timestamp
+pub fn get_commit_timestamp(commit: String, cliente: String) -> Result<i64, GitrError> {
+    let add_gitr = !cliente.contains('/');
+    let commit = read_object(
+        &commit,
+        file_manager::get_current_repo(cliente.clone())?,
+        add_gitr,
+    )?;
+    let timestamp = commit
+        .split('\n')
+        .find(|line| line.starts_with("committer "))
+        .and_then(|line| line.split(' ').rev().nth(1))
+        .unwrap_or_default();
+    match timestamp.parse::<i64>() {
+        Ok(timestamp) => Ok(timestamp),
+        Err(_) => Err(GitrError::TimeError),
+    }
+}
+
 //receives a commit and returns its date
 pub fn get_commit_date(commit: String, cliente: String) -> Result<String, GitrError> {
     let commit = read_object(
@@ -853,7 +928,12 @@ pub fn get_commit_date(commit: String, cliente: String) -> Result<String, GitrEr
         Ok(timestamp) => timestamp,
         Err(_) => return Err(GitrError::TimeError),
     };
-    let dt = Utc.timestamp_opt(timestamp_parsed, 0);
+    format_timestamp(timestamp_parsed)
+}
+
+//receives a timestamp and returns the date as shown by log
+pub fn format_timestamp(timestamp: i64) -> Result<String, GitrError> {
+    let dt = Utc.timestamp_opt(timestamp, 0);
     let dt = match dt.single() {
         Some(dt) => dt,
         None => return Err(GitrError::TimeError),
@@ -922,7 +1002,7 @@ pub fn get_refs_ids(carpeta: &str, cliente: String) -> Result<Vec<String>, GitrE
 //receives a quantity and returns that number of commits from logs
 pub fn commit_log(quantity: String, cliente: String) -> Result<String, GitrError> {
     let mut res: String = "".to_owned();
-    let mut current_commit = get_current_commit(cliente.clone())?;
+    let current_commit = get_current_commit(cliente.clone())?;
     let limit = match quantity.parse::<i32>() {
         Ok(quantity) => quantity,
         Err(_) => {
@@ -933,31 +1013,64 @@ pub fn commit_log(quantity: String, cliente: String) -> Result<String, GitrError
         }
     };
     let mut counter = 0;
-    loop {
+    for commit in rev_list(vec![current_commit], cliente.clone())? {
         counter += 1;
-        let parents = get_parent_commit(current_commit.clone(), cliente.clone())?;
-        if parents.len() == 2 {
-            let parent_1 = parents[0].split_at(7).0;
-            let parent_2 = parents[1].split_at(7).0;
-            let format_merge = format!("Merge: {} {}\n", parent_1, parent_2);
-            res.push_str(&format_merge);
-        }
-        let format_commit = format!("commit: {}\n", current_commit);
-        res.push_str(&format_commit);
-        let date = get_commit_date(current_commit.clone(), cliente.clone())?;
-        let author = get_commit_author(current_commit.clone(), cliente.clone())?;
-        let message = get_commit_message(current_commit.clone(), cliente.clone())?;
-        res.push_str(&format!("Author: {}\n", author));
-        res.push_str(&format!("Date: {}\n", date));
-        res.push_str(&format!("\t{}\n\n", message));
-        if parents[0] == "None" || counter == limit {
+        res.push_str(&format_commit_log_entry(commit, cliente.clone())?);
+        if counter == limit {
             break;
         }
-        current_commit = parents[0].clone();
     }
     Ok(res.to_string())
 }
 
+// devuelve todos los commits alcanzables desde los recibidos, del mas nuevo al mas viejo.
+// A igual fecha respeta el orden en que se encontraron
+pub fn rev_list(commits: Vec<String>, cliente: String) -> Result<Vec<String>, GitrError> {
+    let mut res = Vec::new();
+    let mut visitados = HashSet::new();
+    let mut pendientes = BinaryHeap::new();
+    let mut orden = 0;
+    for commit in commits {
+        if visitados.insert(commit.clone()) {
+            let timestamp = get_commit_timestamp(commit.clone(), cliente.clone())?;
+            pendientes.push((timestamp, Reverse(orden), commit));
+            orden += 1;
+        }
+    }
+    while let Some((_, _, commit)) = pendientes.pop() {
+        for parent in get_parent_commit(commit.clone(), cliente.clone())? {
+            if parent != "None" && visitados.insert(parent.clone()) {
+                let timestamp = get_commit_timestamp(parent.clone(), cliente.clone())?;
+                pendientes.push((timestamp, Reverse(orden), parent));
+                orden += 1;
+            }
+        }
+        res.push(commit);
+    }
+    Ok(res)
+}
+
+// formatea un commit como lo muestra log (con la linea Merge si tiene dos padres)
+pub fn format_commit_log_entry(commit: String, cliente: String) -> Result<String, GitrError> {
+    let mut res = String::new();
+    let parents = get_parent_commit(commit.clone(), cliente.clone())?;
+    if parents.len() == 2 {
+        let parent_1 = parents[0].split_at(7).0;
+        let parent_2 = parents[1].split_at(7).0;
+        let format_merge = format!("Merge: {} {}\n", parent_1, parent_2);
+        res.push_str(&format_merge);
+    }
+    let format_commit = format!("commit: {}\n", commit);
+    res.push_str(&format_commit);
+    let date = get_commit_date(commit.clone(), cliente.clone())?;
+    let author = get_commit_author(commit.clone(), cliente.clone())?;
+    let message = get_commit_message(commit.clone(), cliente.clone())?;
+    res.push_str(&format!("Author: {}\n", author));
+    res.push_str(&format!("Date: {}\n", date));
+    res.push_str(&format!("\t{}\n\n", message));
+    Ok(res)
+}
+
 //returns all repos
 pub fn get_repos(cliente: String) -> Vec<String> {
     let mut repos: Vec<String> = Vec::new();
@@ -993,6 +1106,16 @@ pub fn remove_file(path: String) -> Result<(), GitrError> {
     }
 }
 
+//removes a directory and all its content
+pub fn remove_directory(path: String) -> Result<(), GitrError> {
+    let log_msg = format!("removing dir: {}", path);
+    logger::log_file_operation(log_msg)?;
+    match fs::remove_dir_all(path.clone()) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(GitrError::FileDeleteError(path)),
+    }
+}
+
 pub fn get_all_objects_hashes(cliente: String) -> Result<Vec<String>, GitrError> {
     let mut objects: Vec<String> = Vec::new();
     let repo = get_current_repo(cliente.clone())?;
@@ -1128,4 +1251,4 @@ pub fn contar_archivos_y_directorios(ruta: &str) -> Result<usize, GitrError> {
 pub fn pull_request_exist(path: &str) -> bool {
     let data = read_file(path.to_string());
     data.is_ok()
-}
\ No newline at end of file
+}
@@ -1,5 +1,7 @@
 use std::fmt;
 
+use crate::i18n::tr;
+
 #[derive(Debug, PartialEq)]
 pub enum GitrError {
     InputError,
@@ -32,72 +34,184 @@ pub enum GitrError {
     PullRequestReadError,
     PushError(String),
     BranchNotFound,
+    RebaseInProgress,
+    NoRebaseInProgress,
+    UnresolvedConflicts(String),
+    BisectInProgress,
+    NoBisectInProgress,
+    BisectRunError(String),
+    InvalidPatternError(String),
+    NoTagToDescribe(String),
+    BundleError(String),
+    PatchError(String),
+    ConfigError(String),
+    AliasError(String),
+    HookError(String, String),
+}
+
+impl GitrError {
+    /// Codigo de salida del proceso para cada error, para que los scripts puedan distinguirlos.
+    /// 1 queda para errores que no son de gitr y 129 es el de uso, como en git.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::InputError => 2,
+            Self::FileCreationError(_) => 3,
+            Self::FileWriteError(_) => 4,
+            Self::FileDeletionError(_) => 5,
+            Self::ObjectNotFound(_) => 6,
+            Self::FileReadError(_) => 7,
+            Self::FileDeleteError(_) => 8,
+            Self::NoCommitExisting(_) => 9,
+            Self::NoHead => 10,
+            Self::AlreadyInitialized => 11,
+            Self::NoRepository => 12,
+            Self::InvalidArgumentError(_, _) => 129,
+            Self::LogError => 13,
+            Self::CompressionError => 14,
+            Self::TimeError => 15,
+            Self::InvalidTreeError => 16,
+            Self::InvalidCommitError => 17,
+            Self::InvalidTagError => 18,
+            Self::ConnectionError => 19,
+            Self::SocketError(_, _) => 20,
+            Self::PackFileError(_, _) => 21,
+            Self::BranchNonExistsError(_) => 22,
+            Self::BranchAlreadyExistsError(_) => 23,
+            Self::DeleteCurrentBranchError(_) => 24,
+            Self::TagAlreadyExistsError(_) => 25,
+            Self::TagNonExistsError(_) => 26,
+            Self::PullRequestWriteError => 27,
+            Self::PullRequestReadError => 28,
+            Self::PushError(_) => 29,
+            Self::BranchNotFound => 30,
+            Self::RebaseInProgress => 31,
+            Self::NoRebaseInProgress => 32,
+            Self::UnresolvedConflicts(_) => 33,
+            Self::BisectInProgress => 34,
+            Self::NoBisectInProgress => 35,
+            Self::BisectRunError(_) => 36,
+            Self::InvalidPatternError(_) => 37,
+            Self::NoTagToDescribe(_) => 38,
+            Self::BundleError(_) => 39,
+            Self::PatchError(_) => 40,
+            Self::ConfigError(_) => 41,
+            Self::AliasError(_) => 42,
+            Self::HookError(_, _) => 43,
+        }
+    }
 }
 
 impl fmt::Display for GitrError {
+    // los mensajes salen del catalogo del idioma actual, ver i18n
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Self::InputError => write!(f, "Error en la entrada de comandos"),
-            Self::BranchNonExistsError(branch) => {
-                write!(f, "ERROR: branch '{}' no encontrada.", branch)
-            }
-            Self::FileDeletionError(fun) => {
-                write!(f, "ERROR: En la funcion {} falló una eliminación", fun)
+        let mensaje = match self {
+            Self::InputError => tr("error.input", &[]),
+            Self::FileCreationError(path) => tr("error.file_creation", &[path]),
+            Self::FileWriteError(path) => tr("error.file_write", &[path]),
+            Self::FileDeletionError(fun) => tr("error.file_deletion", &[fun]),
+            Self::ObjectNotFound(obj) => tr("error.object_not_found", &[obj]),
+            Self::FileReadError(path) => tr("error.file_read", &[path]),
+            Self::FileDeleteError(path) => tr("error.file_delete", &[path]),
+            Self::NoCommitExisting(brch) => tr("error.no_commit_existing", &[brch]),
+            Self::NoHead => tr("error.no_head", &[]),
+            Self::AlreadyInitialized => tr("error.already_initialized", &[]),
+            Self::NoRepository => tr("error.no_repository", &[]),
+            Self::InvalidArgumentError(got, usage) => tr("error.invalid_argument", &[got, usage]),
+            Self::LogError => tr("error.log", &[]),
+            Self::CompressionError => tr("error.compression", &[]),
+            Self::TimeError => tr("error.time", &[]),
+            Self::InvalidTreeError => tr("error.invalid_tree", &[]),
+            Self::InvalidCommitError => tr("error.invalid_commit", &[]),
+            Self::InvalidTagError => tr("error.invalid_tag", &[]),
+            Self::ConnectionError => tr("error.connection", &[]),
+            Self::SocketError(origin_function, info) => {
+                tr("error.socket", &[origin_function, info])
             }
-            Self::FileCreationError(path) => {
-                write!(f, "ERROR: No se pudo crear el archivo {}", path)
+            Self::PackFileError(origin_function, info) => {
+                tr("error.pack_file", &[origin_function, info])
             }
-            Self::FileWriteError(path) => {
-                write!(f, "ERROR: No se pudo escribir el archivo {}", path)
-            }
-            Self::FileDeleteError(path) => {
-                write!(f, "ERROR: No se pudo borrar el archivo {}", path)
-            }
-            Self::ObjectNotFound(obj) => write!(f, "ERROR: No se encontro el objeto {}", obj),
-            Self::FileReadError(path) => write!(f, "ERROR: No se pudo leer el archivo {}", path),
-            Self::BranchAlreadyExistsError(branch) => {
-                write!(f, "error: la branch '{}' ya existe.", branch)
-            }
-            Self::NoHead => write!(f, "ERROR: No se encontro HEAD"),
-            Self::AlreadyInitialized => write!(f, "ERROR: El repositorio ya esta inicializado"),
-            Self::NoRepository => write!(f, "ERROR: No se encontro el repositorio"),
-            Self::NoCommitExisting(brch) => {
-                write!(f, "ERROR: nombre de objeto no valido: '{}'", brch)
-            }
-            Self::LogError => write!(f, "ERROR: No se pudo escribir en el log"),
-            Self::CompressionError => write!(f, "ERROR: No se pudo comprimir el archivo"),
-            Self::InvalidArgumentError(got, usage) => write!(
-                f,
-                "ERROR: Argumentos invalidos.\n    Recibi: {}\n    Uso: {}\n",
-                got, usage
-            ),
-            Self::TimeError => write!(f, "ERROR: No se pudo obtener el tiempo actual"),
-            Self::InvalidTreeError => write!(f, "ERROR: El arbol no es valido"),
-            Self::InvalidCommitError => write!(f, "ERROR: El commit no es valido"),
-            Self::ConnectionError => write!(f, "ERROR: No se pudo conectar al servidor"),
-            Self::InvalidTagError => write!(f, "ERROR: La tag no es valida"),
-            Self::SocketError(origin_function, info) => write!(
-                f,
-                "SocketError en la funcion {}. Info: {}",
-                origin_function, info
-            ),
-            Self::PackFileError(origin_function, info) => write!(
-                f,
-                "PackFileError en la funcion {}. Info: {}",
-                origin_function, info
-            ),
-            Self::TagAlreadyExistsError(tag) => write!(f, "ERROR: tag '{}' ya existe", tag),
-            Self::TagNonExistsError(tag) => write!(f, "ERROR: tag '{}' no encontrado", tag),
-            Self::DeleteCurrentBranchError(branch) => write!(
-                f,
-                "ERROR: No se puede borrar branch '{}': HEAD apunta ahi",
-                branch
-            ),
-            Self::PullRequestWriteError => write!(f, "ERROR: No se pudo escribir el PR en el server"),
-            Self::PullRequestReadError => write!(f, "ERROR: No se pudo leer el PR del server"),
-            Self::PushError(info) => write!(f, "ERROR: No se pudo hacer push. Info: {}", info),
-            Self::BranchNotFound => write!(f, "ERROR: No se encontro la branch"),
+            Self::BranchNonExistsError(branch) => tr("error.branch_non_exists", &[branch]),
+            Self::BranchAlreadyExistsError(branch) => tr("error.branch_already_exists", &[branch]),
+            Self::DeleteCurrentBranchError(branch) => tr("error.delete_current_branch", &[branch]),
+            Self::TagAlreadyExistsError(tag) => tr("error.tag_already_exists", &[tag]),
+            Self::TagNonExistsError(tag) => tr("error.tag_non_exists", &[tag]),
+            Self::PullRequestWriteError => tr("error.pull_request_write", &[]),
+            Self::PullRequestReadError => tr("error.pull_request_read", &[]),
+            Self::PushError(info) => tr("error.push", &[info]),
+            Self::BranchNotFound => tr("error.branch_not_found", &[]),
+            Self::RebaseInProgress => tr("error.rebase_in_progress", &[]),
+            Self::NoRebaseInProgress => tr("error.no_rebase_in_progress", &[]),
+            Self::UnresolvedConflicts(files) => tr("error.unresolved_conflicts", &[files]),
+            Self::BisectInProgress => tr("error.bisect_in_progress", &[]),
+            Self::NoBisectInProgress => tr("error.no_bisect_in_progress", &[]),
+            Self::BisectRunError(motivo) => tr("error.bisect_run", &[motivo]),
+            Self::InvalidPatternError(patron) => tr("error.invalid_pattern", &[patron]),
+            Self::NoTagToDescribe(commit) => tr("error.no_tag_to_describe", &[commit]),
+            Self::BundleError(motivo) => tr("error.bundle", &[motivo]),
+            Self::PatchError(motivo) => tr("error.patch", &[motivo]),
+            Self::ConfigError(motivo) => tr("error.config", &[motivo]),
+            Self::AliasError(motivo) => tr("error.alias", &[motivo]),
+            Self::HookError(hook, motivo) => tr("error.hook", &[hook, motivo]),
+        };
+        write!(f, "{}", mensaje)
+    }
+}
 
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test01_codigos_de_salida_distintos() {
+        let errores = [
+            GitrError::InputError,
+            GitrError::FileCreationError(String::new()),
+            GitrError::FileWriteError(String::new()),
+            GitrError::FileDeletionError(String::new()),
+            GitrError::ObjectNotFound(String::new()),
+            GitrError::FileReadError(String::new()),
+            GitrError::FileDeleteError(String::new()),
+            GitrError::NoCommitExisting(String::new()),
+            GitrError::NoHead,
+            GitrError::AlreadyInitialized,
+            GitrError::NoRepository,
+            GitrError::InvalidArgumentError(String::new(), String::new()),
+            GitrError::LogError,
+            GitrError::CompressionError,
+            GitrError::TimeError,
+            GitrError::InvalidTreeError,
+            GitrError::InvalidCommitError,
+            GitrError::InvalidTagError,
+            GitrError::ConnectionError,
+            GitrError::SocketError(String::new(), String::new()),
+            GitrError::PackFileError(String::new(), String::new()),
+            GitrError::BranchNonExistsError(String::new()),
+            GitrError::BranchAlreadyExistsError(String::new()),
+            GitrError::DeleteCurrentBranchError(String::new()),
+            GitrError::TagAlreadyExistsError(String::new()),
+            GitrError::TagNonExistsError(String::new()),
+            GitrError::PullRequestWriteError,
+            GitrError::PullRequestReadError,
+            GitrError::PushError(String::new()),
+            GitrError::BranchNotFound,
+            GitrError::RebaseInProgress,
+            GitrError::NoRebaseInProgress,
+            GitrError::UnresolvedConflicts(String::new()),
+            GitrError::BisectInProgress,
+            GitrError::NoBisectInProgress,
+            GitrError::BisectRunError(String::new()),
+            GitrError::InvalidPatternError(String::new()),
+            GitrError::NoTagToDescribe(String::new()),
+            GitrError::BundleError(String::new()),
+            GitrError::PatchError(String::new()),
+            GitrError::ConfigError(String::new()),
+            GitrError::AliasError(String::new()),
+            GitrError::HookError(String::new(), String::new()),
+        ];
+        let mut codigos: Vec<i32> = errores.iter().map(|e| e.exit_code()).collect();
+        assert!(codigos.iter().all(|c| *c > 1 && *c < 256));
+        codigos.sort();
+        codigos.dedup();
+        assert_eq!(codigos.len(), errores.len());
     }
 }
@@ -9,12 +9,13 @@ use crate::commands::command_utils::_create_pr;
 use crate::commands::commands_fn::{self};
 use crate::file_manager;
 use crate::gitr_errors::GitrError;
+use crate::i18n::{locale, tr, translate_ui};
 use crate::objects::pull_request::PullRequest;
 
 fn get_commits(cliente: String) -> String {
     let mut commits = match file_manager::commit_log("-1".to_string(), cliente) {
         Ok(commits) => commits,
-        Err(_) => return "No hay commits para mostrar".to_string(),
+        Err(_) => return tr("gui.no_commits", &[]),
     };
     commits = commits.trim_end().to_string();
 
@@ -95,7 +96,7 @@ fn update_branches(branch_selector: &ComboBoxText, cliente: String) {
     let branches = match file_manager::get_branches(cliente.clone()) {
         Ok(branches) => branches,
         Err(e) => {
-            println!("Error al obtener branches: {:?}", e);
+            println!("{}", tr("gui.branches_error", &[&format!("{:?}", e)]));
             return;
         }
     };
@@ -109,8 +110,8 @@ fn existe_config(cliente: String) -> bool {
 }
 
 fn build_ui(application: &gtk::Application, cliente: String) -> Option<String> {
-    let glade_src = include_str!("gui_test.glade");
-    let builder = Builder::from_string(glade_src);
+    let glade_src = translate_ui(locale(), include_str!("gui_test.glade"));
+    let builder = Builder::from_string(&glade_src);
 
     //====Builders para componentes====
     let window: Window = builder.object("main_window")?;
@@ -216,13 +217,12 @@ fn build_ui(application: &gtk::Application, cliente: String) -> Option<String> {
     let login_dialog_clone = login_dialog.clone();
     let cliente_clon = cliente.clone();
 
-    login_dialog_top_label
-        .set_text(format!("Hola, {}. Por favor, ingrese su mail", cliente_clon.clone()).as_str());
+    login_dialog_top_label.set_text(tr("gui.login", &[&cliente_clon]).as_str());
 
     login_button_clone.connect_clicked(move |_| {
         let mail = mail_entry.text().to_string();
         if !email_valido(mail.clone()) {
-            login_dialog_top_label.set_text("Mail inválido. Con formato nombre@xxxxxx.yyy");
+            login_dialog_top_label.set_text(&tr("gui.invalid_mail", &[]));
             return;
         }
         let config_file_data = format!(
@@ -271,17 +271,15 @@ fn build_ui(application: &gtk::Application, cliente: String) -> Option<String> {
             Ok(_) => (),
             Err(e) => {
                 if e == GitrError::FileReadError(cliente_.clone() + "/.head_repo") {
-                    remote_error_label_clone
-                        .set_text("No hay un repositorio asociado, busque o cree uno.");
+                    remote_error_label_clone.set_text(&tr("gui.no_repository", &[]));
                 } else {
                     remote_error_label_clone
-                        .set_text(format!("Error al hacer add: {:?}", e).as_str());
+                        .set_text(tr("gui.add_error", &[&format!("{:?}", e)]).as_str());
                 }
                 remote_error_dialog_clone.show();
             }
         };
-        let message = format!("\"{}\"", commit_message.text());
-        let cm_msg = vec!["-m".to_string(), message];
+        let cm_msg = vec!["-m".to_string(), commit_message.text().to_string()];
         let parent = match file_manager::read_file("parent".to_string()){
             Ok(parent) => parent,
             Err(_) => "None".to_string(),
@@ -293,7 +291,7 @@ fn build_ui(application: &gtk::Application, cliente: String) -> Option<String> {
                 }
             },
             Err(e) => {
-                println!("Error al hacer commit: {:?}", e);
+                println!("{}", tr("gui.commit_error", &[&format!("{:?}", e)]));
                 return;
             }
         };
@@ -318,7 +316,7 @@ fn build_ui(application: &gtk::Application, cliente: String) -> Option<String> {
         match commands_fn::checkout(flags, cliente_.clone()) {
             Ok(_) => (),
             Err(e) => {
-                println!("Error al cambiar de branch: {:?}", e);
+                println!("{}", tr("gui.checkout_error", &[&format!("{:?}", e)]));
                 return;
             }
         }
@@ -365,7 +363,7 @@ fn build_ui(application: &gtk::Application, cliente: String) -> Option<String> {
         ) {
             Ok(_) => (),
             Err(e) => {
-                println!("Error al clonar: {}", e);
+                println!("{}", tr("gui.clone_error", &[&e]));
             }
         }
     });
@@ -384,7 +382,7 @@ fn build_ui(application: &gtk::Application, cliente: String) -> Option<String> {
         match commands_fn::push(flags, cliente_.clone()) {
             Ok(_) => (),
             Err(e) => {
-                println!("Error al hacer push: {:?}", e);
+                println!("{}", tr("gui.push_error", &[&format!("{:?}", e)]));
                 clone_error.show();
                 return;
             }
@@ -400,7 +398,7 @@ fn build_ui(application: &gtk::Application, cliente: String) -> Option<String> {
         match commands_fn::pull(flags, cliente_.clone()) {
             Ok(_) => (),
             Err(e) => {
-                println!("Error al hacer pull: {:?}", e);
+                println!("{}", tr("gui.pull_error", &[&format!("{:?}", e)]));
                 clone_error.show();
                 return;
             }
@@ -413,7 +411,7 @@ fn build_ui(application: &gtk::Application, cliente: String) -> Option<String> {
     clone_fetch.connect_clicked(move |_| {
         let flags = vec![String::from("")];
         if commands_fn::fetch(flags, cliente_.clone()).is_err() {
-            println!("Error al hacer fetch");
+            println!("{}", tr("gui.fetch_error", &[]));
             clone_error.show();
             return;
         };
@@ -445,7 +443,7 @@ fn build_ui(application: &gtk::Application, cliente: String) -> Option<String> {
         let repo_name = init_repo_name_clone.text();
         init_dialog_clone.hide();
         if commands_fn::init(vec![repo_name.to_string()], cliente_.clone()).is_err() {
-            println!("Error al inicializar repo");
+            println!("{}", tr("gui.init_error", &[]));
             return;
         };
         repo_sel.set_current_folder(cliente_.clone() + "/" + repo_name.as_str());
@@ -470,11 +468,11 @@ fn build_ui(application: &gtk::Application, cliente: String) -> Option<String> {
                 if !hubo_conflict{
                     return;
                 }
-                remote_error_label_clone.set_text("Surgieron conflicts al hacer merge, por favor arreglarlos y commitear el resultado.");
+                remote_error_label_clone.set_text(&tr("gui.merge_conflicts", &[]));
                 remote_error_dialog_clone.show();
                 file_manager::write_file("parent".to_string(),parent).unwrap();           },
             Err(e) => {
-                println!("Error al hacer merge: {:?}",e);
+                println!("{}", tr("gui.merge_error", &[&format!("{:?}", e)]));
             },
         }
     });
@@ -499,8 +497,9 @@ fn build_ui(application: &gtk::Application, cliente: String) -> Option<String> {
             update_branches(&compare_branch_clone, cliente_clone.clone());
             pr_title_clone.set_text("");
             pr_descripcion_clone.set_text("");
-            pr_title_clone.set_placeholder_text(Some("Título"));
-            pr_descripcion_clone.set_placeholder_text(Some("Descripción"));
+            pr_title_clone.set_placeholder_text(Some(tr("gui.pr_title_placeholder", &[]).as_str()));
+            pr_descripcion_clone
+                .set_placeholder_text(Some(tr("gui.pr_description_placeholder", &[]).as_str()));
             creation_pr_clone.show();
 
         }
@@ -554,7 +553,7 @@ fn build_ui(application: &gtk::Application, cliente: String) -> Option<String> {
             let prs = match file_manager::get_pull_requests(dir){
                 Ok(prs) => prs,
                 Err(e) => {
-                    println!("Error al obtener pull requests: {:?}",e);
+                    println!("{}", tr("gui.pull_requests_error", &[&format!("{:?}", e)]));
                     clone_error.show();
                     return;
                 }
@@ -584,7 +583,7 @@ fn build_ui(application: &gtk::Application, cliente: String) -> Option<String> {
             let prs = match file_manager::get_pull_requests(dir){
                 Ok(prs) => prs,
                 Err(e) => {
-                    println!("Error al obtener pull requests: {:?}",e);
+                    println!("{}", tr("gui.pull_requests_error", &[&format!("{:?}", e)]));
                     clone_error.show();
                     return;
                 }
@@ -642,10 +641,16 @@ pub fn initialize_gui(cliente: String) {
 
 
 fn create_pull_request_row(pull_request: &PullRequest) -> ListBoxRow {
-    let id_label = Label::new(Some(&format!("ID: {}", pull_request.id)));
-    let title_label = Label::new(Some(&format!("Título: {}", pull_request.title)));
-    let description_label = Label::new(Some(&format!("Descripción: {}", pull_request.description)));
-    let branches = Label::new(Some(&format!("{}==>{}", pull_request.head, pull_request.base)));
+    let id_label = Label::new(Some(&tr("gui.pr_id", &[&pull_request.id])));
+    let title_label = Label::new(Some(&tr("gui.pr_title", &[&pull_request.title])));
+    let description_label = Label::new(Some(&tr(
+        "gui.pr_description",
+        &[&pull_request.description],
+    )));
+    let branches = Label::new(Some(&format!(
+        "{}==>{}",
+        pull_request.head, pull_request.base
+    )));
 
     let row_box = gtk::Box::new(Orientation::Vertical, 5);
     row_box.pack_start(&id_label, false, false, 0);
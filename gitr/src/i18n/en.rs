@@ -0,0 +1,211 @@
+// Catalogo en ingles. Cada clave tiene que estar tambien en es.rs
+pub const MENSAJES: &[(&str, &str)] = &[
+    ("error.input", "Command input error"),
+    ("error.file_creation", "ERROR: Could not create file {}"),
+    ("error.file_write", "ERROR: Could not write file {}"),
+    ("error.file_deletion", "ERROR: A deletion failed in function {}"),
+    ("error.object_not_found", "ERROR: Object {} not found"),
+    ("error.file_read", "ERROR: Could not read file {}"),
+    ("error.file_delete", "ERROR: Could not delete file {}"),
+    ("error.no_commit_existing", "ERROR: not a valid object name: '{}'"),
+    ("error.no_head", "ERROR: HEAD not found"),
+    ("error.already_initialized", "ERROR: The repository is already initialized"),
+    ("error.no_repository", "ERROR: Repository not found"),
+    ("error.invalid_argument", "ERROR: Invalid arguments.\n    Got: {}\n    Usage: {}\n"),
+    ("error.log", "ERROR: Could not write to the log"),
+    ("error.compression", "ERROR: Could not compress the file"),
+    ("error.time", "ERROR: Could not get the current time"),
+    ("error.invalid_tree", "ERROR: Invalid tree"),
+    ("error.invalid_commit", "ERROR: Invalid commit"),
+    ("error.invalid_tag", "ERROR: Invalid tag"),
+    ("error.connection", "ERROR: Could not connect to the server"),
+    ("error.socket", "SocketError in function {}. Info: {}"),
+    ("error.pack_file", "PackFileError in function {}. Info: {}"),
+    ("error.branch_non_exists", "ERROR: branch '{}' not found."),
+    ("error.branch_already_exists", "error: branch '{}' already exists."),
+    ("error.delete_current_branch", "ERROR: Cannot delete branch '{}': HEAD points to it"),
+    ("error.tag_already_exists", "ERROR: tag '{}' already exists"),
+    ("error.tag_non_exists", "ERROR: tag '{}' not found"),
+    ("error.pull_request_write", "ERROR: Could not write the PR on the server"),
+    ("error.pull_request_read", "ERROR: Could not read the PR from the server"),
+    ("error.push", "ERROR: Push failed. Info: {}"),
+    ("error.branch_not_found", "ERROR: Branch not found"),
+    ("error.rebase_in_progress", "ERROR: A rebase is in progress. Use 'rebase --continue', 'rebase --skip' or 'rebase --abort'"),
+    ("error.no_rebase_in_progress", "ERROR: No rebase in progress"),
+    ("error.unresolved_conflicts", "ERROR: Unresolved conflicts remain in: {}"),
+    ("error.bisect_in_progress", "ERROR: A bisect is in progress. Use 'bisect reset' to finish it"),
+    ("error.no_bisect_in_progress", "ERROR: No bisect in progress"),
+    ("error.bisect_run", "ERROR: bisect run could not continue: {}"),
+    ("error.invalid_pattern", "ERROR: Invalid regular expression: {}"),
+    ("error.no_tag_to_describe", "ERROR: No tags can describe '{}'. Try --tags or --always"),
+    ("error.bundle", "ERROR: bundle: {}"),
+    ("error.patch", "ERROR: patch: {}"),
+    ("error.config", "ERROR: config: {}"),
+    ("error.alias", "ERROR: alias: {}"),
+    ("error.hook", "ERROR: hook {} rejected the operation: {}"),
+    ("error.exited_with", "exited with {}"),
+    ("error.command_exited_with", "'{}' exited with {}"),
+    ("input.unclosed_quote", "unclosed quote {}"),
+    ("alias.recursion", "alias recursion: {}"),
+    ("init.done", "Initialized empty Gitr repository"),
+    ("commit.detached_head", "detached HEAD"),
+    ("config.key_not_found", "key {} not found"),
+    ("config.invalid_key", "invalid key: {}"),
+    ("config.invalid_line", "invalid line {}: {}"),
+    ("config.not_a_bool", "{} is not a boolean: {}"),
+    ("config.not_an_int", "{} is not an integer: {}"),
+    ("merge.updating", "Updating {}..{}"),
+    ("merge.fast_forward", "Fast-forward"),
+    ("merge.no_conflicts", "no conflicts"),
+    ("merge.added", "merge changes added"),
+    ("merge.conflict_hint", "There was a conflict, please resolve it before continuing"),
+    ("rebase.nothing", "nothing to rebase"),
+    ("rebase.conflict", "CONFLICT: could not apply {}... {}"),
+    ("rebase.both_modified", "both modified:   {}"),
+    ("rebase.resolve_hint", "Resolve all conflicts manually and then run \"rebase --continue\"."),
+    ("rebase.skip_hint", "You can instead skip this commit with \"rebase --skip\"."),
+    ("rebase.abort_hint", "To abort and get back to the state before \"rebase\", run \"rebase --abort\"."),
+    ("rebase.success", "Successfully rebased and updated {}."),
+    ("diff.file_changed", "{} file changed"),
+    ("diff.files_changed", "{} files changed"),
+    ("diff.insertion", "{} insertion(+)"),
+    ("diff.insertions", "{} insertions(+)"),
+    ("diff.deletion", "{} deletion(-)"),
+    ("diff.deletions", "{} deletions(-)"),
+    ("diff.rename_threshold", "diff.renameThreshold is not a percentage: {}"),
+    ("patch.invalid_path", "invalid path: {}"),
+    ("patch.invalid_mode", "invalid mode: {}"),
+    ("patch.color_codes", "the patch has color codes: {}"),
+    ("patch.invalid_header", "invalid header: {}"),
+    ("patch.invalid_hunk", "invalid hunk: {}"),
+    ("patch.hunk_too_long", "the hunk has more lines than its header says: {}"),
+    ("patch.hunk_does_not_apply", "hunk {} does not apply"),
+    ("patch.binary", "cannot apply binary changes to {}"),
+    ("patch.already_exists", "{} already exists"),
+    ("patch.does_not_exist", "{} does not exist"),
+    ("patch.not_empty_after_delete", "{} is not empty after deleting it"),
+    ("patch.no_patches", "{} does not contain any patch"),
+    ("patch.invalid_mail", "invalid mail: {}"),
+    ("patch.mail_without_headers", "no headers"),
+    ("patch.mail_missing_header", "missing header {}"),
+    ("patch.mail_invalid_date", "invalid date"),
+    ("patch.mail_without_patches", "the mail does not contain any patch"),
+    ("apply.conflicts", "Applied patch to '{}' with conflicts.\nU {}"),
+    ("apply.checked", "Checked patch {}"),
+    ("apply.clean", "Applied patch {} cleanly."),
+    ("am.applying", "Applying: {}"),
+    ("push.rejected", "the server rejected {}"),
+    ("push.invalid_report", "invalid response from the server"),
+    ("push.ref_not_reported", "the server did not report the status of {}"),
+    ("bundle.invalid", "'{}' is not a valid bundle"),
+    ("bundle.empty", "refusing to create an empty bundle"),
+    ("bundle.corrupt_pack", "the packfile of '{}' is corrupt"),
+    ("bundle.missing_prerequisite", "missing prerequisite commit {}"),
+    ("bundle.contains_ref", "The bundle contains this ref:"),
+    ("bundle.contains_refs", "The bundle contains these {} refs:"),
+    ("bundle.complete_history", "The bundle records a complete history."),
+    ("bundle.requires_ref", "The bundle requires this ref:"),
+    ("bundle.requires_refs", "The bundle requires these {} refs:"),
+    ("bundle.ok", "{} is okay"),
+    ("bisect.waiting_both", "waiting for both good and bad commits"),
+    ("bisect.waiting_bad", "waiting for bad commit, good commit(s) known"),
+    ("bisect.waiting_good", "waiting for good commit(s), bad commit known"),
+    ("bisect.first_bad", "{} is the first bad commit"),
+    ("bisect.only_skipped", "There are only 'skip'ped commits left to test.\nThe first bad commit could be any of:"),
+    ("bisect.cannot_continue", "We cannot bisect more!"),
+    ("bisect.bisecting", "Bisecting: {} revisions left to test after this (roughly {} steps)"),
+    ("bisect.previous_head", "Previous HEAD position was {}"),
+    ("bisect.run_needs_terms", "a good and a bad commit have to be marked before 'bisect run'"),
+    ("usage.valid_options", "Valid options: {}"),
+    ("usage.value", "<value>"),
+    ("config.identity_unknown", "identity unknown; run 'config --global user.name <name>' and 'config --global user.email <email>'"),
+    ("status.on_branch", "On branch {}"),
+    ("status.head_detached", "HEAD detached at {}"),
+    ("status.rebase_in_progress", "rebase in progress; onto {}"),
+    ("status.rebase_continue_hint", "  (fix conflicts and then run \"rebase --continue\")"),
+    ("status.rebase_skip_hint", "  (use \"rebase --skip\" to skip this patch)"),
+    ("status.rebase_abort_hint", "  (use \"rebase --abort\" to check out the original branch)"),
+    ("status.no_commits", "No commits yet"),
+    ("status.to_be_committed", "Changes to be committed:"),
+    ("status.unstage_hint", "  (use \"rm <file>...\" to unstage)"),
+    ("status.new_file", "new file:   {}"),
+    ("status.modified", "modified:   {}"),
+    ("status.renamed", "renamed:    {} -> {}"),
+    ("status.deleted", "deleted:    {}"),
+    ("status.not_staged", "Changes not staged for commit:"),
+    ("status.add_hint", "  (use \"add <file>...\" to update what will be committed)"),
+    ("status.discard_hint", "  (use \"rm <file>...\" to discard changes in working directory)"),
+    ("status.untracked", "Untracked files:"),
+    ("status.track_hint", "  (use \"add <file>...\" to include in what will be committed)"),
+    ("status.nothing_added", "nothing added to commit but untracked files present (use \"add\" to track)"),
+    ("status.clean", "nothing to commit, working tree clean"),
+    ("repl.email_prompt", "Enter your email: "),
+    ("repl.email_ok", "The email is valid, you can start using Gitr"),
+    ("repl.welcome", "Welcome to the command-line version of Gitr"),
+    ("repl.welcome_commands", "Enter the commands you want to run"),
+    ("repl.welcome_quit", "(enter q to quit)"),
+    ("repl.gui_thread_error", "Error closing the GUI thread: {}"),
+    ("gui.no_commits", "No commits to show"),
+    ("gui.branches_error", "Error getting branches: {}"),
+    ("gui.login", "Hi, {}. Please enter your email"),
+    ("gui.invalid_mail", "Invalid email. Use the format name@xxxxxx.yyy"),
+    ("gui.no_repository", "There is no repository, open or create one."),
+    ("gui.add_error", "Error running add: {}"),
+    ("gui.commit_error", "Error running commit: {}"),
+    ("gui.checkout_error", "Error switching branch: {}"),
+    ("gui.clone_error", "Error cloning: {}"),
+    ("gui.push_error", "Error running push: {}"),
+    ("gui.pull_error", "Error running pull: {}"),
+    ("gui.fetch_error", "Error running fetch"),
+    ("gui.init_error", "Error initializing the repository"),
+    ("gui.merge_conflicts", "The merge has conflicts, please fix them and commit the result."),
+    ("gui.merge_error", "Error running merge: {}"),
+    ("gui.pull_requests_error", "Error getting pull requests: {}"),
+    ("gui.pr_id", "ID: {}"),
+    ("gui.pr_title", "Title: {}"),
+    ("gui.pr_description", "Description: {}"),
+    ("gui.cancel", "Cancel"),
+    ("gui.accept", "OK"),
+    ("gui.close", "Close"),
+    ("gui.connect", "Connect"),
+    ("gui.repo", "Repo"),
+    ("gui.init", "Init"),
+    ("gui.clone", "Clone"),
+    ("gui.commit", "Commit"),
+    ("gui.branch", "Branch"),
+    ("gui.add_branch", "+branch"),
+    ("gui.pull", "Pull"),
+    ("gui.push", "Push"),
+    ("gui.fetch", "Fetch"),
+    ("gui.merge", "Merge"),
+    ("gui.editor", "Editor"),
+    ("gui.new_branch_name", "New branch name"),
+    ("gui.name_placeholder", "Name"),
+    ("gui.clone_url", "Enter the repository URL"),
+    ("gui.commit_message", "Enter the commit message"),
+    ("gui.commit_message_placeholder", "Commit message"),
+    ("gui.init_repo", "Init repo"),
+    ("gui.init_repo_name", "Enter the name of your new repository"),
+    ("gui.repo_name_placeholder", "Repository name"),
+    ("gui.configure_mail", "Set your email for this repo."),
+    ("gui.mail_placeholder", "Email"),
+    ("gui.config_not_found", "Configuration not found.\nPress the \"Connect\" button to provide it."),
+    ("gui.remote_not_connected", "Remote upstream not connected.\nClone a repository or set the remote upstream manually."),
+    ("gui.commit_history", "Commit history"),
+    ("gui.commits_tab", "commits"),
+    ("gui.merge_select_branch", "Select the branch to merge with"),
+    ("gui.merge_tab", "merge"),
+    ("gui.conflicts_tab", "conflicts"),
+    ("gui.save_changes", "Save changes"),
+    ("gui.pull_requests", "Pull Requests"),
+    ("gui.create_pull_request", "Create Pull Request"),
+    ("gui.pr_open", "Open"),
+    ("gui.pr_closed", "Closed"),
+    ("gui.pr_title_placeholder", "Title"),
+    ("gui.pr_description_placeholder", "Description"),
+    ("gui.pr_base", "Base"),
+    ("gui.pr_compare", "Compare "),
+    ("tree.folder_not_found", "Folder not found"),
+    ("fetch.empty_packfile", "Error: empty packfile"),
+    ("remote.missing", "<no remote given>"),
+];
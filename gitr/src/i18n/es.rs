@@ -0,0 +1,211 @@
+// Catalogo en español. Cada clave tiene que estar tambien en en.rs
+pub const MENSAJES: &[(&str, &str)] = &[
+    ("error.input", "Error en la entrada de comandos"),
+    ("error.file_creation", "ERROR: No se pudo crear el archivo {}"),
+    ("error.file_write", "ERROR: No se pudo escribir el archivo {}"),
+    ("error.file_deletion", "ERROR: En la funcion {} falló una eliminación"),
+    ("error.object_not_found", "ERROR: No se encontro el objeto {}"),
+    ("error.file_read", "ERROR: No se pudo leer el archivo {}"),
+    ("error.file_delete", "ERROR: No se pudo borrar el archivo {}"),
+    ("error.no_commit_existing", "ERROR: nombre de objeto no valido: '{}'"),
+    ("error.no_head", "ERROR: No se encontro HEAD"),
+    ("error.already_initialized", "ERROR: El repositorio ya esta inicializado"),
+    ("error.no_repository", "ERROR: No se encontro el repositorio"),
+    ("error.invalid_argument", "ERROR: Argumentos invalidos.\n    Recibi: {}\n    Uso: {}\n"),
+    ("error.log", "ERROR: No se pudo escribir en el log"),
+    ("error.compression", "ERROR: No se pudo comprimir el archivo"),
+    ("error.time", "ERROR: No se pudo obtener el tiempo actual"),
+    ("error.invalid_tree", "ERROR: El arbol no es valido"),
+    ("error.invalid_commit", "ERROR: El commit no es valido"),
+    ("error.invalid_tag", "ERROR: La tag no es valida"),
+    ("error.connection", "ERROR: No se pudo conectar al servidor"),
+    ("error.socket", "SocketError en la funcion {}. Info: {}"),
+    ("error.pack_file", "PackFileError en la funcion {}. Info: {}"),
+    ("error.branch_non_exists", "ERROR: branch '{}' no encontrada."),
+    ("error.branch_already_exists", "error: la branch '{}' ya existe."),
+    ("error.delete_current_branch", "ERROR: No se puede borrar branch '{}': HEAD apunta ahi"),
+    ("error.tag_already_exists", "ERROR: tag '{}' ya existe"),
+    ("error.tag_non_exists", "ERROR: tag '{}' no encontrado"),
+    ("error.pull_request_write", "ERROR: No se pudo escribir el PR en el server"),
+    ("error.pull_request_read", "ERROR: No se pudo leer el PR del server"),
+    ("error.push", "ERROR: No se pudo hacer push. Info: {}"),
+    ("error.branch_not_found", "ERROR: No se encontro la branch"),
+    ("error.rebase_in_progress", "ERROR: Hay un rebase en curso. Use 'rebase --continue', 'rebase --skip' o 'rebase --abort'"),
+    ("error.no_rebase_in_progress", "ERROR: No hay un rebase en curso"),
+    ("error.unresolved_conflicts", "ERROR: Quedan conflictos sin resolver en: {}"),
+    ("error.bisect_in_progress", "ERROR: Hay un bisect en curso. Use 'bisect reset' para terminarlo"),
+    ("error.no_bisect_in_progress", "ERROR: No hay un bisect en curso"),
+    ("error.bisect_run", "ERROR: bisect run no pudo continuar: {}"),
+    ("error.invalid_pattern", "ERROR: Expresion regular invalida: {}"),
+    ("error.no_tag_to_describe", "ERROR: No hay tags que puedan describir '{}'. Pruebe con --tags o --always"),
+    ("error.bundle", "ERROR: bundle: {}"),
+    ("error.patch", "ERROR: patch: {}"),
+    ("error.config", "ERROR: config: {}"),
+    ("error.alias", "ERROR: alias: {}"),
+    ("error.hook", "ERROR: el hook {} rechazo la operacion: {}"),
+    ("error.exited_with", "termino con {}"),
+    ("error.command_exited_with", "'{}' termino con {}"),
+    ("input.unclosed_quote", "falta cerrar la comilla {}"),
+    ("alias.recursion", "recursion en los alias: {}"),
+    ("init.done", "Se inicializo un repositorio de Gitr vacio"),
+    ("commit.detached_head", "HEAD desacoplada"),
+    ("config.key_not_found", "no existe la clave {}"),
+    ("config.invalid_key", "clave invalida: {}"),
+    ("config.invalid_line", "linea {} invalida: {}"),
+    ("config.not_a_bool", "{} no es un booleano: {}"),
+    ("config.not_an_int", "{} no es un entero: {}"),
+    ("merge.updating", "Actualizando {}..{}"),
+    ("merge.fast_forward", "Avance rapido"),
+    ("merge.no_conflicts", "no hubo conflictos"),
+    ("merge.added", "se agregaron los cambios del merge"),
+    ("merge.conflict_hint", "Hubo un conflicto, por favor resuelvalo antes de continuar"),
+    ("rebase.nothing", "no hay nada para rebasar"),
+    ("rebase.conflict", "CONFLICTO: no se pudo aplicar {}... {}"),
+    ("rebase.both_modified", "modificado por ambos: {}"),
+    ("rebase.resolve_hint", "Resuelva todos los conflictos a mano y despues ejecute \"rebase --continue\"."),
+    ("rebase.skip_hint", "Tambien puede saltear este commit con \"rebase --skip\"."),
+    ("rebase.abort_hint", "Para abortar y volver al estado anterior al \"rebase\", ejecute \"rebase --abort\"."),
+    ("rebase.success", "Se rebaso y actualizo {} correctamente."),
+    ("diff.file_changed", "{} archivo cambiado"),
+    ("diff.files_changed", "{} archivos cambiados"),
+    ("diff.insertion", "{} insercion(+)"),
+    ("diff.insertions", "{} inserciones(+)"),
+    ("diff.deletion", "{} eliminacion(-)"),
+    ("diff.deletions", "{} eliminaciones(-)"),
+    ("diff.rename_threshold", "diff.renameThreshold no es un porcentaje: {}"),
+    ("patch.invalid_path", "path invalido: {}"),
+    ("patch.invalid_mode", "modo invalido: {}"),
+    ("patch.color_codes", "el patch tiene codigos de color: {}"),
+    ("patch.invalid_header", "header invalido: {}"),
+    ("patch.invalid_hunk", "hunk invalido: {}"),
+    ("patch.hunk_too_long", "el hunk tiene mas lineas que las que dice su header: {}"),
+    ("patch.hunk_does_not_apply", "el hunk {} no aplica"),
+    ("patch.binary", "no se pueden aplicar cambios binarios a {}"),
+    ("patch.already_exists", "{} ya existe"),
+    ("patch.does_not_exist", "{} no existe"),
+    ("patch.not_empty_after_delete", "{} no quedo vacio al borrarlo"),
+    ("patch.no_patches", "{} no contiene ningun patch"),
+    ("patch.invalid_mail", "mail invalido: {}"),
+    ("patch.mail_without_headers", "sin headers"),
+    ("patch.mail_missing_header", "falta el header {}"),
+    ("patch.mail_invalid_date", "fecha invalida"),
+    ("patch.mail_without_patches", "el mail no tiene ningun patch"),
+    ("apply.conflicts", "Se aplico el patch a '{}' con conflictos.\nU {}"),
+    ("apply.checked", "Patch {} verificado"),
+    ("apply.clean", "Se aplico el patch {} sin problemas."),
+    ("am.applying", "Aplicando: {}"),
+    ("push.rejected", "el server rechazo {}"),
+    ("push.invalid_report", "respuesta invalida del server"),
+    ("push.ref_not_reported", "el server no informo si actualizo {}"),
+    ("bundle.invalid", "'{}' no es un bundle valido"),
+    ("bundle.empty", "no se puede crear un bundle vacio"),
+    ("bundle.corrupt_pack", "el packfile de '{}' esta corrupto"),
+    ("bundle.missing_prerequisite", "falta el commit requerido {}"),
+    ("bundle.contains_ref", "El bundle contiene esta ref:"),
+    ("bundle.contains_refs", "El bundle contiene estas {} refs:"),
+    ("bundle.complete_history", "El bundle tiene la historia completa."),
+    ("bundle.requires_ref", "El bundle requiere esta ref:"),
+    ("bundle.requires_refs", "El bundle requiere estas {} refs:"),
+    ("bundle.ok", "{} esta bien"),
+    ("bisect.waiting_both", "esperando los commits good y bad"),
+    ("bisect.waiting_bad", "esperando el commit bad, ya hay commit(s) good"),
+    ("bisect.waiting_good", "esperando commit(s) good, ya hay un commit bad"),
+    ("bisect.first_bad", "{} es el primer commit malo"),
+    ("bisect.only_skipped", "Solo quedan commits salteados para probar.\nEl primer commit malo puede ser cualquiera de:"),
+    ("bisect.cannot_continue", "No se puede seguir bisecando"),
+    ("bisect.bisecting", "Bisecando: quedan {} revisiones para probar despues de esta (unos {} pasos)"),
+    ("bisect.previous_head", "La posicion anterior de HEAD era {}"),
+    ("bisect.run_needs_terms", "hace falta marcar un commit good y uno bad antes de 'bisect run'"),
+    ("usage.valid_options", "Opciones validas: {}"),
+    ("usage.value", "<valor>"),
+    ("config.identity_unknown", "falta configurar la identidad; use 'config --global user.name <nombre>' y 'config --global user.email <mail>'"),
+    ("status.on_branch", "En la rama {}"),
+    ("status.head_detached", "HEAD desacoplada en {}"),
+    ("status.rebase_in_progress", "rebase en curso; sobre {}"),
+    ("status.rebase_continue_hint", "  (arregle los conflictos y luego ejecute \"rebase --continue\")"),
+    ("status.rebase_skip_hint", "  (use \"rebase --skip\" para saltar este parche)"),
+    ("status.rebase_abort_hint", "  (use \"rebase --abort\" para volver a la branch original)"),
+    ("status.no_commits", "No hay commits todavia"),
+    ("status.to_be_committed", "Cambios a ser confirmados:"),
+    ("status.unstage_hint", "  (use \"rm <archivo>...\" para sacarlos del index)"),
+    ("status.new_file", "nuevo archivo:  {}"),
+    ("status.modified", "modificado:     {}"),
+    ("status.renamed", "renombrado:     {} -> {}"),
+    ("status.deleted", "borrado:        {}"),
+    ("status.not_staged", "Cambios no preparados para el commit:"),
+    ("status.add_hint", "  (use \"add <archivo>...\" para actualizar lo que se va a confirmar)"),
+    ("status.discard_hint", "  (use \"rm <archivo>...\" para descartar los cambios en el directorio de trabajo)"),
+    ("status.untracked", "Archivos sin seguimiento:"),
+    ("status.track_hint", "  (use \"add <archivo>...\" para incluirlos en lo que se va a confirmar)"),
+    ("status.nothing_added", "no hay nada agregado para confirmar, pero hay archivos sin seguimiento (use \"add\" para seguirlos)"),
+    ("status.clean", "nada para confirmar, el arbol de trabajo esta limpio"),
+    ("repl.email_prompt", "Ingrese su email: "),
+    ("repl.email_ok", "El email es valido, ya puede comenzar a usar Gitr"),
+    ("repl.welcome", "Bienvenido a la version command-line de Gitr"),
+    ("repl.welcome_commands", "Introduzca los comandos que desea realizar"),
+    ("repl.welcome_quit", "(introduzca q para salir del programa)"),
+    ("repl.gui_thread_error", "Error al cerrar el thread de la GUI: {}"),
+    ("gui.no_commits", "No hay commits para mostrar"),
+    ("gui.branches_error", "Error al obtener branches: {}"),
+    ("gui.login", "Hola, {}. Por favor, ingrese su mail"),
+    ("gui.invalid_mail", "Mail inválido. Con formato nombre@xxxxxx.yyy"),
+    ("gui.no_repository", "No hay un repositorio asociado, busque o cree uno."),
+    ("gui.add_error", "Error al hacer add: {}"),
+    ("gui.commit_error", "Error al hacer commit: {}"),
+    ("gui.checkout_error", "Error al cambiar de branch: {}"),
+    ("gui.clone_error", "Error al clonar: {}"),
+    ("gui.push_error", "Error al hacer push: {}"),
+    ("gui.pull_error", "Error al hacer pull: {}"),
+    ("gui.fetch_error", "Error al hacer fetch"),
+    ("gui.init_error", "Error al inicializar repo"),
+    ("gui.merge_conflicts", "Surgieron conflicts al hacer merge, por favor arreglarlos y commitear el resultado."),
+    ("gui.merge_error", "Error al hacer merge: {}"),
+    ("gui.pull_requests_error", "Error al obtener pull requests: {}"),
+    ("gui.pr_id", "ID: {}"),
+    ("gui.pr_title", "Título: {}"),
+    ("gui.pr_description", "Descripción: {}"),
+    ("gui.cancel", "Cancelar"),
+    ("gui.accept", "Aceptar"),
+    ("gui.close", "Cerrar"),
+    ("gui.connect", "Conectar"),
+    ("gui.repo", "Repo"),
+    ("gui.init", "Init"),
+    ("gui.clone", "Clone"),
+    ("gui.commit", "Commit"),
+    ("gui.branch", "Branch"),
+    ("gui.add_branch", "+branch"),
+    ("gui.pull", "Pull"),
+    ("gui.push", "Push"),
+    ("gui.fetch", "Fetch"),
+    ("gui.merge", "Merge"),
+    ("gui.editor", "Editor"),
+    ("gui.new_branch_name", "Nombre de la nueva branch"),
+    ("gui.name_placeholder", "Nombre"),
+    ("gui.clone_url", "Ingrese la URL del repositorio"),
+    ("gui.commit_message", "Ingrese el mensaje para el commit"),
+    ("gui.commit_message_placeholder", "Mensaje de commit"),
+    ("gui.init_repo", "Crear repo"),
+    ("gui.init_repo_name", "Ingrese el nombre para su nuevo repositorio"),
+    ("gui.repo_name_placeholder", "Nombre del repositorio"),
+    ("gui.configure_mail", "Configure su mail para este repo."),
+    ("gui.mail_placeholder", "Mail"),
+    ("gui.config_not_found", "Información de configuración no encontrada.\nPresione el botón \"Conectar\" para proveer esta información."),
+    ("gui.remote_not_connected", "Upstream remoto no conectado.\nClone un repositorio o configure el upstream remoto manualmente."),
+    ("gui.commit_history", "Historial de commits"),
+    ("gui.commits_tab", "commits"),
+    ("gui.merge_select_branch", "Seleccione la branch con la cual mergear"),
+    ("gui.merge_tab", "merge"),
+    ("gui.conflicts_tab", "conflictos"),
+    ("gui.save_changes", "Guardar cambios"),
+    ("gui.pull_requests", "Pull Requests"),
+    ("gui.create_pull_request", "Crear Pull Request"),
+    ("gui.pr_open", "Abiertos"),
+    ("gui.pr_closed", "Cerrados"),
+    ("gui.pr_title_placeholder", "Título"),
+    ("gui.pr_description_placeholder", "Descripción"),
+    ("gui.pr_base", "Base"),
+    ("gui.pr_compare", "Comparar "),
+    ("tree.folder_not_found", "No se encontro el folder"),
+    ("fetch.empty_packfile", "Error: packfile vacío"),
+    ("remote.missing", "<no se recibio un remote>"),
+];
@@ -0,0 +1,261 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::config::Config;
+
+mod en;
+mod es;
+
+/// Idiomas en los que se pueden mostrar los mensajes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    Es,
+    En,
+}
+
+// el idioma actual; hasta que se llame a init se usa ingles
+static LOCALE: AtomicU8 = AtomicU8::new(1);
+
+impl Locale {
+    /// Reconoce "es", "en_US.UTF-8", "es_AR@euro", etc. "C" y "POSIX" son ingles, como en git.
+    pub fn parse(valor: &str) -> Option<Locale> {
+        let valor = valor.trim().to_lowercase();
+        let idioma = valor.split(['_', '.', '@', '-']).next().unwrap_or_default();
+        match idioma {
+            "es" => Some(Locale::Es),
+            "en" | "c" | "posix" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    fn catalogo(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::Es => es::MENSAJES,
+            Locale::En => en::MENSAJES,
+        }
+    }
+}
+
+pub fn locale() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        0 => Locale::Es,
+        _ => Locale::En,
+    }
+}
+
+pub fn set_locale(locale: Locale) {
+    let valor = match locale {
+        Locale::Es => 0,
+        Locale::En => 1,
+    };
+    LOCALE.store(valor, Ordering::Relaxed);
+}
+
+/// Elige el idioma con i18n.locale de la config del cliente o, si no esta, con
+/// LC_ALL, LC_MESSAGES o LANG. Si ninguno es un idioma conocido queda ingles.
+pub fn init(cliente: &str) {
+    let de_config = Config::load(cliente)
+        .ok()
+        .and_then(|config| config.get("i18n.locale"));
+    let de_entorno = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|valor| !valor.is_empty());
+    let locale = de_config
+        .into_iter()
+        .chain(de_entorno)
+        .find_map(|valor| Locale::parse(&valor))
+        .unwrap_or(Locale::En);
+    set_locale(locale);
+}
+
+/// El mensaje de la clave en el idioma pedido, reemplazando cada "{}" por el siguiente
+/// argumento. Si la clave no esta en el catalogo se usa el de ingles, y si tampoco, la clave.
+pub fn message(locale: Locale, clave: &str, args: &[&dyn fmt::Display]) -> String {
+    let buscar = |catalogo: &[(&str, &'static str)]| {
+        catalogo
+            .iter()
+            .find(|(c, _)| *c == clave)
+            .map(|(_, mensaje)| *mensaje)
+    };
+    let plantilla = buscar(locale.catalogo())
+        .or_else(|| buscar(Locale::En.catalogo()))
+        .unwrap_or(clave);
+    let mut partes = plantilla.split("{}");
+    let mut res = partes.next().unwrap_or_default().to_string();
+    let mut args = args.iter();
+    for parte in partes {
+        if let Some(arg) = args.next() {
+            res.push_str(&arg.to_string());
+        }
+        res.push_str(parte);
+    }
+    res
+}
+
+/// El mensaje de la clave en el idioma actual
+pub fn tr(clave: &str, args: &[&dyn fmt::Display]) -> String {
+    message(locale(), clave, args)
+}
+
+/// Traduce una interfaz de GtkBuilder: el texto de cada propiedad con context="<clave>"
+/// se reemplaza por el mensaje de la clave en el idioma pedido.
+pub fn translate_ui(locale: Locale, xml: &str) -> String {
+    const CONTEXT: &str = " context=\"";
+    let mut res = String::new();
+    let mut resto = xml;
+    while let Some(i) = resto.find(CONTEXT) {
+        let (antes, despues) = resto.split_at(i + CONTEXT.len());
+        res.push_str(antes);
+        // <clave>">texto</property>
+        let propiedad = despues.split_once("\">").and_then(|(clave, texto)| {
+            let (_, siguiente) = texto.split_once("</property>")?;
+            Some((clave, siguiente))
+        });
+        let Some((clave, siguiente)) = propiedad else {
+            resto = despues;
+            continue;
+        };
+        let mensaje = message(locale, clave, &[])
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;");
+        res.push_str(&format!("{}\">{}</property>", clave, mensaje));
+        resto = siguiente;
+    }
+    res.push_str(resto);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::HashSet, fs, path::Path};
+
+    const LOCALES: [Locale; 2] = [Locale::Es, Locale::En];
+
+    // las claves que se le pasan a tr en el codigo y los context de las interfaces
+    fn claves_usadas(dir: &Path, claves: &mut HashSet<String>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                claves_usadas(&path, claves);
+                continue;
+            }
+            if path.extension().is_some_and(|ext| ext == "rs") {
+                let codigo = fs::read_to_string(&path).unwrap();
+                for (i, _) in codigo.match_indices("tr(\"") {
+                    // que no sea el final de otro nombre, como from_str("
+                    let anterior = codigo[..i].chars().last().unwrap_or(' ');
+                    if anterior.is_alphanumeric() || anterior == '_' {
+                        continue;
+                    }
+                    if let Some((clave, _)) = codigo[i + 4..].split_once('"') {
+                        claves.insert(clave.to_string());
+                    }
+                }
+            }
+            if path.extension().is_some_and(|ext| ext == "glade") {
+                let ui = fs::read_to_string(&path).unwrap();
+                for resto in ui.split(" context=\"").skip(1) {
+                    if let Some((clave, _)) = resto.split_once('"') {
+                        claves.insert(clave.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test01_todos_los_catalogos_tienen_las_mismas_claves() {
+        for locale in LOCALES {
+            let mut vistas = HashSet::new();
+            for (clave, _) in locale.catalogo() {
+                assert!(
+                    vistas.insert(*clave),
+                    "{:?}: clave repetida {}",
+                    locale,
+                    clave
+                );
+            }
+        }
+        for (clave, mensaje) in Locale::Es.catalogo() {
+            for locale in LOCALES {
+                let traduccion = locale.catalogo().iter().find(|(c, _)| c == clave);
+                let Some((_, traduccion)) = traduccion else {
+                    panic!("falta la clave {} en el catalogo {:?}", clave, locale);
+                };
+                assert_eq!(
+                    mensaje.matches("{}").count(),
+                    traduccion.matches("{}").count(),
+                    "{:?}: la clave {} no tiene los mismos argumentos",
+                    locale,
+                    clave
+                );
+            }
+        }
+        for locale in LOCALES {
+            assert_eq!(locale.catalogo().len(), Locale::Es.catalogo().len());
+        }
+    }
+
+    #[test]
+    fn test02_las_claves_usadas_estan_en_los_catalogos() {
+        let mut claves = HashSet::new();
+        claves_usadas(Path::new("src"), &mut claves);
+        assert!(claves.contains("error.no_head"));
+        for clave in claves {
+            for locale in LOCALES {
+                assert!(
+                    locale.catalogo().iter().any(|(c, _)| *c == clave),
+                    "falta la clave {} en el catalogo {:?}",
+                    clave,
+                    locale
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test03_mensajes_con_argumentos_y_locales() {
+        assert_eq!(
+            message(Locale::En, "error.tag_already_exists", &[&"v1"]),
+            "ERROR: tag 'v1' already exists"
+        );
+        assert_eq!(
+            message(Locale::Es, "error.tag_already_exists", &[&"v1"]),
+            "ERROR: tag 'v1' ya existe"
+        );
+        assert_eq!(
+            message(Locale::Es, "diff.files_changed", &[&2]),
+            "2 archivos cambiados"
+        );
+        assert_eq!(message(Locale::En, "no.existe", &[]), "no.existe");
+        assert_eq!(locale(), Locale::En);
+        assert_eq!(Locale::parse("en_US.UTF-8"), Some(Locale::En));
+        assert_eq!(Locale::parse("es_AR"), Some(Locale::Es));
+        assert_eq!(Locale::parse("C"), Some(Locale::En));
+        assert_eq!(Locale::parse("fr_FR"), None);
+    }
+
+    #[test]
+    fn test04_las_interfaces_se_traducen_por_context() {
+        let ui = "<property name=\"label\" translatable=\"yes\" context=\"gui.cancel\">Cancelar</property>\n\
+                  <property name=\"visible\">True</property>\n\
+                  <property name=\"label\" translatable=\"yes\" context=\"gui.config_not_found\">Información\nvieja</property>";
+        assert_eq!(
+            translate_ui(Locale::En, ui),
+            "<property name=\"label\" translatable=\"yes\" context=\"gui.cancel\">Cancel</property>\n\
+             <property name=\"visible\">True</property>\n\
+             <property name=\"label\" translatable=\"yes\" context=\"gui.config_not_found\">Configuration not found.\n\
+             Press the \"Connect\" button to provide it.</property>"
+        );
+        assert!(translate_ui(Locale::Es, ui).contains("\">Cancelar</property>"));
+        // los mensajes se escapan para el XML
+        let ui = "<property name=\"label\" context=\"a<b&c\">x</property>";
+        assert_eq!(
+            translate_ui(Locale::En, ui),
+            "<property name=\"label\" context=\"a<b&c\">a&lt;b&amp;c</property>"
+        );
+    }
+}
@@ -1,10 +1,14 @@
 pub mod commands;
 pub mod diff;
 pub mod git_transport;
+#[cfg(feature = "gui")]
 pub mod gui;
 pub mod objects;
 
+pub mod config;
 pub mod file_manager;
 pub mod gitr_errors;
+pub mod i18n;
 pub mod logger;
+pub mod regex;
 pub mod server;
@@ -1,7 +1,17 @@
 use ::chrono;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::{file_manager, gitr_errors::GitrError};
 
+// con true no poder escribir el log no es un error, como al correr un solo comando
+// desde un directorio que no tiene src/log.json
+static LOG_OPCIONAL: AtomicBool = AtomicBool::new(false);
+
+/// Si el log es opcional, los errores al escribirlo se ignoran en vez de cortar el comando
+pub fn set_opcional(opcional: bool) {
+    LOG_OPCIONAL.store(opcional, Ordering::Relaxed);
+}
+
 #[derive(Debug)]
 enum EntryType {
     Error,
@@ -33,6 +43,7 @@ impl Logger {
         );
         match file_manager::append_to_file("src/log.json".to_string(), entry) {
             Ok(_) => Ok(()),
+            Err(_) if LOG_OPCIONAL.load(Ordering::Relaxed) => Ok(()),
             Err(_) => Err(GitrError::LogError),
         }
     }
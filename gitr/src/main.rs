@@ -1,13 +1,23 @@
-use gitr::{commands, file_manager, gitr_errors::GitrError, logger};
+use gitr::{
+    commands, file_manager,
+    gitr_errors::GitrError,
+    i18n::{self, tr},
+    logger,
+};
 
 use std::{
     fs,
     io::{self, Write},
+    path::Path,
+    process::exit,
 };
 extern crate flate2;
 
+#[cfg(feature = "gui")]
 use gitr::gui::gui_from_glade::*;
 
+const USAGE: &str = "Usage: client [--no-gui] <client_name> [[--] <command> [<args>...]]";
+
 fn get_input() -> Result<String, GitrError> {
     print!("\x1b[34mgitr: $ \x1b[0m");
     match io::stdout().flush() {
@@ -21,6 +31,7 @@ fn get_input() -> Result<String, GitrError> {
     }
     let mut input = String::new();
     match io::stdin().read_line(&mut input) {
+        Ok(0) => return Err(GitrError::InputError), // EOF, p.ej. con stdin redirigido
         Ok(_) => (),
         Err(e) => {
             return Err(GitrError::InvalidArgumentError(
@@ -49,13 +60,13 @@ fn setup_config_file(client_path: String) {
     let mut email_recibido = String::new();
 
     while !email_valido(email_recibido.clone()) {
-        println!("Ingrese su email: ");
+        println!("{}", tr("repl.email_prompt", &[]));
         email_recibido = match get_input() {
             Ok(email) => email,
             Err(_) => "user@mail.com".to_string(),
         };
     }
-    println!("El email es valido, ya puede comenzar a usar Gitr\n");
+    println!("{}\n", tr("repl.email_ok", &[]));
     let name = client_path.clone();
     let config_file_data = format!("[user]\n\temail = {}\n\tname = {}\n", email_recibido, name);
     file_manager::write_file(client_path + "/gitrconfig", config_file_data).unwrap();
@@ -66,28 +77,87 @@ pub fn existe_config(client_path: String) -> bool {
 }
 
 fn print_bienvenida() {
-    println!("\t╔══════════════════════════════════════════════╗");
-    println!("\t║ \x1b[34mBienvenido a la version command-line de Gitr\x1b[0m ║");
-    println!("\t║ \x1b[34mIntroduzca los comandos que desea realizar\x1b[0m   ║");
-    println!("\t║ \x1b[34m(introduzca q para salir del programa)\x1b[0m       ║");
-    println!("\t╚══════════════════════════════════════════════╝");
+    let lineas = [
+        tr("repl.welcome", &[]),
+        tr("repl.welcome_commands", &[]),
+        tr("repl.welcome_quit", &[]),
+    ];
+    // el recuadro se ajusta al texto del idioma
+    let ancho = lineas.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    println!("\t╔{}╗", "═".repeat(ancho + 2));
+    for linea in lineas {
+        let relleno = " ".repeat(ancho - linea.chars().count());
+        println!("\t║ \x1b[34m{}\x1b[0m{} ║", linea, relleno);
+    }
+    println!("\t╚{}╝", "═".repeat(ancho + 2));
+}
+
+// corre un solo comando sin REPL ni GUI y devuelve el codigo de salida
+fn run_command(argv: Vec<String>, cliente: String) -> i32 {
+    match commands::handler::command_handler(argv, false, "".to_string(), cliente) {
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            if let Err(e) = logger::log_error(e.to_string()) {
+                eprintln!("Logger Error: {}", e);
+            }
+            e.exit_code()
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+fn spawn_gui(cliente: String, no_gui: bool) -> Option<std::thread::JoinHandle<()>> {
+    if no_gui {
+        return None;
+    }
+    Some(std::thread::spawn(move || {
+        initialize_gui(cliente);
+    }))
+}
+
+#[cfg(not(feature = "gui"))]
+fn spawn_gui(_cliente: String, _no_gui: bool) -> Option<std::thread::JoinHandle<()>> {
+    None
 }
 
 fn main() {
-    let args = std::env::args().collect::<Vec<String>>();
-    if args.len() < 2 {
-        println!("Usage: cargo run --bin client <client_name>");
-        return;
+    // client [--no-gui] <client_name>                      REPL (con GUI salvo --no-gui)
+    // client [--no-gui] <client_name> [--] <command> ...   corre un comando y sale
+    let mut args = std::env::args().skip(1).collect::<Vec<String>>();
+    let no_gui = args.first().is_some_and(|arg| arg == "--no-gui");
+    if no_gui {
+        args.remove(0);
+    }
+    if args.is_empty() || args[0].starts_with('-') {
+        eprintln!("{}", USAGE);
+        // mismo codigo que un InvalidArgumentError
+        exit(129);
+    }
+    let cliente = args.remove(0);
+    if args.first().is_some_and(|arg| arg == "--") {
+        args.remove(0);
+    }
+    let headless = !args.is_empty();
+    logger::set_opcional(headless);
+    if !Path::new(&cliente).is_dir() {
+        if let Err(e) = file_manager::create_directory(&cliente) {
+            let e = match e {
+                GitrError::AlreadyInitialized => GitrError::FileCreationError(cliente),
+                e => e,
+            };
+            eprintln!("{}", e);
+            exit(e.exit_code());
+        }
+    }
+    i18n::init(&cliente);
+    if headless {
+        exit(run_command(args, cliente));
     }
-    let cliente = args[1].clone();
-    let cliente_clon = cliente.clone();
 
-    let child = std::thread::spawn(move || {
-        initialize_gui(cliente_clon.clone());
-    });
+    let child = spawn_gui(cliente.clone(), no_gui);
     print_bienvenida();
 
-    let _ = file_manager::create_directory(&cliente);
     while !existe_config(cliente.clone()) {
         setup_config_file(cliente.clone());
     }
@@ -97,6 +167,7 @@ fn main() {
     loop {
         let input = match get_input() {
             Ok(input) => input,
+            Err(GitrError::InputError) => break,
             Err(e) => {
                 println!("Error: {}", e);
                 break;
@@ -106,7 +177,13 @@ fn main() {
         if input == "q\n" {
             return;
         }
-        let argv: Vec<String> = commands::handler::parse_input(input);
+        let argv: Vec<String> = match commands::handler::parse_input(input) {
+            Ok(argv) => argv,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
 
         // argv = ["command", "flag1", "flag2", ...]
         match commands::handler::command_handler(
@@ -118,6 +195,8 @@ fn main() {
             Ok((hubo_conflict_res, branch_hash_res)) => {
                 hubo_conflict = hubo_conflict_res;
                 branch_hash = branch_hash_res;
+                // por si el comando cambio i18n.locale o el repo actual
+                i18n::init(&cliente);
             }
             Err(e) => {
                 println!("{}", e);
@@ -128,8 +207,7 @@ fn main() {
             }
         };
     }
-    match child.join() {
-        Ok(_) => (),
-        Err(e) => println!("Error al cerrar el thread de la GUI: {:?}", e),
+    if let Some(Err(e)) = child.map(|child| child.join()) {
+        println!("{}", tr("repl.gui_thread_error", &[&format!("{:?}", e)]));
     }
 }
@@ -26,6 +26,25 @@ impl Commit {
         committer: String,
         message: String,
         cliente: String,
+    ) -> Result<Self, GitrError> {
+        let author = format!(
+            "{} <{}> {} -0300",
+            author,
+            get_user_mail_from_config(cliente.clone())?,
+            Utc::now().timestamp()
+        );
+        Self::new_with_author(tree, parents, author, committer, message, cliente)
+    }
+
+    /// Igual que new, pero recibe la identidad completa del autor ("nombre <mail> timestamp zona")
+    /// para conservar la autoria original, por ejemplo al aplicar un patch con am.
+    pub fn new_with_author(
+        tree: String,
+        parents: Vec<String>,
+        author: String,
+        committer: String,
+        message: String,
+        cliente: String,
     ) -> Result<Self, GitrError> {
         let mut format_data = String::new();
         let header = "commit ";
@@ -36,12 +55,7 @@ impl Commit {
                 format_data.push_str(&format!("parent {}\n", parent));
             }
         }
-        format_data.push_str(&format!(
-            "author {} <{}> {} -0300\n",
-            author,
-            get_user_mail_from_config(cliente.clone())?,
-            Utc::now().timestamp()
-        ));
+        format_data.push_str(&format!("author {}\n", author));
         format_data.push_str(&format!(
             "committer {} <{}> {} -0300\n",
             committer,
@@ -49,7 +63,6 @@ impl Commit {
             Utc::now().timestamp()
         ));
         format_data.push('\n');
-        let message = message.replace('\"', "");
         format_data.push_str(&format!("{}\n", message));
         let size = format_data.as_bytes().len();
         let format_data_entera = format!("{}{}\0{}", header, size, format_data);
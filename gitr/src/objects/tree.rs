@@ -133,7 +133,7 @@ impl Tree {
             }
         };
         for entry in raw_data.split('\n') {
-            let _new_path_hash = entry.split(' ').collect::<Vec<&str>>()[1];
+            let _new_path_hash = entry.splitn(2, ' ').collect::<Vec<&str>>()[1];
             let hash = _new_path_hash.split('\0').collect::<Vec<&str>>()[1];
             objects_id.push(hash.to_string());
         }
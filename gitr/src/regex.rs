@@ -0,0 +1,315 @@
+use crate::gitr_errors::GitrError;
+
+/// Expresion regular extendida con backtracking. Soporta literales, `.`, clases
+/// (`[a-z]`, `[^0-9]`, `\d`, `\w`, `\s`), anclas `^` y `$`, grupos con `|` y los
+/// cuantificadores `*`, `+` y `?`.
+#[derive(Clone, Debug)]
+pub struct Regex {
+    alternativas: Vec<Vec<Nodo>>,
+    ignore_case: bool,
+}
+
+#[derive(Clone, Debug)]
+enum Nodo {
+    Literal(char),
+    Cualquiera,
+    Clase(Vec<(char, char)>, bool),
+    Inicio,
+    Fin,
+    Grupo(Vec<Vec<Nodo>>),
+    Repeticion(Box<Nodo>, usize, Option<usize>),
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    patron: String,
+}
+
+impl Parser {
+    fn error(&self) -> GitrError {
+        GitrError::InvalidPatternError(self.patron.clone())
+    }
+
+    fn siguiente(&mut self) -> Option<char> {
+        let c = self.chars.get(self.pos).copied();
+        self.pos += 1;
+        c
+    }
+
+    fn alternativas(&mut self) -> Result<Vec<Vec<Nodo>>, GitrError> {
+        let mut alternativas = vec![self.secuencia()?];
+        while self.chars.get(self.pos) == Some(&'|') {
+            self.pos += 1;
+            alternativas.push(self.secuencia()?);
+        }
+        Ok(alternativas)
+    }
+
+    fn secuencia(&mut self) -> Result<Vec<Nodo>, GitrError> {
+        let mut nodos = Vec::new();
+        while let Some(c) = self.chars.get(self.pos).copied() {
+            let nodo = match c {
+                '|' | ')' => break,
+                '*' | '+' | '?' => {
+                    self.pos += 1;
+                    let (min, max) = match c {
+                        '*' => (0, None),
+                        '+' => (1, None),
+                        _ => (0, Some(1)),
+                    };
+                    match nodos.pop() {
+                        Some(Nodo::Repeticion(..)) | None => return Err(self.error()),
+                        Some(nodo) => Nodo::Repeticion(Box::new(nodo), min, max),
+                    }
+                }
+                _ => self.atomo()?,
+            };
+            nodos.push(nodo);
+        }
+        Ok(nodos)
+    }
+
+    fn atomo(&mut self) -> Result<Nodo, GitrError> {
+        match self.siguiente() {
+            Some('(') => {
+                let alternativas = self.alternativas()?;
+                match self.siguiente() {
+                    Some(')') => Ok(Nodo::Grupo(alternativas)),
+                    _ => Err(self.error()),
+                }
+            }
+            Some('[') => self.clase(),
+            Some('.') => Ok(Nodo::Cualquiera),
+            Some('^') => Ok(Nodo::Inicio),
+            Some('$') => Ok(Nodo::Fin),
+            Some('\\') => match self.siguiente() {
+                Some(c) => Ok(escape(c)),
+                None => Err(self.error()),
+            },
+            Some(c) => Ok(Nodo::Literal(c)),
+            None => Err(self.error()),
+        }
+    }
+
+    fn clase(&mut self) -> Result<Nodo, GitrError> {
+        let negada = self.chars.get(self.pos) == Some(&'^');
+        if negada {
+            self.pos += 1;
+        }
+        let mut rangos = Vec::new();
+        let mut primero = true;
+        loop {
+            let c = match self.siguiente() {
+                Some(']') if !primero => return Ok(Nodo::Clase(rangos, negada)),
+                Some('\\') => match self.siguiente().map(escape) {
+                    Some(Nodo::Clase(otros, false)) => {
+                        rangos.extend(otros);
+                        primero = false;
+                        continue;
+                    }
+                    Some(Nodo::Literal(c)) => c,
+                    _ => return Err(self.error()),
+                },
+                Some(c) => c,
+                None => return Err(self.error()),
+            };
+            primero = false;
+            match (self.chars.get(self.pos), self.chars.get(self.pos + 1)) {
+                (Some('-'), Some(hasta)) if *hasta != ']' => {
+                    rangos.push((c, *hasta));
+                    self.pos += 2;
+                }
+                _ => rangos.push((c, c)),
+            }
+        }
+    }
+}
+
+// \d, \w y \s son clases, cualquier otro caracter escapado es literal
+fn escape(c: char) -> Nodo {
+    let digitos = vec![('0', '9')];
+    let palabra = vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')];
+    let espacios = vec![(' ', ' '), ('\t', '\t'), ('\r', '\r'), ('\n', '\n')];
+    match c {
+        'd' => Nodo::Clase(digitos, false),
+        'D' => Nodo::Clase(digitos, true),
+        'w' => Nodo::Clase(palabra, false),
+        'W' => Nodo::Clase(palabra, true),
+        's' => Nodo::Clase(espacios, false),
+        'S' => Nodo::Clase(espacios, true),
+        't' => Nodo::Literal('\t'),
+        c => Nodo::Literal(c),
+    }
+}
+
+impl Regex {
+    pub fn new(patron: &str, ignore_case: bool) -> Result<Regex, GitrError> {
+        let mut parser = Parser {
+            chars: patron.chars().collect(),
+            pos: 0,
+            patron: patron.to_string(),
+        };
+        let alternativas = parser.alternativas()?;
+        if parser.pos < parser.chars.len() {
+            return Err(parser.error());
+        }
+        Ok(Regex {
+            alternativas,
+            ignore_case,
+        })
+    }
+
+    /// Regex que busca el texto tal cual, sin interpretar caracteres especiales.
+    pub fn literal(texto: &str, ignore_case: bool) -> Regex {
+        Regex {
+            alternativas: vec![texto.chars().map(Nodo::Literal).collect()],
+            ignore_case,
+        }
+    }
+
+    /// Devuelve true si la regex matchea en alguna parte del texto.
+    pub fn is_match(&self, texto: &str) -> bool {
+        let texto = texto.chars().collect::<Vec<char>>();
+        (0..=texto.len()).any(|inicio| {
+            self.alternativas
+                .iter()
+                .any(|secuencia| self.secuencia(secuencia, &texto, inicio, &mut |_| true))
+        })
+    }
+
+    fn iguales(&self, a: char, b: char) -> bool {
+        a == b || (self.ignore_case && a.to_lowercase().eq(b.to_lowercase()))
+    }
+
+    fn en_clase(&self, c: char, rangos: &[(char, char)]) -> bool {
+        let variantes = match self.ignore_case {
+            true => c
+                .to_lowercase()
+                .chain(c.to_uppercase())
+                .collect::<Vec<char>>(),
+            false => vec![c],
+        };
+        variantes
+            .iter()
+            .any(|c| rangos.iter().any(|(desde, hasta)| desde <= c && c <= hasta))
+    }
+
+    fn secuencia(
+        &self,
+        nodos: &[Nodo],
+        texto: &[char],
+        pos: usize,
+        seguir: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        match nodos.split_first() {
+            None => seguir(pos),
+            Some((nodo, resto)) => self.nodo(nodo, texto, pos, &mut |p| {
+                self.secuencia(resto, texto, p, seguir)
+            }),
+        }
+    }
+
+    fn nodo(
+        &self,
+        nodo: &Nodo,
+        texto: &[char],
+        pos: usize,
+        seguir: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        match nodo {
+            Nodo::Literal(c) => {
+                pos < texto.len() && self.iguales(texto[pos], *c) && seguir(pos + 1)
+            }
+            Nodo::Cualquiera => pos < texto.len() && seguir(pos + 1),
+            Nodo::Clase(rangos, negada) => {
+                pos < texto.len() && self.en_clase(texto[pos], rangos) != *negada && seguir(pos + 1)
+            }
+            Nodo::Inicio => pos == 0 && seguir(pos),
+            Nodo::Fin => pos == texto.len() && seguir(pos),
+            Nodo::Grupo(alternativas) => alternativas
+                .iter()
+                .any(|secuencia| self.secuencia(secuencia, texto, pos, seguir)),
+            Nodo::Repeticion(nodo, min, max) => {
+                self.repetir(nodo, (*min, *max), 0, texto, pos, seguir)
+            }
+        }
+    }
+
+    // intenta primero repetir una vez mas (greedy) y despues seguir con el resto
+    fn repetir(
+        &self,
+        nodo: &Nodo,
+        (min, max): (usize, Option<usize>),
+        veces: usize,
+        texto: &[char],
+        pos: usize,
+        seguir: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        if max.is_none_or(|max| veces < max)
+            && self.nodo(nodo, texto, pos, &mut |p| {
+                (p != pos || veces < min)
+                    && self.repetir(nodo, (min, max), veces + 1, texto, p, seguir)
+            })
+        {
+            return true;
+        }
+        veces >= min && seguir(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matchea(patron: &str, texto: &str) -> bool {
+        Regex::new(patron, false).unwrap().is_match(texto)
+    }
+
+    #[test]
+    fn test00_literales_y_comodines() {
+        assert!(matchea("hola", "dije hola mundo"));
+        assert!(!matchea("hola", "chau"));
+        assert!(matchea("h.la", "hola"));
+        assert!(matchea("^ho", "hola"));
+        assert!(!matchea("^ola", "hola"));
+        assert!(matchea("la$", "hola"));
+        assert!(matchea("a\\.b", "a.b"));
+        assert!(!matchea("a\\.b", "axb"));
+    }
+
+    #[test]
+    fn test01_cuantificadores_grupos_y_alternativas() {
+        assert!(matchea("^ab*c$", "ac"));
+        assert!(matchea("^ab*c$", "abbbc"));
+        assert!(!matchea("^ab+c$", "ac"));
+        assert!(matchea("^colou?r$", "color"));
+        assert!(matchea("^(ab)+$", "ababab"));
+        assert!(!matchea("^(ab)+$", "ababa"));
+        assert!(matchea("^(gato|perro)s?$", "perros"));
+        assert!(matchea("^a.*z$", "abcz"));
+        assert!(matchea("^(a?)+$", ""));
+    }
+
+    #[test]
+    fn test02_clases_e_ignore_case() {
+        assert!(matchea("^[a-c]+$", "abcabc"));
+        assert!(!matchea("^[a-c]+$", "abd"));
+        assert!(matchea("^[^0-9]+$", "abc"));
+        assert!(matchea("\\d\\d", "version 12"));
+        assert!(matchea("^\\w+\\s\\w+$", "dos palabras"));
+        assert!(matchea("[]x]", "]"));
+        assert!(Regex::new("HOLA", true).unwrap().is_match("hola"));
+        assert!(Regex::new("[A-C]", true).unwrap().is_match("b"));
+        assert!(Regex::literal("a.b", false).is_match("xa.b"));
+        assert!(!Regex::literal("a.b", false).is_match("axb"));
+    }
+
+    #[test]
+    fn test03_patrones_invalidos() {
+        assert!(Regex::new("(ab", false).is_err());
+        assert!(Regex::new("ab)", false).is_err());
+        assert!(Regex::new("[ab", false).is_err());
+        assert!(Regex::new("*a", false).is_err());
+    }
+}
@@ -11,6 +11,7 @@ use std::io::Read;
 use std::io::Write;
 use std::net::{TcpListener, TcpStream};
 use std::path::Path;
+use std::process::{Command, Stdio};
 use std::str::from_utf8;
 
 use std::thread;
@@ -187,7 +188,8 @@ fn build_json_from_commit(commit_hash: String, commit_raw_data:String, ruta_repo
     let author = file_manager::get_commit_author(commit_hash.clone(), ruta_repo_server.clone())?;
     let author_mail = file_manager::get_commit_author_mail(commit_hash.clone(), ruta_repo_server.clone())?;
     let message = file_manager::get_commit_message(commit_hash.clone(), ruta_repo_server.clone())?;
-    let message = message.trim_end();
+    // el mensaje puede tener comillas o saltos de linea, serde lo escapa (con sus comillas)
+    let message = serde_json::to_string(message.trim_end()).unwrap_or_default();
     let commiter = file_manager::get_commit_commiter(commit_hash.clone(), ruta_repo_server.clone())?;
     let commiter_mail = file_manager::get_commit_commiter_mail(commit_hash.clone(), ruta_repo_server.clone())?;
 
@@ -202,7 +204,7 @@ fn build_json_from_commit(commit_hash: String, commit_raw_data:String, ruta_repo
                 r#""name":""#,&commiter,r#"","#,
                 r#""email":""#,&commiter_mail,r#"","#,
                 r#""date":""#,&date,r#""},"#,
-            r#""message":""#,&message,r#"","#,
+            r#""message":"#,&message,r#","#,
             r#""tree":{"#,
                 r#""sha":""#,&tree,r#""}","}"#
     ].concat();
@@ -708,7 +710,8 @@ fn gitr_receive_pack(stream: &mut TcpStream, r_path: String) -> std::io::Result<
             let (ids, content) = rcv_packfile_bruno(stream)?;
             update_contents(ids, content, r_path.clone())?;
         }
-        update_refs(old, new, names, r_path)?;
+        let reporte = update_refs_with_hooks(old, new, names, r_path);
+        stream.write_all(reporte.as_bytes())?;
 
         return Ok(());
     }
@@ -941,6 +944,141 @@ fn update_refs(
     Ok(())
 }
 
+/// Corre un hook del repositorio del servidor (r_path/hooks/<nombre>) si existe y es ejecutable.
+/// # Recibe
+/// * nombre: &str con el nombre del hook
+/// * args: &[String] con los argumentos del hook
+/// * stdin: &str con la entrada del hook
+/// * r_path: &str con la ruta del repositorio del servidor
+/// # Devuelve
+/// Una tupla con true si el hook acepto (o no existe) y la salida del hook (stdout y stderr),
+/// o un Error si no se pudo ejecutar.
+fn run_server_hook(
+    nombre: &str,
+    args: &[String],
+    stdin: &str,
+    r_path: &str,
+) -> std::io::Result<(bool, String)> {
+    use std::os::unix::fs::PermissionsExt;
+    let path = format!("{}/hooks/{}", r_path, nombre);
+    match fs::metadata(&path) {
+        Ok(meta) if meta.is_file() && meta.permissions().mode() & 0o111 != 0 => {}
+        _ => return Ok((true, String::new())),
+    }
+    let mut hijo = Command::new(fs::canonicalize(&path)?)
+        .args(args)
+        .current_dir(r_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut entrada) = hijo.stdin.take() {
+        // si el hook no lee el stdin la escritura puede fallar, no importa
+        let _ = entrada.write_all(stdin.as_bytes());
+    }
+    let output = hijo.wait_with_output()?;
+    let salida = String::from_utf8_lossy(&output.stdout).to_string()
+        + &String::from_utf8_lossy(&output.stderr);
+    Ok((output.status.success(), salida))
+}
+
+/// Arma una pkt-line con el texto provisto.
+fn pkt_line(texto: &str) -> String {
+    format!("{:04x}{}", texto.len() + 4, texto)
+}
+
+/// Pasa la salida de un hook a pkt-lines "remote: ..." para que el cliente la muestre.
+fn remote_lines(salida: &str) -> String {
+    salida
+        .lines()
+        .map(|linea| pkt_line(&format!("remote: {}\n", linea)))
+        .collect()
+}
+
+/// Actualiza las referencias del servidor pasando por los hooks pre-receive, update y post-receive.
+/// pre-receive y post-receive reciben por stdin una linea "<old> <new> <ref>" por referencia;
+/// update recibe "<ref> <old> <new>" como argumentos y puede rechazar solo esa referencia.
+/// # Recibe
+/// * old: Vec<String> con los ids de los objetos que el servidor tiene.
+/// * new: Vec<String> con los ids de los objetos que el cliente quiere mandar.
+/// * names: Vec<String> con los nombres de las referencias que el cliente quiere mandar.
+/// * r_path: String con la ruta del repositorio del servidor
+/// # Devuelve
+/// El reporte para el cliente en pkt-lines: la salida de los hooks ("remote: ...") y
+/// "ok <ref>" o "ng <ref> <motivo>" por cada referencia, terminado en "0000". Si un hook
+/// no se pudo ejecutar o no se pudieron actualizar las referencias, las afectadas quedan
+/// con "ng" y el motivo.
+fn update_refs_with_hooks(
+    old: Vec<String>,
+    new: Vec<String>,
+    names: Vec<String>,
+    r_path: String,
+) -> String {
+    let lineas = |indices: &[usize]| -> String {
+        indices
+            .iter()
+            .map(|&i| format!("{} {} {}\n", old[i], new[i], names[i]))
+            .collect()
+    };
+    let todas: Vec<usize> = (0..old.len()).collect();
+    let mut reporte = String::new();
+    let motivo = match run_server_hook("pre-receive", &[], &lineas(&todas), &r_path) {
+        Ok((acepto, salida)) => {
+            reporte += &remote_lines(&salida);
+            (!acepto).then(|| "pre-receive hook declined".to_string())
+        }
+        Err(e) => Some(format!("pre-receive hook failed: {}", e)),
+    };
+    if let Some(motivo) = motivo {
+        for name in names.iter() {
+            reporte += &pkt_line(&format!("ng {} {}\n", name, motivo));
+        }
+        return reporte + "0000";
+    }
+
+    // el motivo del rechazo de cada referencia, None si se acepto
+    let mut motivos: Vec<Option<String>> = vec![None; names.len()];
+    for i in todas.iter().copied() {
+        let args = [names[i].clone(), old[i].clone(), new[i].clone()];
+        match run_server_hook("update", &args, "", &r_path) {
+            Ok((acepto, salida)) => {
+                reporte += &remote_lines(&salida);
+                if !acepto {
+                    motivos[i] = Some("hook declined".to_string());
+                }
+            }
+            Err(e) => motivos[i] = Some(format!("update hook failed: {}", e)),
+        }
+    }
+    let aceptadas: Vec<usize> = todas
+        .into_iter()
+        .filter(|&i| motivos[i].is_none())
+        .collect();
+    let elegir = |v: &Vec<String>| aceptadas.iter().map(|&i| v[i].clone()).collect();
+    match update_refs(elegir(&old), elegir(&new), elegir(&names), r_path.clone()) {
+        Ok(()) if !aceptadas.is_empty() => {
+            // las refs ya se movieron, si post-receive no corre solo se avisa
+            match run_server_hook("post-receive", &[], &lineas(&aceptadas), &r_path) {
+                Ok((_, salida)) => reporte += &remote_lines(&salida),
+                Err(e) => reporte += &remote_lines(&format!("post-receive hook failed: {}", e)),
+            }
+        }
+        Ok(()) => {}
+        Err(e) => {
+            for &i in aceptadas.iter() {
+                motivos[i] = Some(format!("failed to update ref: {}", e));
+            }
+        }
+    }
+    for (name, motivo) in names.iter().zip(motivos) {
+        reporte += &match motivo {
+            None => pkt_line(&format!("ok {}\n", name)),
+            Some(motivo) => pkt_line(&format!("ng {} {}\n", name, motivo)),
+        };
+    }
+    reporte + "0000"
+}
+
 /// Obtiene los cambios que el cliente quiere hacer en el servidor.
 /// # Recibe
 /// * buffer: &[u8] con los datos recibidos del cliente en el ref update request
@@ -1120,6 +1258,7 @@ fn create_dirs(r_path: &str) -> std::io::Result<()> {
     fs::create_dir(p_str.clone() + "/refs/tags")?;
     fs::create_dir(p_str.clone() + "/objects")?;
     fs::create_dir(p_str.clone() + "/pulls")?;
+    fs::create_dir(p_str.clone() + "/hooks")?;
     Ok(())
 }
 
@@ -1314,6 +1453,166 @@ mod tests {
             "Chau mundo"
         );
     }
+
+    fn write_hook(r_path: &str, nombre: &str, script: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        let path = format!("{}/hooks/{}", r_path, nombre);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test08_update_refs_with_hooks() {
+        let r_path = "remote_repo_hooks";
+        let _ = remove_dir_all(r_path);
+        create_dirs(r_path).unwrap();
+        let nul_obj = "0000000000000000000000000000000000000000".to_string();
+        let hash = "74730d410fcb6603ace96f1dc55ea6196122532d".to_string();
+        let names = vec![
+            "refs/heads/master".to_string(),
+            "refs/heads/protegida".to_string(),
+        ];
+        let old = vec![nul_obj.clone(), nul_obj.clone()];
+        let new = vec![hash.clone(), hash.clone()];
+
+        // pre-receive rechaza todo y su salida llega al cliente
+        write_hook(r_path, "pre-receive", "cat\necho rechazado >&2\nexit 1");
+        let reporte =
+            update_refs_with_hooks(old.clone(), new.clone(), names.clone(), r_path.to_string());
+        assert_eq!(
+            reporte,
+            format!(
+                "0070remote: {nul_obj} {hash} refs/heads/master\n\
+                 0073remote: {nul_obj} {hash} refs/heads/protegida\n\
+                 0016remote: rechazado\n\
+                 0033ng refs/heads/master pre-receive hook declined\n\
+                 0036ng refs/heads/protegida pre-receive hook declined\n0000"
+            )
+        );
+        assert!(fs::metadata(format!("{}/refs/heads/master", r_path)).is_err());
+
+        // update rechaza solo la rama protegida, post-receive se entera de las aceptadas
+        fs::remove_file(format!("{}/hooks/pre-receive", r_path)).unwrap();
+        write_hook(r_path, "update", "test \"$1\" != refs/heads/protegida");
+        write_hook(r_path, "post-receive", "cat > recibidas");
+        let reporte = update_refs_with_hooks(old, new, names, r_path.to_string());
+        assert_eq!(
+            reporte,
+            "0019ok refs/heads/master\n002ang refs/heads/protegida hook declined\n0000"
+        );
+        assert_eq!(
+            fs::read_to_string(format!("{}/refs/heads/master", r_path)).unwrap(),
+            hash
+        );
+        assert!(fs::metadata(format!("{}/refs/heads/protegida", r_path)).is_err());
+        assert_eq!(
+            fs::read_to_string(format!("{}/recibidas", r_path)).unwrap(),
+            format!("{} {} refs/heads/master\n", nul_obj, hash)
+        );
+        remove_dir_all(r_path).unwrap();
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test09_hooks_que_rechazan_no_mueven_las_refs_y_el_cliente_ve_su_salida() {
+        let r_path = "remote_repo_hooks_rechazo";
+        let _ = remove_dir_all(r_path);
+        create_dirs(r_path).unwrap();
+        let viejo = "74730d410fcb6603ace96f1dc55ea6196122532d".to_string();
+        let nuevo = "5a3f6be755bbb7deae50065988cbfa1ffa9ab68a".to_string();
+        let master = format!("{}/refs/heads/master", r_path);
+        fs::write(&master, &viejo).unwrap();
+        let refs = vec!["refs/heads/master".to_string()];
+        let cambio = || {
+            update_refs_with_hooks(
+                vec![viejo.clone()],
+                vec![nuevo.clone()],
+                vec!["refs/heads/master".to_string()],
+                r_path.to_string(),
+            )
+        };
+
+        // pre-receive rechaza: la ref sigue igual y el cliente muestra la salida del hook
+        write_hook(r_path, "pre-receive", "echo no se aceptan pushes >&2\nexit 1");
+        let (salida, rechazadas) = command_utils::parse_push_report(&cambio(), &refs).unwrap();
+        assert_eq!(fs::read_to_string(&master).unwrap(), viejo);
+        assert_eq!(
+            salida,
+            "remote: no se aceptan pushes\n\
+             ! [remote rejected] refs/heads/master pre-receive hook declined\n"
+        );
+        assert_eq!(rechazadas, vec!["refs/heads/master".to_string()]);
+
+        // update rechaza: tampoco se mueve y post-receive no corre
+        fs::remove_file(format!("{}/hooks/pre-receive", r_path)).unwrap();
+        write_hook(r_path, "update", "echo \"$1 esta protegida\"\nexit 1");
+        write_hook(r_path, "post-receive", "touch corrio");
+        let (salida, rechazadas) = command_utils::parse_push_report(&cambio(), &refs).unwrap();
+        assert_eq!(fs::read_to_string(&master).unwrap(), viejo);
+        assert_eq!(
+            salida,
+            "remote: refs/heads/master esta protegida\n\
+             ! [remote rejected] refs/heads/master hook declined\n"
+        );
+        assert_eq!(rechazadas, vec!["refs/heads/master".to_string()]);
+        assert!(fs::metadata(format!("{}/corrio", r_path)).is_err());
+        remove_dir_all(r_path).unwrap();
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test10_hooks_que_no_se_pueden_ejecutar_rechazan_las_refs() {
+        use std::os::unix::fs::PermissionsExt;
+        let r_path = "remote_repo_hooks_rotos";
+        let _ = remove_dir_all(r_path);
+        create_dirs(r_path).unwrap();
+        let viejo = "74730d410fcb6603ace96f1dc55ea6196122532d".to_string();
+        let nuevo = "5a3f6be755bbb7deae50065988cbfa1ffa9ab68a".to_string();
+        let master = format!("{}/refs/heads/master", r_path);
+        fs::write(&master, &viejo).unwrap();
+        let refs = vec!["refs/heads/master".to_string()];
+        let cambio = || {
+            update_refs_with_hooks(
+                vec![viejo.clone()],
+                vec![nuevo.clone()],
+                refs.clone(),
+                r_path.to_string(),
+            )
+        };
+        // un hook ejecutable sin shebang no se puede ejecutar (ENOEXEC)
+        let romper = |nombre: &str| {
+            let path = format!("{}/hooks/{}", r_path, nombre);
+            fs::write(&path, "exit 0\n").unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        };
+
+        for hook in ["pre-receive", "update"] {
+            romper(hook);
+            let reporte = cambio();
+            let motivo = format!("ng refs/heads/master {} hook failed: ", hook);
+            assert!(reporte.contains(&motivo), "{}", reporte);
+            assert!(reporte.ends_with("\n0000"));
+            let (_, rechazadas) = command_utils::parse_push_report(&reporte, &refs).unwrap();
+            assert_eq!(rechazadas, refs);
+            assert_eq!(fs::read_to_string(&master).unwrap(), viejo);
+            fs::remove_file(format!("{}/hooks/{}", r_path, hook)).unwrap();
+        }
+
+        // si el que falla es post-receive la ref ya se movio y solo se avisa
+        romper("post-receive");
+        let reporte = cambio();
+        assert!(reporte.contains("remote: post-receive hook failed: "));
+        assert!(reporte.ends_with("0019ok refs/heads/master\n0000"));
+        assert_eq!(fs::read_to_string(&master).unwrap(), nuevo);
+
+        // un reporte vacio o sin el estado de alguna ref no es un push exitoso
+        assert!(command_utils::parse_push_report("", &refs).is_err());
+        assert!(command_utils::parse_push_report("0000", &refs).is_err());
+        assert!(command_utils::parse_push_report("0019ok refs/heads/otra\n0000", &refs).is_err());
+        assert!(command_utils::parse_push_report("", &[]).is_ok());
+        remove_dir_all(r_path).unwrap();
+    }
 }
 
 #[cfg(test)]
@@ -1350,7 +1649,7 @@ mod http_tests{
 
 
         commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
-        commands_fn::commit(vec!["-m".to_string(), "\"commit base\"".to_string()], "None".to_string(), cliente.clone()).unwrap();
+        commands_fn::commit(vec!["-m".to_string(), "commit base".to_string()], "None".to_string(), cliente.clone()).unwrap();
         
         commands_fn::checkout(vec!["-b".to_string(), "branch".to_string()], cliente.clone()).unwrap();
 
@@ -1360,7 +1659,7 @@ mod http_tests{
         ).unwrap();
 
         commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
-        commands_fn::commit(vec!["-m".to_string(), "\"commit branch\"".to_string()], "None".to_string(), cliente.clone()).unwrap();
+        commands_fn::commit(vec!["-m".to_string(), "commit branch".to_string()], "None".to_string(), cliente.clone()).unwrap();
         
         
         commands_fn::remote(vec!["localhost:9418/server_test".to_string()], cliente.clone()).unwrap();
@@ -1628,7 +1927,7 @@ mod merge_pr_tests{
 
 
         commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
-        commands_fn::commit(vec!["-m".to_string(), "\"commit base\"".to_string()], "None".to_string(), cliente.clone()).unwrap();
+        commands_fn::commit(vec!["-m".to_string(), "commit base".to_string()], "None".to_string(), cliente.clone()).unwrap();
         
         commands_fn::checkout(vec!["-b".to_string(), "branch".to_string()], cliente.clone()).unwrap();
 
@@ -1638,7 +1937,7 @@ mod merge_pr_tests{
         ).unwrap();
 
         commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
-        commands_fn::commit(vec!["-m".to_string(), "\"commit branch1\"".to_string()], "None".to_string(), cliente.clone()).unwrap();
+        commands_fn::commit(vec!["-m".to_string(), "commit branch1".to_string()], "None".to_string(), cliente.clone()).unwrap();
         
         commands_fn::checkout(vec!["master".to_string()], cliente.clone()).unwrap();
         file_manager::write_file(
@@ -1646,7 +1945,7 @@ mod merge_pr_tests{
             "cambio en master 1\n".to_string(),
         ).unwrap();
         commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
-        commands_fn::commit(vec!["-m".to_string(), "\"commit master1\"".to_string()], "None".to_string(), cliente.clone()).unwrap();
+        commands_fn::commit(vec!["-m".to_string(), "commit master1".to_string()], "None".to_string(), cliente.clone()).unwrap();
         
         commands_fn::remote(vec!["localhost:9418/server_test".to_string()], cliente.clone()).unwrap();
 
@@ -1731,7 +2030,7 @@ mod merge_pr_tests{
         ).unwrap();
 
         commands_fn::add(vec![".".to_string()], "cliente".to_string()).unwrap();
-        commands_fn::commit(vec!["-m".to_string(), "\"commit para no conflict\"".to_string()], "None".to_string(), "cliente".to_string()).unwrap();
+        commands_fn::commit(vec!["-m".to_string(), "commit para no conflict".to_string()], "None".to_string(), "cliente".to_string()).unwrap();
         commands_fn::push(vec![], "cliente".to_string()).unwrap();
         
         let child = Command::new("curl")
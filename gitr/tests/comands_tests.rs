@@ -1,12 +1,14 @@
 use gitr::commands::command_utils;
 use gitr::commands::command_utils::*;
-use std::{fs, path::Path};
+use std::{fs, io::Read, path::Path};
 
 use gitr::commands::commands_fn;
+use gitr::commands::handler::{command_handler, parse_input};
 use gitr::file_manager;
 use gitr::file_manager::*;
 use gitr::gitr_errors::GitrError;
 use gitr::objects::blob::Blob;
+use gitr::objects::tree::{get_formated_hash, Tree};
 use serial_test::serial;
 
 /*********************
@@ -185,7 +187,7 @@ fn test_tag_lightweight() {
     commands_fn::add(vec!["blob1".to_string()], cliente.clone()).unwrap();
     commands_fn::add(vec!["blob2".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"commit 1\"".to_string()],
+        vec!["-m".to_string(), "commit 1".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -220,7 +222,7 @@ fn test_tag_delete() {
     commands_fn::add(vec!["blob1".to_string()], cliente.clone()).unwrap();
     commands_fn::add(vec!["blob2".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"commit 1\"".to_string()],
+        vec!["-m".to_string(), "commit 1".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -257,7 +259,7 @@ fn test_tag_annotated() {
     commands_fn::add(vec!["blob1".to_string()], cliente.clone()).unwrap();
     commands_fn::add(vec!["blob2".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"commit 1\"".to_string()],
+        vec!["-m".to_string(), "commit 1".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -267,7 +269,7 @@ fn test_tag_annotated() {
             "-a".to_string(),
             "tag1".to_string(),
             "-m".to_string(),
-            "\"un tag anotado\"".to_string(),
+            "un tag anotado".to_string(),
         ],
         cliente.clone(),
     )
@@ -309,7 +311,7 @@ fn test_branch_newbranch() {
     commands_fn::add(vec!["blob1".to_string()], cliente.clone()).unwrap();
     commands_fn::add(vec!["blob2".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"commit 1\"".to_string()],
+        vec!["-m".to_string(), "commit 1".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -360,7 +362,7 @@ fn test_branch_already_exists() {
     commands_fn::add(vec!["blob1".to_string()], cliente.clone()).unwrap();
     commands_fn::add(vec!["blob2".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"commit 1\"".to_string()],
+        vec!["-m".to_string(), "commit 1".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -392,7 +394,7 @@ fn test_branch_delete() {
     commands_fn::add(vec!["blob1".to_string()], cliente.clone()).unwrap();
     commands_fn::add(vec!["blob2".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"commit 1\"".to_string()],
+        vec!["-m".to_string(), "commit 1".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -437,7 +439,7 @@ fn test_branch_delete_current() {
     commands_fn::add(vec!["blob1".to_string()], cliente.clone()).unwrap();
     commands_fn::add(vec!["blob2".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"commit 1\"".to_string()],
+        vec!["-m".to_string(), "commit 1".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -472,7 +474,7 @@ fn test_branch_move() {
     commands_fn::add(vec!["blob1".to_string()], cliente.clone()).unwrap();
     commands_fn::add(vec!["blob2".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"commit 1\"".to_string()],
+        vec!["-m".to_string(), "commit 1".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -613,7 +615,7 @@ fn merge_con_archivos_test_1_conflict_trivial() {
     file_manager::write_file("cliente/test/archivo1.txt".to_string(), base).unwrap();
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"base\"".to_string()],
+        vec!["-m".to_string(), "base".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -627,7 +629,7 @@ fn merge_con_archivos_test_1_conflict_trivial() {
     file_manager::write_file("cliente/test/archivo1.txt".to_string(), branch).unwrap();
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"branch\"".to_string()],
+        vec!["-m".to_string(), "branch".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -637,7 +639,7 @@ fn merge_con_archivos_test_1_conflict_trivial() {
     file_manager::write_file("cliente/test/archivo1.txt".to_string(), origin).unwrap();
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"origin\"".to_string()],
+        vec!["-m".to_string(), "origin".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -726,7 +728,7 @@ fn merge_con_archivos_test_3_conflict_multilinea() {
     file_manager::write_file("cliente/test/archivo1.txt".to_string(), base).unwrap();
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"base\"".to_string()],
+        vec!["-m".to_string(), "base".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -740,7 +742,7 @@ fn merge_con_archivos_test_3_conflict_multilinea() {
     file_manager::write_file("cliente/test/archivo1.txt".to_string(), branch).unwrap();
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"branch\"".to_string()],
+        vec!["-m".to_string(), "branch".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -750,7 +752,7 @@ fn merge_con_archivos_test_3_conflict_multilinea() {
     file_manager::write_file("cliente/test/archivo1.txt".to_string(), origin).unwrap();
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"origin\"".to_string()],
+        vec!["-m".to_string(), "origin".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -845,7 +847,7 @@ fn merge_con_archivos_test_4_multiples_conflicts_multilinea() {
     file_manager::write_file("cliente/test/archivo1.txt".to_string(), base).unwrap();
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"base\"".to_string()],
+        vec!["-m".to_string(), "base".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -859,7 +861,7 @@ fn merge_con_archivos_test_4_multiples_conflicts_multilinea() {
     file_manager::write_file("cliente/test/archivo1.txt".to_string(), branch).unwrap();
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"branch\"".to_string()],
+        vec!["-m".to_string(), "branch".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -869,7 +871,7 @@ fn merge_con_archivos_test_4_multiples_conflicts_multilinea() {
     file_manager::write_file("cliente/test/archivo1.txt".to_string(), origin).unwrap();
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"origin\"".to_string()],
+        vec!["-m".to_string(), "origin".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -965,7 +967,7 @@ fn merge_con_archivos_test_5_ejemplo_de_codigo() {
     file_manager::write_file("cliente/test/archivo1.txt".to_string(), base).unwrap();
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"base\"".to_string()],
+        vec!["-m".to_string(), "base".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -979,7 +981,7 @@ fn merge_con_archivos_test_5_ejemplo_de_codigo() {
     file_manager::write_file("cliente/test/archivo1.txt".to_string(), branch).unwrap();
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"branch\"".to_string()],
+        vec!["-m".to_string(), "branch".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -989,7 +991,7 @@ fn merge_con_archivos_test_5_ejemplo_de_codigo() {
     file_manager::write_file("cliente/test/archivo1.txt".to_string(), origin).unwrap();
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"origin\"".to_string()],
+        vec!["-m".to_string(), "origin".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -1052,7 +1054,7 @@ fn test_commit() {
     );
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"commit 1\"".to_string()],
+        vec!["-m".to_string(), "commit 1".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -1083,7 +1085,7 @@ fn test_checkout() {
     );
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"commit 1\"".to_string()],
+        vec!["-m".to_string(), "commit 1".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -1096,7 +1098,7 @@ fn test_checkout() {
     );
     commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"commit 2\"".to_string()],
+        vec!["-m".to_string(), "commit 2".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -1185,7 +1187,7 @@ fn test_status_new_file_modified() {
     );
     commands_fn::add(vec!["blob1".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"commit 1\"".to_string()],
+        vec!["-m".to_string(), "commit 1".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -1219,7 +1221,7 @@ fn test_ls_tree_sin_flags_se_comporta_como_cat_file() {
     .unwrap();
     commands_fn::add(vec!["blob1".to_string()], cliente.clone()).unwrap();
     commands_fn::commit(
-        vec!["-m".to_string(), "\"commit 1\"".to_string()],
+        vec!["-m".to_string(), "commit 1".to_string()],
         "None".to_string(),
         cliente.clone(),
     )
@@ -1239,3 +1241,2328 @@ fn test_ls_tree_sin_flags_se_comporta_como_cat_file() {
 
     assert_eq!(res, cat_file);
 }
+
+// /*********************
+//   REBASE TESTS
+// *********************/
+fn commit_file(cliente: String, path: &str, data: &str, message: &str) {
+    file_manager::write_file(path.to_string(), data.to_string()).unwrap();
+    commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
+    commands_fn::commit(
+        vec!["-m".to_string(), format!("{}", message)],
+        "None".to_string(),
+        cliente,
+    )
+    .unwrap();
+}
+
+fn setup_rebase_conflict() -> String {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    let archivo = "cliente/test/archivo1.txt";
+    commit_file(cliente.clone(), archivo, "hola\ncomo\nestas\n", "base");
+    commands_fn::checkout(
+        vec!["-b".to_string(), "branch".to_string()],
+        cliente.clone(),
+    )
+    .unwrap();
+    commit_file(cliente.clone(), archivo, "hola\nbranch\nestas\n", "branch");
+    commands_fn::checkout(vec!["master".to_string()], cliente.clone()).unwrap();
+    commit_file(cliente.clone(), archivo, "hola\nmaster\nestas\n", "master");
+    commands_fn::checkout(vec!["branch".to_string()], cliente.clone()).unwrap();
+    cliente
+}
+
+#[test]
+#[serial]
+fn test_rebase_con_conflict_se_detiene_y_abort_restaura_orig_head() {
+    let cliente = setup_rebase_conflict();
+    let orig_head = file_manager::get_current_commit(cliente.clone()).unwrap();
+
+    commands_fn::rebase(vec!["master".to_string()], cliente.clone()).unwrap();
+
+    assert!(Path::new("cliente/test/gitr/rebase-merge").is_dir());
+    let archivo = file_manager::read_file("cliente/test/archivo1.txt".to_string()).unwrap();
+    assert!(archivo.contains("<<<<<<< HEAD"));
+    let res = commands_fn::rebase(vec!["master".to_string()], cliente.clone());
+    assert_eq!(res.unwrap_err(), GitrError::RebaseInProgress);
+
+    commands_fn::rebase(vec!["--abort".to_string()], cliente.clone()).unwrap();
+
+    assert!(!Path::new("cliente/test/gitr/rebase-merge").exists());
+    assert_eq!(
+        file_manager::get_current_commit(cliente.clone()).unwrap(),
+        orig_head
+    );
+    assert_eq!(
+        file_manager::read_file("cliente/test/archivo1.txt".to_string()).unwrap(),
+        "hola\nbranch\nestas\n"
+    );
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_rebase_continue_con_conflicts_sin_resolver_falla() {
+    let cliente = setup_rebase_conflict();
+    commands_fn::rebase(vec!["master".to_string()], cliente.clone()).unwrap();
+
+    let res = commands_fn::rebase(vec!["--continue".to_string()], cliente.clone());
+
+    assert!(matches!(res, Err(GitrError::UnresolvedConflicts(_))));
+    assert!(Path::new("cliente/test/gitr/rebase-merge").is_dir());
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_rebase_continue_crea_el_commit_sobre_la_branch_destino() {
+    let cliente = setup_rebase_conflict();
+    let master_commit = file_manager::get_commit("master".to_string(), cliente.clone()).unwrap();
+    commands_fn::rebase(vec!["master".to_string()], cliente.clone()).unwrap();
+    file_manager::write_file(
+        "cliente/test/archivo1.txt".to_string(),
+        "hola\nresuelto\nestas\n".to_string(),
+    )
+    .unwrap();
+
+    commands_fn::rebase(vec!["--continue".to_string()], cliente.clone()).unwrap();
+
+    assert!(!Path::new("cliente/test/gitr/rebase-merge").exists());
+    let new_commit = file_manager::get_current_commit(cliente.clone()).unwrap();
+    let parents = file_manager::get_parent_commit(new_commit.clone(), cliente.clone()).unwrap();
+    assert_eq!(parents, vec![master_commit]);
+    assert_eq!(
+        file_manager::get_commit_message(new_commit, cliente.clone()).unwrap(),
+        "branch\n"
+    );
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_rebase_skip_descarta_el_commit_en_conflicto() {
+    let cliente = setup_rebase_conflict();
+    let master_commit = file_manager::get_commit("master".to_string(), cliente.clone()).unwrap();
+    commands_fn::rebase(vec!["master".to_string()], cliente.clone()).unwrap();
+
+    commands_fn::rebase(vec!["--skip".to_string()], cliente.clone()).unwrap();
+
+    assert!(!Path::new("cliente/test/gitr/rebase-merge").exists());
+    assert_eq!(
+        file_manager::get_current_commit(cliente.clone()).unwrap(),
+        master_commit
+    );
+    assert_eq!(
+        file_manager::read_file("cliente/test/archivo1.txt".to_string()).unwrap(),
+        "hola\nmaster\nestas\n"
+    );
+    delete_repo("cliente/test".to_string());
+}
+
+// /*********************
+//   DIFF TESTS
+// *********************/
+#[test]
+#[serial]
+fn test_diff_working_tree_muestra_hunk_contra_el_index() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo1.txt",
+        "hola\ncomo\nestas\n",
+        "base",
+    );
+    file_manager::write_file(
+        "cliente/test/archivo1.txt".to_string(),
+        "hola\nque\nestas\n".to_string(),
+    )
+    .unwrap();
+
+    let res = command_utils::get_diff(vec!["--no-color".to_string()], cliente.clone()).unwrap();
+
+    let lineas = res.lines().collect::<Vec<&str>>();
+    assert_eq!(lineas[0], "diff --git a/archivo1.txt b/archivo1.txt");
+    assert!(lineas[1].starts_with("index ") && lineas[1].ends_with(" 100644"));
+    assert_eq!(
+        lineas[2..].to_vec(),
+        vec![
+            "--- a/archivo1.txt",
+            "+++ b/archivo1.txt",
+            "@@ -1,3 +1,3 @@",
+            " hola",
+            "-como",
+            "+que",
+            " estas",
+        ]
+    );
+    let res = command_utils::get_diff(
+        vec!["--no-color".to_string(), "-U0".to_string()],
+        cliente.clone(),
+    )
+    .unwrap();
+    assert!(res.contains("@@ -2 +2 @@\n-como\n+que\n"));
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_diff_cached_muestra_archivos_nuevos_en_el_index() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo1.txt",
+        "hola\n",
+        "base",
+    );
+    file_manager::write_file("cliente/test/nuevo.txt".to_string(), "nuevo\n".to_string()).unwrap();
+    commands_fn::add(vec!["nuevo.txt".to_string()], cliente.clone()).unwrap();
+
+    let sin_cached = command_utils::get_diff(vec![], cliente.clone()).unwrap();
+    let res = command_utils::get_diff(
+        vec!["--cached".to_string(), "--no-color".to_string()],
+        cliente.clone(),
+    )
+    .unwrap();
+
+    assert!(sin_cached.is_empty());
+    assert!(res.starts_with("diff --git a/nuevo.txt b/nuevo.txt\nnew file mode 100644\n"));
+    assert!(res.contains("--- /dev/null\n+++ b/nuevo.txt\n@@ -0,0 +1 @@\n+nuevo\n"));
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_diff_entre_commits_filtrado_por_path() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo1.txt",
+        "uno\n",
+        "primero",
+    );
+    let primero = file_manager::get_current_commit(cliente.clone()).unwrap();
+    file_manager::write_file("cliente/test/archivo2.txt".to_string(), "dos\n".to_string()).unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo1.txt",
+        "uno\nmas\n",
+        "segundo",
+    );
+
+    let todo = command_utils::get_diff(
+        vec![
+            "--no-color".to_string(),
+            primero.clone(),
+            "HEAD".to_string(),
+        ],
+        cliente.clone(),
+    )
+    .unwrap();
+    let filtrado = command_utils::get_diff(
+        vec![
+            "--no-color".to_string(),
+            primero[..7].to_string(),
+            "master".to_string(),
+            "--".to_string(),
+            "archivo1.txt".to_string(),
+        ],
+        cliente.clone(),
+    )
+    .unwrap();
+
+    assert!(todo.contains("diff --git a/archivo1.txt b/archivo1.txt"));
+    assert!(todo.contains("diff --git a/archivo2.txt b/archivo2.txt"));
+    assert!(filtrado.contains(" uno\n+mas\n"));
+    assert!(!filtrado.contains("archivo2.txt"));
+    assert_eq!(
+        command_utils::get_diff(vec!["HEAD~1..HEAD".to_string()], cliente.clone()).unwrap(),
+        command_utils::get_diff(vec![primero, "HEAD".to_string()], cliente.clone()).unwrap()
+    );
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_diff_stat_numstat_y_name_status() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    file_manager::write_file("cliente/test/borrado.txt".to_string(), "chau\n".to_string()).unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo1.txt",
+        "uno\ndos\n",
+        "primero",
+    );
+    let primero = file_manager::get_current_commit(cliente.clone()).unwrap();
+    commands_fn::rm(vec!["borrado.txt".to_string()], cliente.clone()).unwrap();
+    file_manager::remove_file("cliente/test/borrado.txt".to_string()).unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo1.txt",
+        "uno\ntres\ncuatro\n",
+        "segundo",
+    );
+    let diff = |flag: &str| {
+        command_utils::get_diff(
+            vec![
+                "--no-color".to_string(),
+                flag.to_string(),
+                primero.clone(),
+                "HEAD".to_string(),
+            ],
+            cliente.clone(),
+        )
+        .unwrap()
+    };
+
+    assert_eq!(diff("--numstat"), "2\t1\tarchivo1.txt\n0\t1\tborrado.txt\n");
+    assert_eq!(diff("--name-only"), "archivo1.txt\nborrado.txt\n");
+    assert_eq!(diff("--name-status"), "M\tarchivo1.txt\nD\tborrado.txt\n");
+    assert_eq!(
+        diff("--shortstat"),
+        " 2 files changed, 2 insertions(+), 2 deletions(-)\n"
+    );
+    assert_eq!(
+        diff("--stat"),
+        [
+            " archivo1.txt |   3 ++-\n",
+            " borrado.txt  |   1 -\n",
+            " 2 files changed, 2 insertions(+), 2 deletions(-)\n",
+        ]
+        .concat()
+    );
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_diff_ignorando_espacios() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo1.txt",
+        "a b\nc\n",
+        "base",
+    );
+    file_manager::write_file(
+        "cliente/test/archivo1.txt".to_string(),
+        "a   b\n\nc\n".to_string(),
+    )
+    .unwrap();
+    let diff = |flags: Vec<&str>| {
+        command_utils::get_diff(
+            flags.iter().map(|f| f.to_string()).collect(),
+            cliente.clone(),
+        )
+        .unwrap()
+    };
+
+    assert_eq!(diff(vec!["--numstat"]), "2\t1\tarchivo1.txt\n");
+    assert_eq!(diff(vec!["--numstat", "-b"]), "1\t0\tarchivo1.txt\n");
+    assert_eq!(diff(vec!["--numstat", "-w", "--ignore-blank-lines"]), "");
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_diff_status_y_log_detectan_renames() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    let contenido = "uno\ndos\ntres\ncuatro\ncinco\n";
+    commit_file(cliente.clone(), "cliente/test/viejo.txt", contenido, "base");
+    file_manager::remove_file("cliente/test/viejo.txt".to_string()).unwrap();
+    file_manager::write_file("cliente/test/nuevo.txt".to_string(), contenido.to_string()).unwrap();
+    commands_fn::add(vec![".".to_string()], cliente.clone()).unwrap();
+
+    let diff = |flags: Vec<&str>| {
+        command_utils::get_diff(
+            flags.iter().map(|f| f.to_string()).collect(),
+            cliente.clone(),
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        diff(vec!["--cached", "--name-status"]),
+        "R100\tviejo.txt\tnuevo.txt\n"
+    );
+    assert_eq!(
+        diff(vec!["--cached", "--name-status", "--no-renames"]),
+        "A\tnuevo.txt\nD\tviejo.txt\n"
+    );
+    let status = command_utils::get_status(cliente.clone()).unwrap();
+    assert!(status.contains("renamed:    test/viejo.txt -> test/nuevo.txt"));
+    assert!(!status.contains("new file:"));
+
+    commands_fn::commit(
+        vec!["-m".to_string(), "rename".to_string()],
+        "None".to_string(),
+        cliente.clone(),
+    )
+    .unwrap();
+    let log = command_utils::get_log(
+        vec![
+            "-n".to_string(),
+            "1".to_string(),
+            "--name-status".to_string(),
+        ],
+        cliente.clone(),
+    )
+    .unwrap();
+    assert!(log.contains("\trename\n"));
+    assert!(log.ends_with("R100\tviejo.txt\tnuevo.txt\n\n"));
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_merge_lleva_los_cambios_al_archivo_renombrado() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/viejo.txt",
+        "uno\ndos\ntres\ncuatro\ncinco\nseis\n",
+        "base",
+    );
+
+    commands_fn::checkout(
+        vec!["-b".to_string(), "branch".to_string()],
+        cliente.clone(),
+    )
+    .unwrap();
+    file_manager::remove_file("cliente/test/viejo.txt".to_string()).unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/nuevo.txt",
+        "uno\ndos\ntres\ncuatro\ncinco\nseis\n",
+        "rename",
+    );
+
+    commands_fn::checkout(vec!["master".to_string()], cliente.clone()).unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/viejo.txt",
+        "uno\ndos\nTRES\ncuatro\ncinco\nseis\n",
+        "origin",
+    );
+
+    let (hubo_conflict, _, _) =
+        commands_fn::merge(vec!["branch".to_string()], cliente.clone()).unwrap();
+
+    assert!(!hubo_conflict);
+    assert!(!Path::new("cliente/test/viejo.txt").exists());
+    assert_eq!(
+        file_manager::read_file("cliente/test/nuevo.txt".to_string()).unwrap(),
+        "uno\ndos\nTRES\ncuatro\ncinco\nseis\n"
+    );
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_merge_no_pierde_un_conflicto_si_despues_otro_archivo_mergea_bien() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    file_manager::write_file(
+        "cliente/test/otro.txt".to_string(),
+        "a\nb\nc\nd\ne\nf\n".to_string(),
+    )
+    .unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/viejo.txt",
+        "uno\ndos\ntres\ncuatro\ncinco\nseis\n",
+        "base",
+    );
+
+    commands_fn::checkout(flags(&["-b", "branch"]), cliente.clone()).unwrap();
+    file_manager::remove_file("cliente/test/viejo.txt".to_string()).unwrap();
+    file_manager::write_file(
+        "cliente/test/otro.txt".to_string(),
+        "A\nb\nc\nd\ne\nf\n".to_string(),
+    )
+    .unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/nuevo.txt",
+        "uno\ndos\nTRES\ncuatro\ncinco\nseis\n",
+        "rename",
+    );
+
+    commands_fn::checkout(flags(&["master"]), cliente.clone()).unwrap();
+    file_manager::write_file(
+        "cliente/test/otro.txt".to_string(),
+        "a\nb\nc\nd\ne\nF\n".to_string(),
+    )
+    .unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/viejo.txt",
+        "uno\ndos\nTres\ncuatro\ncinco\nseis\n",
+        "origin",
+    );
+
+    let (hubo_conflict, _, _) = commands_fn::merge(flags(&["branch"]), cliente.clone()).unwrap();
+    assert!(hubo_conflict);
+    assert_eq!(
+        file_manager::read_file("cliente/test/otro.txt".to_string()).unwrap(),
+        "A\nb\nc\nd\ne\nF\n"
+    );
+    delete_repo("cliente/test".to_string());
+}
+
+/*********************
+//   LOG TESTS
+// *********************/
+
+fn get_log(flags: Vec<&str>) -> String {
+    command_utils::get_log(
+        flags.iter().map(|f| f.to_string()).collect(),
+        "cliente".to_string(),
+    )
+    .unwrap()
+}
+
+#[test]
+#[serial]
+fn test_log_graph_recorre_las_dos_ramas_del_merge() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(cliente.clone(), "cliente/test/base.txt", "base\n", "base");
+    commands_fn::checkout(
+        vec!["-b".to_string(), "branch".to_string()],
+        cliente.clone(),
+    )
+    .unwrap();
+    commit_file(cliente.clone(), "cliente/test/b.txt", "b\n", "commit_b");
+    commands_fn::checkout(vec!["master".to_string()], cliente.clone()).unwrap();
+    commit_file(cliente.clone(), "cliente/test/a.txt", "a\n", "commit_a");
+    commands_fn::merge(vec!["branch".to_string()], cliente.clone()).unwrap();
+
+    assert_eq!(
+        get_log(vec!["--graph", "--pretty=format:%s"]),
+        [
+            "*   Merge branch 'branch'\n",
+            "|\\\n",
+            "* | commit_a\n",
+            "| * commit_b\n",
+            "|/\n",
+            "* base\n",
+        ]
+        .concat()
+    );
+    let oneline = get_log(vec!["--oneline"]);
+    assert_eq!(oneline.lines().count(), 4);
+    assert!(oneline.contains(" commit_b\n"));
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_log_filtra_por_path_autor_mensaje_y_fecha() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(cliente.clone(), "cliente/test/uno.txt", "1\n", "agrega_uno");
+    commit_file(cliente.clone(), "cliente/test/dos.txt", "2\n", "agrega_dos");
+    commit_file(
+        cliente.clone(),
+        "cliente/test/uno.txt",
+        "11\n",
+        "cambia_uno",
+    );
+
+    assert_eq!(
+        get_log(vec!["--format=%s", "--", "uno.txt"]),
+        "cambia_uno\nagrega_uno\n"
+    );
+    assert_eq!(get_log(vec!["--format=%s", "--grep=dos"]), "agrega_dos\n");
+    assert_eq!(
+        get_log(vec!["--format=%s", "--grep=^(agrega|cambia)_uno"]),
+        "cambia_uno\nagrega_uno\n"
+    );
+    assert_eq!(get_log(vec!["--format=%s", "--grep=a.*_d"]), "agrega_dos\n");
+    assert_eq!(get_log(vec!["--format=%s", "-n", "1"]), "cambia_uno\n");
+    assert_eq!(
+        get_log(vec!["--format=%an", "--author", "cliente", "-n", "1"]),
+        "cliente\n"
+    );
+    assert_eq!(get_log(vec!["--author=otro"]), "");
+    assert_eq!(
+        get_log(vec!["--format=%s", "--author=^cli", "-n", "1"]),
+        "cambia_uno\n"
+    );
+    assert!(command_utils::get_log(flags(&["--grep=(sin_cerrar"]), cliente.clone()).is_err());
+    assert_eq!(get_log(vec!["--since=2999-01-01"]), "");
+    assert_eq!(get_log(vec!["--until=2000-01-01"]), "");
+    assert_eq!(
+        get_log(vec!["--format=%s", "HEAD~1"]),
+        "agrega_dos\nagrega_uno\n"
+    );
+    assert!(command_utils::get_log(vec!["--desconocido".to_string()], cliente).is_err());
+    delete_repo("cliente/test".to_string());
+}
+
+/*********************
+//   SHOW TESTS
+// *********************/
+
+fn get_show(flags: Vec<&str>) -> String {
+    command_utils::get_show(
+        flags.iter().map(|f| f.to_string()).collect(),
+        "cliente".to_string(),
+    )
+    .unwrap()
+}
+
+#[test]
+#[serial]
+fn test_show_commit_tag_tree_y_archivo_en_revision() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo1.txt",
+        "hola\n",
+        "base",
+    );
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo1.txt",
+        "chau señor\n",
+        "cambio",
+    );
+    commands_fn::tag(
+        vec![
+            "-a".to_string(),
+            "v1".to_string(),
+            "-m".to_string(),
+            "version uno".to_string(),
+        ],
+        cliente.clone(),
+    )
+    .unwrap();
+
+    let show = get_show(vec!["--no-color"]);
+    assert!(show.contains("\tcambio\n"));
+    assert!(show.ends_with("@@ -1 +1 @@\n-hola\n+chau señor\n"));
+
+    assert_eq!(get_show(vec!["HEAD~1:archivo1.txt"]), "hola\n");
+    assert_eq!(get_show(vec!["HEAD:archivo1.txt"]), "chau señor\n");
+    let tag = get_show(vec!["--no-patch", "v1"]);
+    assert!(tag.starts_with("tag v1\nTagger: "));
+    assert!(tag.contains("\tcambio\n"));
+    let tree = get_show(vec!["HEAD:"]);
+    assert!(tree.starts_with("100644 blob ") && tree.ends_with(" archivo1.txt\n"));
+
+    // el tipo de cada entrada sale del objeto y no del modo
+    let blob = resolve_object("HEAD:archivo1.txt".to_string(), cliente.clone()).unwrap();
+    let vacio = Tree::new_from_packfile(&[]).unwrap();
+    vacio.save(cliente.clone()).unwrap();
+    assert_eq!(get_show(vec![&vacio.get_hash()]), "");
+    let entradas = [
+        b"100755 correr.sh\0".to_vec(),
+        get_formated_hash(blob.clone(), "").unwrap(),
+        b"40000 vacio\0".to_vec(),
+        get_formated_hash(vacio.get_hash(), "").unwrap(),
+    ]
+    .concat();
+    let tree = Tree::new_from_packfile(&entradas).unwrap();
+    tree.save(cliente.clone()).unwrap();
+    assert_eq!(
+        get_show(vec![&tree.get_hash()]),
+        format!(
+            "100755 blob {} correr.sh\n40000 tree {} vacio\n",
+            blob,
+            vacio.get_hash()
+        )
+    );
+    assert!(command_utils::get_show(vec!["HEAD:no_existe".to_string()], cliente).is_err());
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_show_merge_muestra_diff_combinado() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    let base = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n";
+    commit_file(cliente.clone(), "cliente/test/archivo1.txt", base, "base");
+    commands_fn::checkout(
+        vec!["-b".to_string(), "branch".to_string()],
+        cliente.clone(),
+    )
+    .unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo1.txt",
+        &base.replace("10\n", "diez\n"),
+        "branch",
+    );
+    commands_fn::checkout(vec!["master".to_string()], cliente.clone()).unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo1.txt",
+        &base.replacen("1\n", "uno\n", 1),
+        "origin",
+    );
+    commands_fn::merge(vec!["branch".to_string()], cliente.clone()).unwrap();
+
+    let show = get_show(vec!["--no-color", "-U1"]);
+    let diff = &show[show.find("diff --combined").unwrap()..];
+    let lineas = diff.lines().collect::<Vec<&str>>();
+    assert_eq!(lineas[0], "diff --combined archivo1.txt");
+    assert!(lineas[1].starts_with("index ") && lineas[1].contains(','));
+    assert_eq!(
+        lineas[4..].to_vec(),
+        vec![
+            "@@@ -1,2 -1,2 +1,2 @@@",
+            " -1",
+            " +uno",
+            "  2",
+            "@@@ -9,2 -9,2 +9,2 @@@",
+            "  9",
+            "- 10",
+            "+ diez",
+        ]
+    );
+    delete_repo("cliente/test".to_string());
+}
+
+/*********************
+//   BLAME TESTS
+// *********************/
+
+#[test]
+#[serial]
+fn test_blame_atribuye_cada_linea_al_ultimo_commit_que_la_cambio() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo1.txt",
+        "uno\ndos\ntres\n",
+        "base",
+    );
+    let base = command_utils::resolve_commit("HEAD".to_string(), cliente.clone()).unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo1.txt",
+        "uno\nDOS señor\ntres\ncuatro\n",
+        "cambio",
+    );
+    let cambio = command_utils::resolve_commit("HEAD".to_string(), cliente.clone()).unwrap();
+    commit_file(cliente.clone(), "cliente/test/otro.txt", "otro\n", "otro");
+
+    let lineas =
+        command_utils::blame_lines("archivo1.txt", cambio.clone(), cliente.clone()).unwrap();
+    assert_eq!(
+        lineas,
+        vec![
+            (base.clone(), "uno".to_string()),
+            (cambio.clone(), "DOS señor".to_string()),
+            (base.clone(), "tres".to_string()),
+            (cambio.clone(), "cuatro".to_string()),
+        ]
+    );
+
+    let blame =
+        command_utils::get_blame(vec!["archivo1.txt".to_string()], cliente.clone()).unwrap();
+    let primera = blame.lines().next().unwrap();
+    assert!(primera.starts_with(&format!("{} (cliente ", &base[..7])));
+    assert!(primera.ends_with(" 1) uno"));
+    assert!(blame.lines().nth(1).unwrap().ends_with(" 2) DOS señor"));
+    let blame_base = command_utils::get_blame(
+        vec!["archivo1.txt".to_string(), "HEAD~2".to_string()],
+        cliente.clone(),
+    )
+    .unwrap();
+    assert_eq!(blame_base.lines().count(), 3);
+    assert!(command_utils::get_blame(vec!["no_existe.txt".to_string()], cliente).is_err());
+    delete_repo("cliente/test".to_string());
+}
+
+/*********************
+//   BISECT TESTS
+// *********************/
+
+// repo con 6 commits donde el archivo estado pasa de "ok" a "roto" en el cuarto
+fn setup_bisect() -> Vec<String> {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    let mut commits = vec![];
+    for i in 1..=6 {
+        let estado = if i < 4 { "ok\n" } else { "roto\n" };
+        file_manager::write_file("cliente/test/numero".to_string(), format!("{}\n", i)).unwrap();
+        commit_file(
+            cliente.clone(),
+            "cliente/test/estado",
+            estado,
+            &format!("commit_{}", i),
+        );
+        commits.push(command_utils::resolve_commit("HEAD".to_string(), cliente.clone()).unwrap());
+    }
+    commits
+}
+
+#[test]
+#[serial]
+fn test_bisect_encuentra_el_primer_commit_malo() {
+    let commits = setup_bisect();
+    let cliente = "cliente".to_string();
+    let mut salida = command_utils::bisect_start(
+        vec!["HEAD".to_string(), commits[0].clone()],
+        cliente.clone(),
+    )
+    .unwrap();
+    assert!(salida.starts_with("Bisecting: "));
+    assert!(Path::new("cliente/test/gitr/BISECT_START").is_file());
+
+    let mut pasos = 0;
+    while salida.starts_with("Bisecting: ") {
+        pasos += 1;
+        let estado = file_manager::read_file("cliente/test/estado".to_string()).unwrap();
+        let term = if estado == "ok\n" { "good" } else { "bad" };
+        salida = command_utils::bisect_mark(term, vec![], cliente.clone()).unwrap();
+    }
+    assert!(pasos <= 3);
+    assert!(salida.starts_with(&format!("{} is the first bad commit", commits[3])));
+    let log = command_utils::bisect_log(cliente.clone()).unwrap();
+    assert!(log.starts_with("bisect start\n# bad: "));
+    assert!(log.contains(&format!("# first bad commit: [{}] commit_4", commits[3])));
+
+    command_utils::bisect_reset(cliente.clone()).unwrap();
+    assert!(!Path::new("cliente/test/gitr/BISECT_START").exists());
+    assert_eq!(
+        file_manager::get_head(cliente.clone()).unwrap(),
+        "refs/heads/master"
+    );
+    assert_eq!(
+        file_manager::read_file("cliente/test/numero".to_string()).unwrap(),
+        "6\n"
+    );
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_bisect_run_y_skip() {
+    let commits = setup_bisect();
+    let cliente = "cliente".to_string();
+    command_utils::bisect_start(vec![], cliente.clone()).unwrap();
+    assert_eq!(
+        command_utils::bisect_mark("bad", vec![], cliente.clone()).unwrap(),
+        "status: waiting for good commit(s), bad commit known\n"
+    );
+    command_utils::bisect_mark("good", vec![commits[0].clone()], cliente.clone()).unwrap();
+
+    let salida = command_utils::bisect_run(
+        vec![
+            "grep".to_string(),
+            "-q".to_string(),
+            "ok".to_string(),
+            "estado".to_string(),
+        ],
+        cliente.clone(),
+    )
+    .unwrap();
+    assert!(salida.contains(&format!("{} is the first bad commit", commits[3])));
+    command_utils::bisect_reset(cliente.clone()).unwrap();
+
+    command_utils::bisect_start(
+        vec![commits[3].clone(), commits[1].clone()],
+        cliente.clone(),
+    )
+    .unwrap();
+    let salida = command_utils::bisect_mark("skip", vec![], cliente.clone()).unwrap();
+    assert!(salida.starts_with("There are only 'skip'ped commits left to test."));
+    assert!(command_utils::bisect_start(vec![], cliente.clone()).is_err());
+    command_utils::bisect_reset(cliente.clone()).unwrap();
+    assert!(command_utils::bisect_reset(cliente).is_err());
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_bisect_desde_el_handler_con_head_desacoplada() {
+    let commits = setup_bisect();
+    let cliente = "cliente".to_string();
+    let correr =
+        |args: &[&str]| command_handler(flags(args), false, "".to_string(), cliente.clone());
+    correr(&["bisect", "start", "HEAD", &commits[0]]).unwrap();
+    assert_eq!(
+        file_manager::get_head(cliente.clone()).unwrap(),
+        "BISECT_HEAD"
+    );
+    assert_eq!(
+        command_utils::get_head_branch(cliente.clone()).unwrap(),
+        None
+    );
+
+    // con HEAD fuera de una branch, status y los comandos que chequean que haya commits andan
+    let actual = command_utils::resolve_commit("HEAD".to_string(), cliente.clone()).unwrap();
+    let estado = command_utils::status_print_current_branch(cliente.clone()).unwrap();
+    assert!(estado.contains(&actual[..7]));
+    correr(&["status"]).unwrap();
+    correr(&["log", "--oneline"]).unwrap();
+    correr(&["bisect", "good"]).unwrap();
+    correr(&["bisect", "bad"]).unwrap();
+    assert!(correr(&["merge", "master"]).is_err());
+
+    correr(&["bisect", "reset"]).unwrap();
+    assert_eq!(
+        command_utils::get_head_branch(cliente.clone()).unwrap(),
+        Some("master".to_string())
+    );
+    delete_repo("cliente/test".to_string());
+}
+
+// *********************
+//   GREP TESTS
+// *********************/
+fn get_grep(flags: &[&str]) -> String {
+    let flags = flags.iter().map(|f| f.to_string()).collect();
+    command_utils::get_grep(flags, "cliente".to_string()).unwrap()
+}
+
+#[test]
+#[serial]
+fn test_grep_busca_en_working_tree_index_y_revisiones() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    fs::create_dir_all("cliente/test/src").unwrap();
+    file_manager::write_file(
+        "cliente/test/src/main.rs".to_string(),
+        "fn main() {\n    println!(\"Hola\");\n}\n".to_string(),
+    )
+    .unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/notas",
+        "hola mundo\nchau señor\n",
+        "primero",
+    );
+    let primero = command_utils::resolve_commit("HEAD".to_string(), cliente.clone()).unwrap();
+
+    // cambios sin agregar, y un archivo sin trackear que no se busca
+    file_manager::write_file(
+        "cliente/test/notas".to_string(),
+        "chau\nhola otra vez\n".to_string(),
+    )
+    .unwrap();
+    file_manager::write_file("cliente/test/suelto".to_string(), "hola\n".to_string()).unwrap();
+
+    assert_eq!(get_grep(&["hola"]), "notas:hola otra vez\n");
+    assert_eq!(
+        get_grep(&["-n", "-i", "hola"]),
+        "notas:2:hola otra vez\nsrc/main.rs:2:    println!(\"Hola\");\n"
+    );
+    assert_eq!(
+        get_grep(&["--cached", "-n", "hola"]),
+        "notas:1:hola mundo\n"
+    );
+    assert_eq!(
+        get_grep(&["-c", "^(hola|chau)", &primero[..7]]),
+        format!("{}:notas:2\n", &primero[..7])
+    );
+    assert_eq!(
+        get_grep(&["-l", "-i", "hola", "--", "src"]),
+        "src/main.rs\n"
+    );
+    assert_eq!(
+        get_grep(&["-F", "-e", "println!(\""]),
+        "src/main.rs:    println!(\"Hola\");\n"
+    );
+    assert_eq!(get_grep(&["--cached", "señor"]), "notas:chau señor\n");
+    assert_eq!(get_grep(&["señ.r", "HEAD"]), "HEAD:notas:chau señor\n");
+    assert_eq!(get_grep(&["no_existe"]), "");
+    assert!(command_utils::get_grep(vec!["(hola".to_string()], cliente).is_err());
+    delete_repo("cliente/test".to_string());
+}
+
+// *********************
+//   DESCRIBE TESTS
+// *********************/
+fn flags(flags: &[&str]) -> Vec<String> {
+    flags.iter().map(|f| f.to_string()).collect()
+}
+
+#[test]
+#[serial]
+fn test_describe_y_name_rev_nombran_commits_desde_los_tags() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    let mut commits = vec![];
+    for i in 1..=4 {
+        commit_file(
+            cliente.clone(),
+            "cliente/test/version",
+            &format!("{}\n", i),
+            &format!("commit_{}", i),
+        );
+        commits.push(command_utils::resolve_commit("HEAD".to_string(), cliente.clone()).unwrap());
+        match i {
+            1 => {
+                commands_fn::tag(flags(&["-a", "v1.0", "-m", "primera"]), cliente.clone()).unwrap()
+            }
+            3 => commands_fn::tag(flags(&["ligero"]), cliente.clone()).unwrap(),
+            _ => {}
+        }
+    }
+    let describe = |f: &[&str]| command_utils::get_describe(flags(f), cliente.clone()).unwrap();
+
+    assert_eq!(describe(&[]), format!("v1.0-3-g{}\n", &commits[3][..7]));
+    assert_eq!(describe(&[&commits[0]]), "v1.0\n");
+    assert_eq!(
+        describe(&["--long", &commits[0]]),
+        format!("v1.0-0-g{}\n", &commits[0][..7])
+    );
+    assert_eq!(
+        describe(&["--tags"]),
+        format!("ligero-1-g{}\n", &commits[3][..7])
+    );
+    assert_eq!(
+        describe(&["--dirty"]),
+        format!("v1.0-3-g{}\n", &commits[3][..7])
+    );
+    file_manager::write_file("cliente/test/version".to_string(), "cambio\n".to_string()).unwrap();
+    assert_eq!(
+        describe(&["--dirty"]),
+        format!("v1.0-3-g{}-dirty\n", &commits[3][..7])
+    );
+
+    let name_rev = |f: &[&str]| command_utils::get_name_rev(flags(f), cliente.clone()).unwrap();
+    assert_eq!(
+        name_rev(&["--name-only", &commits[0], &commits[1], "HEAD"]),
+        "tags/v1.0\ntags/ligero~1\nmaster\n"
+    );
+    assert_eq!(name_rev(&["--tags", "HEAD"]), "HEAD undefined\n");
+    assert_eq!(name_rev(&["HEAD~3"]), "HEAD~3 tags/v1.0\n");
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_describe_sin_tags_falla_salvo_con_always() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(cliente.clone(), "cliente/test/archivo", "hola\n", "primero");
+    commands_fn::tag(flags(&["ligero"]), cliente.clone()).unwrap();
+    let head = command_utils::resolve_commit("HEAD".to_string(), cliente.clone()).unwrap();
+
+    assert!(command_utils::get_describe(vec![], cliente.clone()).is_err());
+    assert_eq!(
+        command_utils::get_describe(flags(&["--always"]), cliente.clone()).unwrap(),
+        format!("{}\n", &head[..7])
+    );
+    assert_eq!(
+        command_utils::get_describe(flags(&["--tags"]), cliente.clone()).unwrap(),
+        "ligero\n"
+    );
+    assert!(command_utils::get_name_rev(vec![], cliente).is_err());
+    delete_repo("cliente/test".to_string());
+}
+
+// *********************
+//   ARCHIVE TESTS
+// *********************/
+// (nombre, tipo, contenido) de cada entrada de un tar
+fn leer_tar(tar: &[u8]) -> Vec<(String, u8, String)> {
+    let mut entradas = vec![];
+    let mut pos = 0;
+    while pos + 512 <= tar.len() && tar[pos] != 0 {
+        let header = &tar[pos..pos + 512];
+        let campo = |desde: usize, hasta: usize| {
+            String::from_utf8_lossy(&header[desde..hasta])
+                .trim_end_matches('\0')
+                .to_string()
+        };
+        let tamanio = usize::from_str_radix(&campo(124, 135), 8).unwrap();
+        let checksum = usize::from_str_radix(&campo(148, 154), 8).unwrap();
+        let suma: usize = header
+            .iter()
+            .enumerate()
+            .map(|(i, b)| {
+                if (148..156).contains(&i) {
+                    32
+                } else {
+                    *b as usize
+                }
+            })
+            .sum();
+        assert_eq!(checksum, suma);
+        let contenido = String::from_utf8_lossy(&tar[pos + 512..pos + 512 + tamanio]).to_string();
+        entradas.push((campo(0, 100), header[156], contenido));
+        pos += 512 + tamanio.div_ceil(512) * 512;
+    }
+    entradas
+}
+
+fn setup_archive() -> String {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    fs::create_dir_all("cliente/test/src").unwrap();
+    file_manager::write_file(
+        "cliente/test/src/main.rs".to_string(),
+        "fn main() {}\n".to_string(),
+    )
+    .unwrap();
+    commit_file(cliente.clone(), "cliente/test/a.txt", "hola\n", "primero");
+    // cambios y archivos sin trackear que no tienen que aparecer
+    file_manager::write_file("cliente/test/a.txt".to_string(), "cambio\n".to_string()).unwrap();
+    file_manager::write_file("cliente/test/suelto".to_string(), "x\n".to_string()).unwrap();
+    cliente
+}
+
+#[test]
+#[serial]
+fn test_archive_tar_y_tar_gz_del_tree_de_un_commit() {
+    let cliente = setup_archive();
+    let (tar, output) = command_utils::get_archive(
+        vec!["--prefix=proyecto/".to_string(), "HEAD".to_string()],
+        cliente.clone(),
+    )
+    .unwrap();
+    assert_eq!(output, None);
+    assert_eq!(tar.len() % 512, 0);
+    assert_eq!(
+        leer_tar(&tar),
+        vec![
+            ("proyecto/".to_string(), b'5', "".to_string()),
+            ("proyecto/a.txt".to_string(), b'0', "hola\n".to_string()),
+            ("proyecto/src/".to_string(), b'5', "".to_string()),
+            (
+                "proyecto/src/main.rs".to_string(),
+                b'0',
+                "fn main() {}\n".to_string()
+            ),
+        ]
+    );
+
+    let (gz, output) = command_utils::get_archive(
+        vec![
+            "-o".to_string(),
+            "cliente/salida.tgz".to_string(),
+            "HEAD".to_string(),
+            "src".to_string(),
+        ],
+        cliente.clone(),
+    )
+    .unwrap();
+    assert_eq!(output, Some("cliente/salida.tgz".to_string()));
+    let mut tar = vec![];
+    flate2::read::GzDecoder::new(&gz[..])
+        .read_to_end(&mut tar)
+        .unwrap();
+    let nombres: Vec<String> = leer_tar(&tar).into_iter().map(|(n, _, _)| n).collect();
+    assert_eq!(nombres, vec!["src/", "src/main.rs"]);
+
+    assert!(command_utils::get_archive(
+        vec!["--format=rar".to_string(), "HEAD".to_string()],
+        cliente.clone()
+    )
+    .is_err());
+    assert!(command_utils::get_archive(vec![], cliente).is_err());
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_archive_zip_guarda_los_archivos_comprimidos() {
+    let cliente = setup_archive();
+    let (zip, _) = command_utils::get_archive(
+        vec!["--format=zip".to_string(), "HEAD".to_string()],
+        cliente,
+    )
+    .unwrap();
+    assert_eq!(&zip[..4], &[0x50, 0x4b, 0x03, 0x04]);
+    // el primer archivo es a.txt, comprimido con deflate
+    let entero = |desde: usize, bytes: usize| {
+        zip[desde..desde + bytes]
+            .iter()
+            .rev()
+            .fold(0usize, |acc, b| acc << 8 | *b as usize)
+    };
+    assert_eq!(entero(8, 2), 8);
+    let (comprimido, largo_nombre) = (entero(18, 4), entero(26, 2));
+    assert_eq!(&zip[30..30 + largo_nombre], b"a.txt");
+    let inicio = 30 + largo_nombre;
+    let mut contenido = String::new();
+    flate2::read::DeflateDecoder::new(&zip[inicio..inicio + comprimido])
+        .read_to_string(&mut contenido)
+        .unwrap();
+    assert_eq!(contenido, "hola\n");
+    // el directorio central termina con la cantidad de entradas: a.txt, src/ y src/main.rs
+    let fin = zip.len() - 22;
+    assert_eq!(&zip[fin..fin + 4], &[0x50, 0x4b, 0x05, 0x06]);
+    assert_eq!(entero(fin + 10, 2), 3);
+    let central = entero(fin + 16, 4);
+    assert_eq!(&zip[central..central + 4], &[0x50, 0x4b, 0x01, 0x02]);
+    let siguiente = central + 46 + entero(central + 28, 2);
+    assert_eq!(&zip[siguiente..siguiente + 4], &[0x50, 0x4b, 0x01, 0x02]);
+    assert_eq!(&zip[siguiente + 46..siguiente + 50], b"src/");
+    delete_repo("cliente/test".to_string());
+}
+
+// *********************
+//   BUNDLE TESTS
+// *********************/
+#[test]
+#[serial]
+fn test_bundle_clone_y_fetch_sin_red() {
+    refresh_files();
+    delete_repo("cliente/copia".to_string());
+    let cliente = "cliente".to_string();
+    commit_file(cliente.clone(), "cliente/test/archivo", "uno\n", "primero");
+    commit_file(cliente.clone(), "cliente/test/archivo", "dos\n", "segundo");
+    commands_fn::tag(flags(&["-a", "v1", "-m", "version"]), cliente.clone()).unwrap();
+    let segundo = command_utils::resolve_commit("HEAD".to_string(), cliente.clone()).unwrap();
+
+    commands_fn::bundle(
+        flags(&["create", "cliente/completo.bundle", "--all"]),
+        cliente.clone(),
+    )
+    .unwrap();
+    assert!(command_utils::is_bundle("cliente/completo.bundle"));
+    let verify =
+        command_utils::bundle_verify("cliente/completo.bundle".to_string(), cliente.clone())
+            .unwrap();
+    assert!(verify.starts_with("The bundle contains these 2 refs:\n"));
+    assert!(verify.contains(&format!("{} refs/heads/master\n", segundo)));
+    assert!(verify.contains("The bundle records a complete history.\n"));
+
+    // clonar desde el bundle deja el repo igual que el original
+    commands_fn::clone(
+        flags(&["cliente/completo.bundle", "copia"]),
+        cliente.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        file_manager::get_current_repo(cliente.clone()).unwrap(),
+        "cliente/copia"
+    );
+    assert_eq!(
+        file_manager::read_file("cliente/copia/archivo".to_string()).unwrap(),
+        "dos\n"
+    );
+    assert_eq!(
+        command_utils::resolve_commit("v1".to_string(), cliente.clone()).unwrap(),
+        segundo
+    );
+
+    // un bundle incremental solo lleva lo nuevo y requiere el commit base
+    file_manager::write_file("cliente/.head_repo".to_string(), "test".to_string()).unwrap();
+    commit_file(cliente.clone(), "cliente/test/archivo", "tres\n", "tercero");
+    let tercero = command_utils::resolve_commit("HEAD".to_string(), cliente.clone()).unwrap();
+    command_utils::bundle_create(
+        "cliente/nuevo.bundle".to_string(),
+        flags(&["v1..master"]),
+        cliente.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        command_utils::bundle_list_heads("cliente/nuevo.bundle".to_string()).unwrap(),
+        format!("{} refs/heads/master\n", tercero)
+    );
+
+    file_manager::write_file("cliente/.head_repo".to_string(), "copia".to_string()).unwrap();
+    let verify =
+        command_utils::bundle_verify("cliente/nuevo.bundle".to_string(), cliente.clone()).unwrap();
+    assert!(verify.contains(&format!("The bundle requires this ref:\n{}\n", segundo)));
+    file_manager::set_remote("cliente/nuevo.bundle", cliente.clone()).unwrap();
+    commands_fn::fetch(vec![], cliente.clone()).unwrap();
+    assert_eq!(
+        command_utils::resolve_commit("FETCH_HEAD".to_string(), cliente.clone()).unwrap(),
+        tercero
+    );
+    assert_eq!(
+        command_utils::get_show(flags(&["FETCH_HEAD:archivo"]), cliente.clone()).unwrap(),
+        "tres\n"
+    );
+
+    // el bundle tambien se puede pasar directamente a fetch y a pull
+    fs::remove_file("cliente/copia/gitr/FETCH_HEAD").unwrap();
+    commands_fn::fetch(flags(&["cliente/nuevo.bundle"]), cliente.clone()).unwrap();
+    assert_eq!(
+        command_utils::resolve_commit("FETCH_HEAD".to_string(), cliente.clone()).unwrap(),
+        tercero
+    );
+    commands_fn::pull(flags(&["cliente/nuevo.bundle"]), cliente.clone()).unwrap();
+    assert_eq!(
+        command_utils::resolve_commit("HEAD".to_string(), cliente.clone()).unwrap(),
+        tercero
+    );
+    assert_eq!(
+        fs::read_to_string("cliente/copia/archivo").unwrap(),
+        "tres\n"
+    );
+    match commands_fn::pull(flags(&["origin", "master"]), cliente.clone()) {
+        Err(GitrError::InvalidArgumentError(_, uso)) => assert!(uso.starts_with("pull ")),
+        _ => panic!("pull con dos argumentos tiene que fallar con su uso"),
+    }
+
+    // un repo sin el commit requerido no puede usar el bundle incremental
+    refresh_files();
+    assert!(
+        command_utils::bundle_verify("cliente/nuevo.bundle".to_string(), cliente.clone()).is_err()
+    );
+    assert!(command_utils::bundle_create(
+        "cliente/vacio.bundle".to_string(),
+        flags(&["master..master"]),
+        cliente
+    )
+    .is_err());
+    delete_repo("cliente/copia".to_string());
+    delete_repo("cliente/test".to_string());
+    fs::remove_file("cliente/completo.bundle").unwrap();
+    fs::remove_file("cliente/nuevo.bundle").unwrap();
+}
+
+// *********************
+//   FORMAT-PATCH / AM TESTS
+// *********************/
+#[test]
+#[serial]
+fn test_format_patch_y_am_reproducen_los_commits() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    file_manager::write_file("cliente/test/borrar".to_string(), "x\n".to_string()).unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo",
+        "uno\ndos\ntres\ncuatro\ncinco\nseis\nsiete\n",
+        "base",
+    );
+    commands_fn::branch(flags(&["otra"]), cliente.clone()).unwrap();
+
+    fs::create_dir_all("cliente/test/dir").unwrap();
+    file_manager::write_file("cliente/test/dir/nuevo".to_string(), "nuevo\n".to_string()).unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo",
+        "uno\nDOS\ntres\ncuatro\ncinco\nseis\nSIETE\n",
+        "Cambia el archivo!",
+    );
+    fs::remove_file("cliente/test/borrar").unwrap();
+    commands_fn::rm(flags(&["borrar"]), cliente.clone()).unwrap();
+    commands_fn::commit(
+        flags(&["-m", "Borra un archivo"]),
+        "None".to_string(),
+        cliente.clone(),
+    )
+    .unwrap();
+    let master = command_utils::resolve_commit("master".to_string(), cliente.clone()).unwrap();
+
+    let archivos =
+        command_utils::get_format_patch(flags(&["-o", "cliente/patches", "otra"]), cliente.clone())
+            .unwrap();
+    assert_eq!(
+        archivos,
+        "cliente/patches/0001-Cambia-el-archivo.patch\ncliente/patches/0002-Borra-un-archivo.patch\n"
+    );
+    let mail = file_manager::read_file("cliente/patches/0001-Cambia-el-archivo.patch".to_string())
+        .unwrap();
+    assert!(mail.starts_with("From "));
+    assert!(mail.contains("\nFrom: cliente <"));
+    assert!(mail.contains(">\nDate: "));
+    assert!(mail.contains("\nSubject: [PATCH 1/2] Cambia el archivo!\n\n---\n"));
+    assert!(mail.contains(" 2 files changed, 3 insertions(+), 2 deletions(-)\n"));
+    assert!(mail.contains("new file mode 100644\n"));
+    assert!(mail.contains("-DOS\n") || mail.contains("+DOS\n"));
+    let stdout =
+        command_utils::get_format_patch(flags(&["--stdout", "-1"]), cliente.clone()).unwrap();
+    assert!(stdout.contains("Subject: [PATCH] Borra un archivo\n"));
+    assert!(stdout.contains("deleted file mode 100644\n"));
+
+    // aplicados sobre la otra branch dejan el mismo tree con la misma autoria
+    commands_fn::checkout(flags(&["otra"]), cliente.clone()).unwrap();
+    let salida = command_utils::get_am(
+        flags(&[
+            "cliente/patches/0001-Cambia-el-archivo.patch",
+            "cliente/patches/0002-Borra-un-archivo.patch",
+        ]),
+        cliente.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        salida,
+        "Applying: Cambia el archivo!\nApplying: Borra un archivo\n"
+    );
+    let otra = command_utils::resolve_commit("otra".to_string(), cliente.clone()).unwrap();
+    assert_eq!(
+        file_manager::get_main_tree(otra.clone(), cliente.clone()).unwrap(),
+        file_manager::get_main_tree(master.clone(), cliente.clone()).unwrap()
+    );
+    let autor = |commit: &str| {
+        let (_, body) =
+            file_manager::read_object(&commit.to_string(), "cliente/test".to_string(), true)
+                .unwrap()
+                .split_once('\0')
+                .map(|(h, b)| (h.to_string(), b.to_string()))
+                .unwrap();
+        body.lines()
+            .find(|l| l.starts_with("author "))
+            .unwrap()
+            .to_string()
+    };
+    assert_eq!(autor(&otra), autor(&master));
+    assert_eq!(
+        file_manager::get_commit_message(otra.clone(), cliente.clone()).unwrap(),
+        file_manager::get_commit_message(master, cliente.clone()).unwrap()
+    );
+    assert!(!Path::new("cliente/test/borrar").exists());
+    assert_eq!(
+        file_manager::read_file("cliente/test/dir/nuevo".to_string()).unwrap(),
+        "nuevo\n"
+    );
+
+    // un patch que ya no aplica no crea commits
+    assert!(command_utils::get_am(
+        flags(&["cliente/patches/0001-Cambia-el-archivo.patch"]),
+        cliente.clone()
+    )
+    .is_err());
+    assert_eq!(
+        command_utils::resolve_commit("otra".to_string(), cliente).unwrap(),
+        otra
+    );
+    delete_repo("cliente/patches".to_string());
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_format_patch_y_am_con_utf8_y_sin_newline_final() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(cliente.clone(), "cliente/test/archivo", "año\nx\ny", "base");
+    commands_fn::branch(flags(&["otra"]), cliente.clone()).unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo",
+        "año\nx\ny\nñandú\n",
+        "Agrega una linea",
+    );
+    let mail =
+        command_utils::get_format_patch(flags(&["--stdout", "-1"]), cliente.clone()).unwrap();
+    assert!(mail.contains("\n año\n"));
+    assert!(mail.contains("\n-y\n\\ No newline at end of file\n+y\n+ñandú\n"));
+    file_manager::write_file("cliente/utf8.patch".to_string(), mail).unwrap();
+
+    commands_fn::checkout(flags(&["otra"]), cliente.clone()).unwrap();
+    command_utils::get_am(flags(&["cliente/utf8.patch"]), cliente.clone()).unwrap();
+    assert_eq!(
+        file_manager::read_file("cliente/test/archivo".to_string()).unwrap(),
+        "año\nx\ny\nñandú\n"
+    );
+    let otra = command_utils::resolve_commit("otra".to_string(), cliente.clone()).unwrap();
+    let master = command_utils::resolve_commit("master".to_string(), cliente.clone()).unwrap();
+    assert_eq!(
+        file_manager::get_main_tree(otra, cliente.clone()).unwrap(),
+        file_manager::get_main_tree(master, cliente.clone()).unwrap()
+    );
+    fs::remove_file("cliente/utf8.patch").unwrap();
+    delete_repo("cliente/test".to_string());
+}
+
+// *********************
+//   APPLY TESTS
+// *********************/
+#[test]
+#[serial]
+fn test_apply_en_working_tree_index_y_3way() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    let base = "uno\ndos\ntres\ncuatro\ncinco\nseis\nsiete\n";
+    let nuevo = "uno\nDOS\ntres\ncuatro\ncinco\nseis\nsiete\n";
+    file_manager::write_file("cliente/test/borrar".to_string(), "x\n".to_string()).unwrap();
+    commit_file(cliente.clone(), "cliente/test/archivo", base, "base");
+    let hash = |data: &str| Blob::new(data.to_string()).unwrap().get_hash();
+    let patch = format!(
+        "diff --git a/archivo b/archivo\nindex {}..{} 100644\n--- a/archivo\n+++ b/archivo\n\
+         @@ -1,7 +1,7 @@\n uno\n-dos\n+DOS\n tres\n cuatro\n cinco\n seis\n siete\n\
+         diff --git a/borrar b/borrar\ndeleted file mode 100644\nindex {}..0000000\n\
+         --- a/borrar\n+++ /dev/null\n@@ -1 +0,0 @@\n-x\n\
+         diff --git a/script b/script\nnew file mode 100755\nindex 0000000..{}\n\
+         --- /dev/null\n+++ b/script\n@@ -0,0 +1 @@\n+echo hola\n",
+        &hash(base)[..7],
+        &hash(nuevo)[..7],
+        &hash("x\n")[..7],
+        &hash("echo hola\n")[..7]
+    );
+    file_manager::write_file("cliente/cambios.patch".to_string(), patch).unwrap();
+    let archivo = || file_manager::read_file("cliente/test/archivo".to_string()).unwrap();
+    let index = || command_utils::get_index_hashmap(cliente.clone()).unwrap().0;
+
+    // --check no toca nada
+    let salida = command_utils::get_apply(
+        flags(&["--check", "cliente/cambios.patch"]),
+        cliente.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        salida,
+        "Checked patch archivo\nChecked patch borrar\nChecked patch script\n"
+    );
+    assert_eq!(archivo(), base);
+
+    // --cached solo cambia el index
+    command_utils::get_apply(
+        flags(&["--cached", "cliente/cambios.patch"]),
+        cliente.clone(),
+    )
+    .unwrap();
+    assert_eq!(archivo(), base);
+    assert!(Path::new("cliente/test/borrar").exists());
+    assert!(!Path::new("cliente/test/script").exists());
+    assert_eq!(index()["cliente/test/archivo"], hash(nuevo));
+    assert!(!index().contains_key("cliente/test/borrar"));
+    assert_eq!(index()["cliente/test/script"], hash("echo hola\n"));
+    assert!(file_manager::read_index(cliente.clone())
+        .unwrap()
+        .contains("100755 "));
+
+    // sin flags se aplica solo al working tree, con el modo del archivo nuevo
+    let salida =
+        command_utils::get_apply(flags(&["cliente/cambios.patch"]), cliente.clone()).unwrap();
+    assert!(salida.contains("Applied patch archivo cleanly.\n"));
+    assert_eq!(archivo(), nuevo);
+    assert!(!Path::new("cliente/test/borrar").exists());
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let modo = fs::metadata("cliente/test/script")
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(modo & 0o777, 0o755);
+    }
+    // aplicarlo de nuevo falla sin dejar cambios a medias
+    assert!(command_utils::get_apply(flags(&["cliente/cambios.patch"]), cliente.clone()).is_err());
+    assert!(command_utils::get_apply(
+        flags(&["--cached", "--3way", "cliente/cambios.patch"]),
+        cliente.clone()
+    )
+    .is_err());
+
+    // con --3way los cambios locales que rompen el contexto se mergean con el patch
+    let patch_archivo = file_manager::read_file("cliente/cambios.patch".to_string())
+        .unwrap()
+        .split("diff --git a/borrar")
+        .next()
+        .unwrap()
+        .to_string();
+    file_manager::write_file("cliente/archivo.patch".to_string(), patch_archivo).unwrap();
+    let local = "uno\ndos\ntres\ncuatro\ncinco\nSEIS\nsiete\n";
+    file_manager::write_file("cliente/test/archivo".to_string(), local.to_string()).unwrap();
+    assert!(command_utils::get_apply(flags(&["cliente/archivo.patch"]), cliente.clone()).is_err());
+    let salida =
+        command_utils::get_apply(flags(&["--3way", "cliente/archivo.patch"]), cliente.clone())
+            .unwrap();
+    assert_eq!(salida, "Applied patch archivo cleanly.\n");
+    assert_eq!(archivo(), "uno\nDOS\ntres\ncuatro\ncinco\nSEIS\nsiete\n");
+
+    // y si tocan las mismas lineas quedan los marcadores de conflicto
+    let local = "uno\ndos local\ntres\ncuatro\ncinco\nseis\nsiete\n";
+    file_manager::write_file("cliente/test/archivo".to_string(), local.to_string()).unwrap();
+    let salida =
+        command_utils::get_apply(flags(&["--3way", "cliente/archivo.patch"]), cliente.clone())
+            .unwrap();
+    assert_eq!(
+        salida,
+        "Applied patch to 'archivo' with conflicts.\nU archivo\n"
+    );
+    assert!(archivo().contains("<<<<<<< HEAD\n"));
+    assert!(archivo().contains("DOS"));
+    fs::remove_file("cliente/cambios.patch").unwrap();
+    fs::remove_file("cliente/archivo.patch").unwrap();
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_apply_y_apply_reverse_respetan_el_newline_final() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(cliente.clone(), "cliente/test/f", "x\ny", "base");
+    file_manager::write_file("cliente/test/f".to_string(), "x\ny\nz\n".to_string()).unwrap();
+    let diff = command_utils::get_diff(flags(&[]), cliente.clone()).unwrap();
+    assert!(diff.contains("\n-y\n\\ No newline at end of file\n+y\n+z\n"));
+    file_manager::write_file("cliente/f.patch".to_string(), diff).unwrap();
+    let archivo = || file_manager::read_file("cliente/test/f".to_string()).unwrap();
+
+    // deshecho saca el newline final y aplicado de nuevo lo vuelve a agregar
+    command_utils::get_apply(flags(&["-R", "cliente/f.patch"]), cliente.clone()).unwrap();
+    assert_eq!(archivo(), "x\ny");
+    assert!(command_utils::get_apply(flags(&["-R", "cliente/f.patch"]), cliente.clone()).is_err());
+    command_utils::get_apply(flags(&["cliente/f.patch"]), cliente.clone()).unwrap();
+    assert_eq!(archivo(), "x\ny\nz\n");
+    assert!(command_utils::get_apply(flags(&["cliente/f.patch"]), cliente.clone()).is_err());
+
+    // en el index tambien, contra el blob sin newline final
+    command_utils::get_apply(flags(&["--cached", "cliente/f.patch"]), cliente.clone()).unwrap();
+    let index = command_utils::get_index_hashmap(cliente.clone()).unwrap().0;
+    assert_eq!(
+        index["cliente/test/f"],
+        Blob::new("x\ny\nz\n".to_string()).unwrap().get_hash()
+    );
+    command_utils::get_apply(
+        flags(&["-R", "--index", "cliente/f.patch"]),
+        cliente.clone(),
+    )
+    .unwrap();
+    let index = command_utils::get_index_hashmap(cliente.clone()).unwrap().0;
+    assert_eq!(
+        index["cliente/test/f"],
+        Blob::new("x\ny".to_string()).unwrap().get_hash()
+    );
+    assert_eq!(archivo(), "x\ny");
+    fs::remove_file("cliente/f.patch").unwrap();
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_apply_rechaza_patches_mal_formados() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(cliente.clone(), "cliente/test/g", "uno\ndos\n", "base");
+    file_manager::write_file("cliente/test/g".to_string(), "uno\nDOS\n".to_string()).unwrap();
+
+    // la salida de diff --color no es un patch y no puede crear archivos con los codigos
+    let diff = command_utils::get_diff(flags(&["--color"]), cliente.clone()).unwrap();
+    assert!(diff.contains('\x1b'));
+    file_manager::write_file("cliente/color.patch".to_string(), diff).unwrap();
+    file_manager::write_file("cliente/test/g".to_string(), "uno\ndos\n".to_string()).unwrap();
+    assert!(command_utils::get_apply(flags(&["cliente/color.patch"]), cliente.clone()).is_err());
+    assert_eq!(fs::read_dir("cliente/test").unwrap().count(), 3);
+
+    let header = "diff --git a/g b/g\n--- a/g\n+++ b/g\n";
+    let invalidos = [
+        // headers que no se pueden parsear
+        "diff --git g g\n--- a/g\n+++ b/g\n@@ -1 +1 @@\n-dos\n+DOS\n".to_string(),
+        "diff --git a/g b/g\nindex zzz..123 100644\n--- a/g\n+++ b/g\n".to_string(),
+        "diff --git a/g b/g\nnew file mode 644\n--- /dev/null\n+++ b/g\n".to_string(),
+        "--- a/../afuera\n+++ b/../afuera\n@@ -1 +1 @@\n-dos\n+DOS\n".to_string(),
+        // hunks que no se pueden parsear o que no tienen las lineas que dicen
+        format!("{}@@ -1 +1\n-dos\n+DOS\n", header),
+        format!("{}@@ 1 1 @@\n-dos\n+DOS\n", header),
+        format!("{}@@ -1,2 +1,2 @@\n-dos\n+DOS\n", header),
+        format!("{}@@ -1 +1 @@\n-dos\n+DOS\n+de mas\n", header),
+        format!("{}@@ -1 +1 @@\n-dos\n+DOS\n-de mas\n", header),
+    ];
+    for patch in invalidos {
+        assert!(command_utils::parse_patch(&patch).is_err(), "{}", patch);
+    }
+    // la firma de format-patch y el texto del mail no son parte del hunk
+    let patch = format!("{}@@ -2 +2 @@\n-dos\n+DOS\n-- \ngitr\n", header);
+    assert_eq!(
+        command_utils::parse_patch(&patch).unwrap()[0].hunks.len(),
+        1
+    );
+    fs::remove_file("cliente/color.patch").unwrap();
+    delete_repo("cliente/test".to_string());
+}
+
+// *********************
+//   GITRIGNORE TESTS
+// *********************/
+#[test]
+#[serial]
+fn test_add_y_status_respetan_gitrignore_anidados_y_negaciones() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    let escribir = |path: &str, data: &str| {
+        let path = format!("cliente/test/{}", path);
+        fs::create_dir_all(Path::new(&path).parent().unwrap()).unwrap();
+        file_manager::write_file(path, data.to_string()).unwrap()
+    };
+    escribir("gitrignore", "*.log\n!importante.log\nbuild/\n");
+    escribir("sub/gitrignore", "!debug.log\nlocal\n");
+    for path in [
+        "a.txt",
+        "a.log",
+        "importante.log",
+        "build/x",
+        "sub/debug.log",
+        "sub/b.log",
+        "sub/local",
+        "sub/c.txt",
+        "sub/build/y",
+    ] {
+        escribir(path, "x\n");
+    }
+
+    commands_fn::add(flags(&["."]), cliente.clone()).unwrap();
+    let mut index = command_utils::get_index_hashmap(cliente.clone())
+        .unwrap()
+        .0
+        .into_keys()
+        .collect::<Vec<String>>();
+    index.sort();
+    assert_eq!(
+        index,
+        [
+            "cliente/test/a.txt",
+            "cliente/test/importante.log",
+            "cliente/test/sub/c.txt",
+            "cliente/test/sub/debug.log",
+            "cliente/test/sub/gitrignore",
+        ]
+    );
+
+    // los ignorados tampoco aparecen como archivos sin seguimiento
+    escribir("nuevo.log", "x\n");
+    escribir("sub/nuevo.txt", "x\n");
+    escribir("sub/build/z", "x\n");
+    let status = get_status_porcelain("v1", false, false, cliente.clone()).unwrap();
+    assert_eq!(
+        status,
+        "A  a.txt\nA  importante.log\nA  sub/c.txt\nA  sub/debug.log\nA  sub/gitrignore\n\
+         ?? gitrignore\n?? sub/nuevo.txt\n"
+    );
+    let status = command_utils::get_status(cliente.clone()).unwrap();
+    assert!(status.contains("sub/nuevo.txt"));
+    assert!(!status.contains("nuevo.log"));
+    assert!(!status.contains("build/"));
+    assert!(!status.contains("sub/local"));
+    delete_repo("cliente/test".to_string());
+}
+
+// *********************
+//   ATTRIBUTES TESTS
+// *********************/
+#[test]
+#[serial]
+fn test_attributes_normalizan_finales_de_linea_y_marcan_binarios() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    file_manager::write_file(
+        "cliente/test/gitrattributes".to_string(),
+        "*.txt text eol=crlf\n*.dat binary\n".to_string(),
+    )
+    .unwrap();
+    file_manager::write_file("cliente/test/datos.dat".to_string(), "uno\n".to_string()).unwrap();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/a.txt",
+        "uno\r\ndos\r\n",
+        "crlf",
+    );
+
+    // en el blob queda con LF y el working tree no aparece modificado
+    let index = command_utils::get_index_hashmap(cliente.clone()).unwrap().0;
+    let hash = |data: &str| Blob::new(data.to_string()).unwrap().get_hash();
+    assert_eq!(index["cliente/test/a.txt"], hash("uno\ndos\n"));
+    let (_, not_staged, _) = command_utils::get_untracked_notstaged_files(cliente.clone()).unwrap();
+    assert!(!not_staged.contains(&"cliente/test/a.txt".to_string()));
+
+    // al hacer checkout se vuelve a escribir con CRLF
+    commands_fn::branch(flags(&["otra"]), cliente.clone()).unwrap();
+    file_manager::write_file("cliente/test/a.txt".to_string(), "uno\n".to_string()).unwrap();
+    commands_fn::checkout(flags(&["otra"]), cliente.clone()).unwrap();
+    assert_eq!(
+        fs::read("cliente/test/a.txt").unwrap(),
+        b"uno\r\ndos\r\n".to_vec()
+    );
+
+    // los binarios no muestran diff de texto
+    file_manager::write_file("cliente/test/datos.dat".to_string(), "dos\n".to_string()).unwrap();
+    let diff = command_utils::get_diff(vec![], cliente.clone()).unwrap();
+    assert!(diff.contains("Binary files a/datos.dat and b/datos.dat differ"));
+    assert!(!diff.contains("+dos"));
+    // y en --stat cuentan 0 lineas
+    let stat =
+        command_utils::get_diff(flags(&["--stat", "--", "datos.dat"]), cliente.clone()).unwrap();
+    assert_eq!(
+        stat,
+        " datos.dat | Bin 4 -> 4 bytes\n 1 file changed, 0 insertions(+), 0 deletions(-)\n"
+    );
+
+    // el archivo de atributos tambien puede llamarse .gitrattributes
+    fs::create_dir_all("cliente/test/sub").unwrap();
+    file_manager::write_file(
+        "cliente/test/sub/.gitrattributes".to_string(),
+        "*.raw binary\n".to_string(),
+    )
+    .unwrap();
+    commit_file(cliente.clone(), "cliente/test/sub/x.raw", "uno\n", "raw");
+    file_manager::write_file("cliente/test/sub/x.raw".to_string(), "dos\n".to_string()).unwrap();
+    let diff = command_utils::get_diff(vec![], cliente.clone()).unwrap();
+    assert!(diff.contains("Binary files a/sub/x.raw and b/sub/x.raw differ"));
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_attributes_drivers_de_merge_y_export_ignore() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    file_manager::write_file(
+        "cliente/test/gitrattributes".to_string(),
+        "union.txt merge=union\nours.txt merge=ours\n*.dat binary\nsecreto export-ignore\ndocs export-ignore\n".to_string(),
+    )
+    .unwrap();
+    fs::create_dir_all("cliente/test/docs").unwrap();
+    file_manager::write_file("cliente/test/docs/manual".to_string(), "m\n".to_string()).unwrap();
+    file_manager::write_file("cliente/test/secreto".to_string(), "s\n".to_string()).unwrap();
+    let archivos = ["union.txt", "ours.txt", "datos.dat"];
+    for archivo in archivos {
+        file_manager::write_file(
+            format!("cliente/test/{}", archivo),
+            "a\nbase\nz\n".to_string(),
+        )
+        .unwrap();
+    }
+    commit_file(cliente.clone(), "cliente/test/otro", "o\n", "base");
+    commands_fn::branch(flags(&["branch"]), cliente.clone()).unwrap();
+    for archivo in archivos {
+        file_manager::write_file(
+            format!("cliente/test/{}", archivo),
+            "a\nmaster\nz\n".to_string(),
+        )
+        .unwrap();
+    }
+    commit_file(cliente.clone(), "cliente/test/otro", "o\n", "en master");
+    commands_fn::checkout(flags(&["branch"]), cliente.clone()).unwrap();
+    for archivo in archivos {
+        file_manager::write_file(
+            format!("cliente/test/{}", archivo),
+            "a\nbranch\nz\n".to_string(),
+        )
+        .unwrap();
+    }
+    commit_file(cliente.clone(), "cliente/test/otro", "o\n", "en branch");
+    commands_fn::checkout(flags(&["master"]), cliente.clone()).unwrap();
+
+    let (_, _, conflictos) = commands_fn::merge(flags(&["branch"]), cliente.clone()).unwrap();
+    assert_eq!(conflictos, vec!["datos.dat".to_string()]);
+    let leer =
+        |archivo: &str| file_manager::read_file(format!("cliente/test/{}", archivo)).unwrap();
+    assert_eq!(leer("ours.txt"), "a\nmaster\nz\n");
+    assert_eq!(leer("datos.dat"), "a\nmaster\nz\n");
+    let union = leer("union.txt");
+    assert!(union.contains("master") && union.contains("branch"));
+    assert!(!union.contains("<<<<<<<") && !union.contains("======="));
+
+    // archive deja afuera lo marcado con export-ignore
+    let (tar, _) = command_utils::get_archive(flags(&["master"]), cliente.clone()).unwrap();
+    let tar = String::from_utf8_lossy(&tar).to_string();
+    assert!(tar.contains("otro"));
+    assert!(!tar.contains("secreto\0"));
+    assert!(!tar.contains("docs/manual"));
+    delete_repo("cliente/test".to_string());
+}
+
+// *********************
+//   CONFIG TESTS
+// *********************/
+#[test]
+#[serial]
+fn test_config_por_niveles_y_usos() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    let config = |args: &[&str]| command_utils::get_config(flags(args), cliente.clone());
+    assert_eq!(config(&["user.email"]).unwrap(), "cliente@gmail.com\n");
+    assert_eq!(config(&["user.name"]).unwrap(), "cliente\n");
+
+    // el valor del repo pisa al global
+    config(&["user.name", "Otro Nombre"]).unwrap();
+    assert_eq!(config(&["user.name"]).unwrap(), "Otro Nombre\n");
+    assert_eq!(
+        config(&["--global", "--get", "user.name"]).unwrap(),
+        "cliente\n"
+    );
+    assert!(config(&["--list"])
+        .unwrap()
+        .contains("user.name=Otro Nombre\n"));
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo",
+        "hola\n",
+        "con config",
+    );
+    let commit = get_current_commit(cliente.clone()).unwrap();
+    let data = file_manager::read_object(&commit, "cliente/test".to_string(), true).unwrap();
+    assert!(data.contains("\nauthor Otro Nombre <cliente@gmail.com> "));
+
+    config(&["--unset", "user.name"]).unwrap();
+    assert_eq!(config(&["user.name"]).unwrap(), "cliente\n");
+    assert!(config(&["--unset", "user.name"]).is_err());
+    assert!(config(&["sinseccion"]).is_err());
+    assert!(config(&["--bla", "user.name"]).is_err());
+
+    // el remote queda en la config del repo
+    commands_fn::remote(flags(&["localhost:9418/repo"]), cliente.clone()).unwrap();
+    assert_eq!(
+        file_manager::get_remote(cliente.clone()).unwrap(),
+        "localhost:9418/repo"
+    );
+    let local = file_manager::read_file("cliente/test/gitr/config".to_string()).unwrap();
+    assert!(local.contains("[remote \"origin\"]\n\turl = localhost:9418/repo\n"));
+
+    // core.excludesFile cambia el gitrignore global
+    file_manager::write_file("cliente/ignorar".to_string(), "*.bak\n".to_string()).unwrap();
+    config(&["core.excludesFile", "cliente/ignorar"]).unwrap();
+    assert_eq!(
+        check_ignore_(vec!["cliente/test/x.bak".to_string()], cliente.clone()).unwrap(),
+        vec!["cliente/test/x.bak".to_string()]
+    );
+    fs::remove_file("cliente/ignorar").unwrap();
+    delete_repo("cliente/test".to_string());
+}
+
+/*********************
+    ALIAS TESTS
+*********************/
+
+#[test]
+#[serial]
+fn test_alias_desde_config() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    let config = |args: &[&str]| command_utils::get_config(flags(args), cliente.clone());
+    let correr =
+        |args: &[&str]| command_handler(flags(args), false, "".to_string(), cliente.clone());
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo",
+        "hola\n",
+        "primer commit",
+    );
+
+    // alias con argumentos extra agregados al final
+    config(&["alias.nb", "branch"]).unwrap();
+    correr(&["nb", "nueva"]).unwrap();
+    assert!(Path::new("cliente/test/gitr/refs/heads/nueva").is_file());
+    config(&["alias.lg", "log --oneline"]).unwrap();
+    correr(&["lg"]).unwrap();
+
+    // los atajos por defecto siguen andando y se pueden redefinir
+    let hash = get_current_commit(cliente.clone()).unwrap();
+    correr(&["c", "-t", &hash]).unwrap();
+    config(&["alias.cr", "branch"]).unwrap();
+    correr(&["cr", "otra"]).unwrap();
+    assert!(Path::new("cliente/test/gitr/refs/heads/otra").is_file());
+
+    // alias de shell corren en el repo con los argumentos
+    config(&["alias.eco", "!echo > eco.txt"]).unwrap();
+    correr(&["eco", "uno", "dos"]).unwrap();
+    assert_eq!(
+        file_manager::read_file("cliente/test/eco.txt".to_string()).unwrap(),
+        "uno dos\n"
+    );
+    config(&["alias.falla", "!exit 3"]).unwrap();
+    assert!(matches!(correr(&["falla"]), Err(GitrError::AliasError(_))));
+
+    // recursion
+    config(&["alias.a", "b"]).unwrap();
+    config(&["alias.b", "a"]).unwrap();
+    assert_eq!(
+        correr(&["a"]),
+        Err(GitrError::AliasError(
+            "alias recursion: a -> b -> a".to_string()
+        ))
+    );
+    assert!(matches!(
+        correr(&["noexiste"]),
+        Err(GitrError::InvalidArgumentError(_, _))
+    ));
+    delete_repo("cliente/test".to_string());
+}
+
+/*********************
+    HOOKS TESTS
+*********************/
+
+fn write_hook(nombre: &str, script: &str) {
+    use std::os::unix::fs::PermissionsExt;
+    let path = format!("cliente/test/gitr/hooks/{}", nombre);
+    fs::create_dir_all("cliente/test/gitr/hooks").unwrap();
+    fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+#[serial]
+fn test_hooks_de_cliente() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/archivo",
+        "hola\n",
+        "primer commit",
+    );
+    let primero = get_current_commit(cliente.clone()).unwrap();
+
+    // pre-commit que falla aborta el commit
+    write_hook("pre-commit", "exit 1");
+    fs::write("cliente/test/archivo", "chau\n").unwrap();
+    commands_fn::add(flags(&["archivo"]), cliente.clone()).unwrap();
+    assert!(matches!(
+        commands_fn::commit(
+            flags(&["-m", "rechazado"]),
+            "None".to_string(),
+            cliente.clone()
+        ),
+        Err(GitrError::HookError(_, _))
+    ));
+    assert_eq!(get_current_commit(cliente.clone()).unwrap(), primero);
+
+    // commit-msg puede cambiar el mensaje, post-commit corre en el repo
+    write_hook("pre-commit", "exit 0");
+    write_hook("commit-msg", "sed -i 's/^/[T-1] /' \"$1\"");
+    write_hook("post-commit", "echo hecho > post_commit.txt");
+    commands_fn::commit(
+        flags(&["-m", "aceptado"]),
+        "None".to_string(),
+        cliente.clone(),
+    )
+    .unwrap();
+    let segundo = get_current_commit(cliente.clone()).unwrap();
+    let data = file_manager::read_object(&segundo, "cliente/test".to_string(), true).unwrap();
+    assert!(data.ends_with("\n[T-1] aceptado\n"));
+    assert!(Path::new("cliente/test/post_commit.txt").is_file());
+    fs::remove_file("cliente/test/post_commit.txt").unwrap();
+
+    // un hook sin permisos de ejecucion se ignora
+    fs::write(
+        "cliente/test/gitr/hooks/post-checkout",
+        "#!/bin/sh\nexit 1\n",
+    )
+    .unwrap();
+    commands_fn::checkout(flags(&["-b", "otra"]), cliente.clone()).unwrap();
+
+    // post-checkout recibe el commit anterior, el nuevo y 1
+    write_hook("post-checkout", "echo \"$1 $2 $3\" > checkout.txt");
+    commands_fn::checkout(flags(&["master"]), cliente.clone()).unwrap();
+    assert_eq!(
+        file_manager::read_file("cliente/test/checkout.txt".to_string()).unwrap(),
+        format!("{} {} 1\n", segundo, segundo)
+    );
+    fs::remove_file("cliente/test/checkout.txt").unwrap();
+
+    // pre-rebase rechaza el rebase, post-merge corre despues del merge
+    write_hook("pre-rebase", "exit 1");
+    assert!(matches!(
+        commands_fn::rebase(flags(&["otra"]), cliente.clone()),
+        Err(GitrError::HookError(_, _))
+    ));
+    write_hook("post-merge", "echo \"$1\" > merge.txt");
+    commands_fn::merge(flags(&["otra"]), cliente.clone()).unwrap();
+    assert_eq!(
+        file_manager::read_file("cliente/test/merge.txt".to_string()).unwrap(),
+        "0\n"
+    );
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_argumentos_entre_comillas_y_flags_invalidos() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    let correr = |input: &str| {
+        let argv = parse_input(input.to_string())?;
+        command_handler(argv, false, "".to_string(), cliente.clone())
+    };
+    commit_file(cliente.clone(), "cliente/test/base", "base\n", "base");
+    file_manager::write_file(
+        "cliente/test/con espacios.txt".to_string(),
+        "hola\n".to_string(),
+    )
+    .unwrap();
+    correr("add 'con espacios.txt'").unwrap();
+    correr("commit -m \"mensaje con espacios\"").unwrap();
+    let commit = get_current_commit(cliente.clone()).unwrap();
+    let data = file_manager::read_object(&commit, "cliente/test".to_string(), true).unwrap();
+    assert!(data.ends_with("\nmensaje con espacios\n"));
+    // las comillas que quedan despues de parsear son parte del mensaje
+    file_manager::write_file("cliente/test/base".to_string(), "otra\n".to_string()).unwrap();
+    correr("add base").unwrap();
+    correr("commit -m 'dijo \"hola\"'").unwrap();
+    let commit = get_current_commit(cliente.clone()).unwrap();
+    let data = file_manager::read_object(&commit, "cliente/test".to_string(), true).unwrap();
+    assert!(data.ends_with("\ndijo \"hola\"\n"));
+    assert!(correr("ls-files")
+        .map(|_| get_ls_files_cached(cliente.clone()).unwrap())
+        .unwrap()
+        .contains(&"test/con espacios.txt".to_string()));
+
+    match correr("branch -x nueva") {
+        Err(GitrError::InvalidArgumentError(recibi, uso)) => {
+            assert_eq!(recibi, "-x nueva");
+            assert!(uso.ends_with(
+                "Valid options: --list | -l, -d | --delete, -m | --move, --format <value>"
+            ));
+        }
+        otro => panic!("se esperaba un error de uso: {:?}", otro),
+    }
+    assert!(correr("commit -m").is_err());
+    assert!(correr("tag -a v1").is_err());
+    assert!(correr("rebase --continue master").is_err());
+    assert!(correr("commit -m 'sin cerrar").is_err());
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_salida_para_scripts() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    let correr = |input: &str| {
+        let argv = parse_input(input.to_string())?;
+        command_handler(argv, false, "".to_string(), cliente.clone())
+    };
+    commit_file(cliente.clone(), "cliente/test/a", "a\n", "primero");
+    commit_file(cliente.clone(), "cliente/test/b", "b\n", "segundo");
+    let head = get_current_commit(cliente.clone()).unwrap();
+    file_manager::write_file("cliente/test/nuevo".to_string(), "n\n".to_string()).unwrap();
+    correr("add nuevo").unwrap();
+    file_manager::write_file("cliente/test/a".to_string(), "a2\n".to_string()).unwrap();
+    file_manager::write_file("cliente/test/suelto".to_string(), "s\n".to_string()).unwrap();
+    fs::remove_file("cliente/test/b").unwrap();
+
+    let v1 = get_status_porcelain("v1", false, false, cliente.clone()).unwrap();
+    assert_eq!(v1, " M a\n D b\nA  nuevo\n?? gitrignore\n?? suelto\n");
+    let z = get_status_porcelain("v1", true, false, cliente.clone()).unwrap();
+    assert_eq!(z, " M a\0 D b\0A  nuevo\0?? gitrignore\0?? suelto\0");
+    let v1_branch = get_status_porcelain("v1", false, true, cliente.clone()).unwrap();
+    assert_eq!(v1_branch, format!("## master\n{}", v1));
+    let v2 = get_status_porcelain("v2", false, false, cliente.clone()).unwrap();
+    let lineas = v2.lines().collect::<Vec<&str>>();
+    assert!(lineas[0].starts_with("1 .M N... 100644 100644 100644 "));
+    assert!(lineas[1].starts_with("1 .D N... 100644 100644 000000 "));
+    assert!(lineas[2].starts_with(&format!(
+        "1 A. N... 000000 100644 100644 {} ",
+        "0".repeat(40)
+    )));
+    assert!(lineas[2].ends_with(" nuevo"));
+    assert_eq!(lineas[4], "? suelto");
+    // los headers de la branch solo van con --branch
+    let v2_branch = get_status_porcelain("v2", false, true, cliente.clone()).unwrap();
+    assert_eq!(
+        v2_branch,
+        format!("# branch.oid {}\n# branch.head master\n{}", head, v2)
+    );
+    assert!(correr("status --porcelain=v3").is_err());
+
+    assert_eq!(
+        get_ls_files_deleted_modified(true, cliente.clone()).unwrap(),
+        vec!["test/b"]
+    );
+    assert_eq!(
+        get_ls_files_deleted_modified(false, cliente.clone()).unwrap(),
+        vec!["test/a", "test/b"]
+    );
+
+    correr("branch otra").unwrap();
+    let branches = format_branches(
+        "%(HEAD) %(refname:short) %(objectname) %(subject)",
+        cliente.clone(),
+    );
+    assert_eq!(
+        branches.unwrap(),
+        format!("* master {} segundo\n  otra {} segundo\n", head, head)
+    );
+    assert!(correr("branch --format=%(refname) nueva").is_err());
+
+    let log = command_utils::get_log(flags(&["--json"]), cliente.clone()).unwrap();
+    let entradas: serde_json::Value = serde_json::from_str(&log).unwrap();
+    let entradas = entradas.as_array().unwrap();
+    assert_eq!(entradas.len(), 2);
+    assert_eq!(entradas[0]["hash"], head.as_str());
+    assert_eq!(entradas[0]["subject"], "segundo");
+    assert_eq!(entradas[0]["parents"][0], entradas[1]["hash"]);
+    assert_eq!(entradas[1]["parents"].as_array().unwrap().len(), 0);
+    assert!(command_utils::get_log(flags(&["--json", "--graph"]), cliente.clone()).is_err());
+
+    let error = correr("status --otra").unwrap_err();
+    assert_eq!(error.exit_code(), 129);
+    assert_ne!(
+        GitrError::NoHead.exit_code(),
+        GitrError::NoRepository.exit_code()
+    );
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_diff_con_y_sin_color_utf8_y_sin_cambios_de_modo_del_working_tree() {
+    use std::os::unix::fs::PermissionsExt;
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(
+        cliente.clone(),
+        "cliente/test/script.sh",
+        "# señor\necho a\n",
+        "base",
+    );
+    file_manager::write_file(
+        "cliente/test/script.sh".to_string(),
+        "# señor\necho b\n".to_string(),
+    )
+    .unwrap();
+
+    let res = command_utils::get_diff(flags(&["--no-color"]), cliente.clone()).unwrap();
+    assert!(!res.contains('\x1b'));
+    assert!(res.contains("@@ -1,2 +1,2 @@\n # señor\n-echo a\n+echo b\n"));
+    let res = command_utils::get_diff(flags(&["--color"]), cliente.clone()).unwrap();
+    assert!(res.contains("\x1b[31m-echo a\x1b[0m"));
+
+    // el index no guarda el bit de ejecucion, asi que no hay cambio de modo
+    fs::set_permissions("cliente/test/script.sh", fs::Permissions::from_mode(0o755)).unwrap();
+    let res = command_utils::get_diff(flags(&["--no-color"]), cliente.clone()).unwrap();
+    assert!(!res.contains("mode"));
+    assert!(res.lines().nth(1).unwrap().ends_with(" 100644"));
+    commands_fn::add(flags(&["script.sh"]), cliente.clone()).unwrap();
+    assert_eq!(
+        command_utils::get_diff(flags(&["--no-color"]), cliente.clone()).unwrap(),
+        ""
+    );
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_diff_distingue_rangos_de_paths_con_puntos() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(cliente.clone(), "cliente/test/a..b.txt", "uno\n", "base");
+    file_manager::write_file("cliente/test/a..b.txt".to_string(), "dos\n".to_string()).unwrap();
+    let diff = |args: &[&str]| command_utils::get_diff(flags(args), cliente.clone());
+
+    assert!(diff(&["a..b.txt"]).unwrap().contains("-uno\n+dos\n"));
+    assert!(diff(&["--", "a..b.txt"]).unwrap().contains("-uno\n+dos\n"));
+    assert_eq!(diff(&["--", "../a..b.txt"]).unwrap(), "");
+    commit_file(cliente.clone(), "cliente/test/otro", "x\n", "segundo");
+    assert_eq!(
+        diff(&["--name-only", "HEAD~1..HEAD"]).unwrap(),
+        "a..b.txt\notro\n"
+    );
+    delete_repo("cliente/test".to_string());
+}
+
+#[test]
+#[serial]
+fn test_umbral_de_renames_desde_la_config_y_archivos_vacios() {
+    refresh_files();
+    let cliente = "cliente".to_string();
+    commit_file(cliente.clone(), "cliente/test/vacio1", "", "vacio");
+    commit_file(
+        cliente.clone(),
+        "cliente/test/viejo.txt",
+        "uno\ndos\ntres\ncuatro\n",
+        "base",
+    );
+    file_manager::remove_file("cliente/test/viejo.txt".to_string()).unwrap();
+    file_manager::remove_file("cliente/test/vacio1".to_string()).unwrap();
+    file_manager::write_file(
+        "cliente/test/nuevo.txt".to_string(),
+        "uno\ndos\ntres\ncinco\n".to_string(),
+    )
+    .unwrap();
+    file_manager::write_file("cliente/test/vacio2".to_string(), "".to_string()).unwrap();
+    commands_fn::add(flags(&["."]), cliente.clone()).unwrap();
+    let renames = || {
+        let (not_staged, _, _) = get_untracked_notstaged_files(cliente.clone()).unwrap();
+        let (mut new_files, _) = get_tobe_commited_files(&not_staged, cliente.clone()).unwrap();
+        get_status_renamed_files(&mut new_files, cliente.clone()).unwrap()
+    };
+    let name_status =
+        || command_utils::get_diff(flags(&["--cached", "--name-status"]), cliente.clone()).unwrap();
+
+    // los vacios no se emparejan aunque sean iguales
+    assert_eq!(
+        renames(),
+        vec![(
+            "cliente/test/viejo.txt".to_string(),
+            "cliente/test/nuevo.txt".to_string(),
+            75
+        )]
+    );
+    assert_eq!(
+        name_status(),
+        "R075\tviejo.txt\tnuevo.txt\nD\tvacio1\nA\tvacio2\n"
+    );
+
+    commands_fn::config(flags(&["diff.renameThreshold", "90%"]), cliente.clone()).unwrap();
+    assert!(renames().is_empty());
+    assert_eq!(
+        name_status(),
+        "A\tnuevo.txt\nD\tvacio1\nA\tvacio2\nD\tviejo.txt\n"
+    );
+    let con_m70 = flags(&["--cached", "--name-status", "-M70"]);
+    assert!(command_utils::get_diff(con_m70, cliente.clone())
+        .unwrap()
+        .contains("R075\tviejo.txt\tnuevo.txt"));
+
+    commands_fn::config(flags(&["diff.renameThreshold", "mucho"]), cliente.clone()).unwrap();
+    assert!(matches!(
+        command_utils::get_diff(flags(&["--cached"]), cliente.clone()),
+        Err(GitrError::ConfigError(_))
+    ));
+    delete_repo("cliente/test".to_string());
+}
+
+/*********************
+    UN SOLO COMANDO TESTS
+*********************/
+
+#[test]
+#[serial]
+fn test_un_solo_comando_fuera_del_repo_y_sin_identidad() {
+    // un directorio sin src/log.json, como cuando se usa el cliente desde un script
+    let dir = std::env::temp_dir().join(format!("gitr_un_comando_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let correr = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_client"))
+            .args(args)
+            .current_dir(&dir)
+            .env("LANG", "en_US.UTF-8")
+            .env_remove("LC_ALL")
+            .env_remove("LC_MESSAGES")
+            .output()
+            .unwrap()
+    };
+
+    let salida = correr(&["yo", "init", "repo"]);
+    assert_eq!(salida.status.code(), Some(0));
+    assert!(dir.join("yo/repo/gitr").is_dir());
+    fs::write(dir.join("yo/repo/a"), "a\n").unwrap();
+    assert_eq!(correr(&["yo", "--", "add", "a"]).status.code(), Some(0));
+
+    // sin gitrconfig commit y tag -a fallan sin escribir nada
+    let codigo = GitrError::ConfigError(String::new()).exit_code();
+    let salida = correr(&["yo", "commit", "-m", "primero"]);
+    assert_eq!(salida.status.code(), Some(codigo));
+    assert!(String::from_utf8_lossy(&salida.stderr).contains("user.email"));
+    assert!(!dir.join("yo/repo/gitr/refs/heads/master").exists());
+    assert_eq!(correr(&["yo", "status"]).status.code(), Some(0));
+
+    fs::write(
+        dir.join("yo/gitrconfig"),
+        "[user]\n\tname = yo\n\temail = yo@mail.com\n",
+    )
+    .unwrap();
+    assert_eq!(
+        correr(&["yo", "commit", "-m", "primero"]).status.code(),
+        Some(0)
+    );
+    fs::remove_file(dir.join("yo/gitrconfig")).unwrap();
+    let salida = correr(&["yo", "tag", "-a", "v1", "-m", "version"]);
+    assert_eq!(salida.status.code(), Some(codigo));
+    assert!(!dir.join("yo/repo/gitr/refs/tags/v1").exists());
+
+    // si no se puede crear el directorio del cliente se informa el error
+    let salida = correr(&["no/existe/yo", "status"]);
+    assert_ne!(salida.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&salida.stderr).contains("no/existe/yo"));
+    assert!(!dir.join("no").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}